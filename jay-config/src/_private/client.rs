@@ -4,12 +4,16 @@ use {
     crate::{
         _private::{
             bincode_ops,
-            ipc::{ClientMessage, InitMessage, Response, ServerMessage},
+            ipc::{ClientMessage, InitMessage, Placement, Response, ServerMessage},
             logging, Config, ConfigEntry, ConfigEntryGen, VERSION,
         },
-        exec::Command,
-        input::{acceleration::AccelProfile, capability::Capability, InputDevice, Seat},
-        keyboard::Keymap,
+        exec::{Command, ExitStatus, Process},
+        idle::IdleStage,
+        input::{
+            acceleration::AccelProfile, button::Button, capability::Capability, InputDevice,
+            ScrollDirection, Seat,
+        },
+        keyboard::{mods::Modifiers, BindingMode, KeyBinding, Keymap},
         logging::LogLevel,
         theme::{colors::Colorable, sized::Resizable, Color},
         timer::Timer,
@@ -17,7 +21,7 @@ use {
             connector_type::{ConnectorType, CON_UNKNOWN},
             Connector, DrmDevice, Mode,
         },
-        Axis, Direction, ModifiedKeySym, PciId, Workspace,
+        Axis, ContainerLayout, Direction, ModifiedKeySym, PciId, Workspace,
     },
     std::{
         cell::{Cell, RefCell},
@@ -35,8 +39,16 @@ pub(crate) struct Client {
     srv_data: *const u8,
     srv_unref: unsafe extern "C" fn(data: *const u8),
     srv_handler: unsafe extern "C" fn(data: *const u8, msg: *const u8, size: usize),
-    key_handlers: RefCell<HashMap<(Seat, ModifiedKeySym), Rc<dyn Fn()>>>,
+    key_handlers: RefCell<HashMap<(Seat, KeyBinding), Rc<dyn Fn()>>>,
+    binding_modes: RefCell<HashMap<String, BindingMode>>,
+    button_handlers: RefCell<HashMap<(Seat, Modifiers, Button), Rc<dyn Fn()>>>,
+    scroll_handlers: RefCell<HashMap<(Seat, Modifiers, Axis, ScrollDirection), Rc<dyn Fn()>>>,
     timer_handlers: RefCell<HashMap<Timer, Rc<dyn Fn()>>>,
+    idle_stage_entered_handlers: RefCell<HashMap<IdleStage, Rc<dyn Fn()>>>,
+    idle_stage_resumed_handlers: RefCell<HashMap<IdleStage, Rc<dyn Fn()>>>,
+    keyboard_group_handlers: RefCell<HashMap<Seat, Rc<dyn Fn(u32)>>>,
+    process_exit_handlers: RefCell<HashMap<Process, Box<dyn FnOnce(ExitStatus)>>>,
+    process_exit_statuses: RefCell<HashMap<Process, ExitStatus>>,
     response: RefCell<Vec<Response>>,
     on_new_seat: RefCell<Option<Rc<dyn Fn(Seat)>>>,
     on_new_input_device: RefCell<Option<Rc<dyn Fn(InputDevice)>>>,
@@ -47,6 +59,7 @@ pub(crate) struct Client {
     on_new_drm_device: RefCell<Option<Rc<dyn Fn(DrmDevice)>>>,
     on_del_drm_device: RefCell<Option<Rc<dyn Fn(DrmDevice)>>>,
     on_idle: RefCell<Option<Rc<dyn Fn()>>>,
+    on_locker_died: RefCell<Option<Rc<dyn Fn()>>>,
     bufs: RefCell<Vec<Vec<u8>>>,
     reload: Cell<bool>,
 }
@@ -124,7 +137,15 @@ pub unsafe extern "C" fn init(
         srv_unref,
         srv_handler,
         key_handlers: Default::default(),
+        binding_modes: Default::default(),
+        button_handlers: Default::default(),
+        scroll_handlers: Default::default(),
         timer_handlers: Default::default(),
+        idle_stage_entered_handlers: Default::default(),
+        idle_stage_resumed_handlers: Default::default(),
+        keyboard_group_handlers: Default::default(),
+        process_exit_handlers: Default::default(),
+        process_exit_statuses: Default::default(),
         response: Default::default(),
         on_new_seat: Default::default(),
         on_new_input_device: Default::default(),
@@ -135,6 +156,7 @@ pub unsafe extern "C" fn init(
         on_new_drm_device: Default::default(),
         on_del_drm_device: Default::default(),
         on_idle: Default::default(),
+        on_locker_died: Default::default(),
         bufs: Default::default(),
         reload: Cell::new(false),
     });
@@ -190,17 +212,59 @@ impl Client {
         self.reload.get()
     }
 
-    pub fn spawn(&self, command: &Command) {
+    pub fn spawn(&self, command: &Command) -> Process {
         let env = command
             .env
             .iter()
             .map(|(a, b)| (a.to_string(), b.to_string()))
             .collect();
-        self.send(&ClientMessage::Run {
+        let res = self.send_with_response(&ClientMessage::Run2 {
             prog: &command.prog,
             args: command.args.clone(),
             env,
+            stdout: command.stdout,
+            stderr: command.stderr,
+            placement: Placement {
+                workspace: command.workspace,
+                output: command.output,
+                floating: command.floating,
+                sticky: command.sticky,
+            },
         });
+        get_response!(res, Process(0), Run { process });
+        process
+    }
+
+    fn get_process(&self, process: Process) -> (Option<i32>, bool) {
+        let res = self.send_with_response(&ClientMessage::GetProcess { process });
+        get_response!(res, (None, false), GetProcess { pid, alive });
+        (pid, alive)
+    }
+
+    pub fn process_pid(&self, process: Process) -> Option<i32> {
+        self.get_process(process).0
+    }
+
+    pub fn process_is_alive(&self, process: Process) -> bool {
+        self.get_process(process).1
+    }
+
+    pub fn process_exit_status(&self, process: Process) -> Option<ExitStatus> {
+        self.process_exit_statuses.borrow_mut().remove(&process)
+    }
+
+    pub fn process_signal(&self, process: Process, signal: i32) {
+        self.send(&ClientMessage::ProcessSignal { process, signal })
+    }
+
+    pub fn on_process_exit<F: FnOnce(ExitStatus) + 'static>(&self, process: Process, f: F) {
+        if let Some(status) = self.process_exit_status(process) {
+            f(status);
+            return;
+        }
+        self.process_exit_handlers
+            .borrow_mut()
+            .insert(process, Box::new(f));
     }
 
     pub fn grab(&self, kb: InputDevice, grab: bool) {
@@ -215,20 +279,127 @@ impl Client {
         self.send(&ClientMessage::Move { seat, direction });
     }
 
+    pub fn resize(&self, seat: Seat, direction: Direction, amount: i32) {
+        self.send(&ClientMessage::Resize {
+            seat,
+            direction,
+            amount,
+        });
+    }
+
+    pub fn equalize(&self, seat: Seat) {
+        self.send(&ClientMessage::Equalize { seat });
+    }
+
     pub fn unbind<T: Into<ModifiedKeySym>>(&self, seat: Seat, mod_sym: T) {
-        let mod_sym = mod_sym.into();
+        self.unbind_key(seat, KeyBinding::from(mod_sym.into()))
+    }
+
+    pub fn unbind_key(&self, seat: Seat, binding: KeyBinding) {
         let deregister = self
             .key_handlers
             .borrow_mut()
-            .remove(&(seat, mod_sym))
+            .remove(&(seat, binding.clone()))
+            .is_some();
+        if deregister {
+            self.send(&ClientMessage::RemoveKeyBinding { seat, binding })
+        }
+    }
+
+    pub fn bind_button<F: Fn() + 'static>(
+        &self,
+        seat: Seat,
+        mods: Modifiers,
+        button: Button,
+        f: F,
+    ) {
+        let register = self
+            .button_handlers
+            .borrow_mut()
+            .insert((seat, mods, button), Rc::new(f))
+            .is_none();
+        if register {
+            self.send(&ClientMessage::AddButtonBinding { seat, mods, button });
+        }
+    }
+
+    pub fn unbind_button(&self, seat: Seat, mods: Modifiers, button: Button) {
+        let deregister = self
+            .button_handlers
+            .borrow_mut()
+            .remove(&(seat, mods, button))
+            .is_some();
+        if deregister {
+            self.send(&ClientMessage::RemoveButtonBinding { seat, mods, button });
+        }
+    }
+
+    pub fn bind_scroll<F: Fn() + 'static>(
+        &self,
+        seat: Seat,
+        mods: Modifiers,
+        axis: Axis,
+        direction: ScrollDirection,
+        f: F,
+    ) {
+        let register = self
+            .scroll_handlers
+            .borrow_mut()
+            .insert((seat, mods, axis, direction), Rc::new(f))
+            .is_none();
+        if register {
+            self.send(&ClientMessage::AddScrollBinding {
+                seat,
+                mods,
+                axis,
+                direction,
+            });
+        }
+    }
+
+    pub fn unbind_scroll(
+        &self,
+        seat: Seat,
+        mods: Modifiers,
+        axis: Axis,
+        direction: ScrollDirection,
+    ) {
+        let deregister = self
+            .scroll_handlers
+            .borrow_mut()
+            .remove(&(seat, mods, axis, direction))
             .is_some();
         if deregister {
-            self.send(&ClientMessage::RemoveShortcut {
+            self.send(&ClientMessage::RemoveScrollBinding {
                 seat,
-                mods: mod_sym.mods,
-                sym: mod_sym.sym,
-            })
+                mods,
+                axis,
+                direction,
+            });
+        }
+    }
+
+    pub fn get_binding_mode(&self, name: &str) -> BindingMode {
+        if name == "default" {
+            return BindingMode::DEFAULT;
         }
+        let mut modes = self.binding_modes.borrow_mut();
+        let next = BindingMode(modes.len() as u64 + 1);
+        *modes.entry(name.to_string()).or_insert(next)
+    }
+
+    pub fn seat_set_binding_mode(&self, seat: Seat, mode: BindingMode) {
+        self.send(&ClientMessage::SeatSetBindingMode { seat, mode })
+    }
+
+    pub fn seat_set_window_drag_modifier(&self, seat: Seat, mods: Modifiers) {
+        self.send(&ClientMessage::SeatSetWindowDragModifier { seat, mods })
+    }
+
+    pub fn seat_get_binding_mode(&self, seat: Seat) -> BindingMode {
+        let res = self.send_with_response(&ClientMessage::SeatGetBindingMode { seat });
+        get_response!(res, BindingMode::DEFAULT, GetBindingMode { mode });
+        mode
     }
 
     fn with_response<F: FnOnce()>(&self, f: F) -> Response {
@@ -242,6 +413,32 @@ impl Client {
         seats
     }
 
+    pub fn hide_titles(&self, seat: Seat) -> bool {
+        let res = self.send_with_response(&ClientMessage::SeatGetHideTitles { seat });
+        get_response!(res, false, SeatGetHideTitles { hide });
+        hide
+    }
+
+    pub fn seat_set_hide_titles(&self, seat: Seat, hide: bool) {
+        self.send(&ClientMessage::SeatSetHideTitles { seat, hide });
+    }
+
+    pub fn minimize(&self, seat: Seat) {
+        self.send(&ClientMessage::SeatMinimize { seat });
+    }
+
+    pub fn restore_last_minimized(&self, seat: Seat) {
+        self.send(&ClientMessage::SeatRestoreLastMinimized { seat });
+    }
+
+    pub fn move_to_scratchpad(&self, seat: Seat) {
+        self.send(&ClientMessage::SeatMoveToScratchpad { seat });
+    }
+
+    pub fn toggle_scratchpad(&self, seat: Seat, filter: Option<&str>) {
+        self.send(&ClientMessage::SeatToggleScratchpad { seat, filter });
+    }
+
     pub fn mono(&self, seat: Seat) -> bool {
         let res = self.send_with_response(&ClientMessage::GetMono { seat });
         get_response!(res, false, GetMono { mono });
@@ -275,6 +472,38 @@ impl Client {
         self.timer_handlers.borrow_mut().insert(timer, Rc::new(f));
     }
 
+    pub fn get_idle_stage(&self, name: &str) -> IdleStage {
+        let res = self.send_with_response(&ClientMessage::GetIdleStage { name });
+        get_response!(res, IdleStage(0), GetIdleStage { stage });
+        stage
+    }
+
+    pub fn remove_idle_stage(&self, stage: IdleStage) {
+        self.idle_stage_entered_handlers.borrow_mut().remove(&stage);
+        self.idle_stage_resumed_handlers.borrow_mut().remove(&stage);
+        self.send(&ClientMessage::RemoveIdleStage { stage });
+    }
+
+    pub fn set_idle_stage_timeout(&self, stage: IdleStage, timeout: Duration) {
+        self.send(&ClientMessage::SetIdleStageTimeout { stage, timeout });
+    }
+
+    pub fn set_idle_stage_disable_outputs(&self, stage: IdleStage, disable: bool) {
+        self.send(&ClientMessage::SetIdleStageDisableOutputs { stage, disable });
+    }
+
+    pub fn on_idle_stage_entered<F: Fn() + 'static>(&self, stage: IdleStage, f: F) {
+        self.idle_stage_entered_handlers
+            .borrow_mut()
+            .insert(stage, Rc::new(f));
+    }
+
+    pub fn on_idle_stage_resumed<F: Fn() + 'static>(&self, stage: IdleStage, f: F) {
+        self.idle_stage_resumed_handlers
+            .borrow_mut()
+            .insert(stage, Rc::new(f));
+    }
+
     pub fn get_workspace(&self, name: &str) -> Workspace {
         let res = self.send_with_response(&ClientMessage::GetWorkspace { name });
         get_response!(res, Workspace(0), GetWorkspace { workspace });
@@ -327,6 +556,20 @@ impl Client {
         axis
     }
 
+    pub fn layout(&self, seat: Seat) -> ContainerLayout {
+        let res = self.send_with_response(&ClientMessage::SeatGetLayout { seat });
+        get_response!(res, ContainerLayout::Split, SeatGetLayout { layout });
+        layout
+    }
+
+    pub fn set_layout(&self, seat: Seat, layout: ContainerLayout) {
+        self.send(&ClientMessage::SeatSetLayout { seat, layout });
+    }
+
+    pub fn set_workspace_layout(&self, workspace: Workspace, layout: ContainerLayout) {
+        self.send(&ClientMessage::SetWorkspaceLayout { workspace, layout });
+    }
+
     pub fn disable_pointer_constraint(&self, seat: Seat) {
         self.send(&ClientMessage::DisablePointerConstraint { seat });
     }
@@ -355,6 +598,10 @@ impl Client {
         font
     }
 
+    pub fn set_cursor_theme(&self, theme: Option<&str>) {
+        self.send(&ClientMessage::SetCursorTheme { theme });
+    }
+
     pub fn get_floating(&self, seat: Seat) -> bool {
         let res = self.send_with_response(&ClientMessage::GetFloating { seat });
         get_response!(res, false, GetFloating { floating });
@@ -369,6 +616,10 @@ impl Client {
         self.set_floating(seat, !self.get_floating(seat));
     }
 
+    pub fn toggle_sticky(&self, seat: Seat) {
+        self.send(&ClientMessage::SeatToggleSticky { seat });
+    }
+
     pub fn reset_colors(&self) {
         self.send(&ClientMessage::ResetColors);
     }
@@ -377,6 +628,14 @@ impl Client {
         self.send(&ClientMessage::ResetSizes);
     }
 
+    pub fn set_smart_gaps(&self, enabled: bool) {
+        self.send(&ClientMessage::SetSmartGaps { enabled });
+    }
+
+    pub fn set_hide_titles(&self, hide: bool) {
+        self.send(&ClientMessage::SetHideTitles { hide });
+    }
+
     pub fn get_color(&self, colorable: Colorable) -> Color {
         let res = self.send_with_response(&ClientMessage::GetColor { colorable });
         get_response!(res, Color::BLACK, GetColor { color });
@@ -472,6 +731,25 @@ impl Client {
         self.send(&ClientMessage::ConnectorSetEnabled { connector, enabled });
     }
 
+    pub fn create_virtual_output(
+        &self,
+        width: i32,
+        height: i32,
+        refresh_millihz: u32,
+    ) -> Connector {
+        let res = self.send_with_response(&ClientMessage::CreateVirtualOutput {
+            width,
+            height,
+            refresh_millihz,
+        });
+        get_response!(res, Connector(0), CreateVirtualOutput { connector });
+        connector
+    }
+
+    pub fn remove_virtual_output(&self, connector: Connector) {
+        self.send(&ClientMessage::RemoveVirtualOutput { connector });
+    }
+
     pub fn device_connectors(&self, device: DrmDevice) -> Vec<Connector> {
         let res = self.send_with_response(&ClientMessage::GetDeviceConnectors { device });
         get_response!(res, vec![], GetDeviceConnectors { connectors });
@@ -574,6 +852,14 @@ impl Client {
         *self.on_idle.borrow_mut() = Some(Rc::new(f));
     }
 
+    pub fn on_locker_died<F: Fn() + 'static>(&self, f: F) {
+        *self.on_locker_died.borrow_mut() = Some(Rc::new(f));
+    }
+
+    pub fn set_lock_fallback_text(&self, text: &str) {
+        self.send(&ClientMessage::SetLockFallbackText { text });
+    }
+
     pub fn on_connector_connected<F: Fn(Connector) + 'static>(&self, f: F) {
         *self.on_connector_connected.borrow_mut() = Some(Rc::new(f));
     }
@@ -609,6 +895,10 @@ impl Client {
         self.send(&ClientMessage::SetTransformMatrix { device, matrix })
     }
 
+    pub fn device_set_keymap(&self, device: InputDevice, keymap: Keymap) {
+        self.send(&ClientMessage::DeviceSetKeymap { device, keymap })
+    }
+
     pub fn set_px_per_wheel_scroll(&self, device: InputDevice, px: f64) {
         self.send(&ClientMessage::SetPxPerWheelScroll { device, px })
     }
@@ -645,6 +935,40 @@ impl Client {
         self.send(&ClientMessage::SeatSetRepeatRate { seat, rate, delay })
     }
 
+    pub fn seat_get_keyboard_group(&self, seat: Seat) -> (u32, u32) {
+        let res = self.send_with_response(&ClientMessage::SeatGetKeyboardGroup { seat });
+        get_response!(res, (0, 1), GetKeyboardGroup { group, num_groups });
+        (group, num_groups)
+    }
+
+    pub fn seat_set_keyboard_group(&self, seat: Seat, group: u32) {
+        self.send(&ClientMessage::SeatSetKeyboardGroup { seat, group })
+    }
+
+    pub fn seat_next_keyboard_group(&self, seat: Seat) {
+        self.send(&ClientMessage::SeatNextKeyboardGroup { seat })
+    }
+
+    pub fn seat_prev_keyboard_group(&self, seat: Seat) {
+        self.send(&ClientMessage::SeatPrevKeyboardGroup { seat })
+    }
+
+    pub fn seat_get_keyboard_group_name(&self, seat: Seat, group: u32) -> String {
+        let res = self.send_with_response(&ClientMessage::SeatGetKeyboardGroupName { seat, group });
+        get_response!(res, String::new(), GetKeyboardGroupName { name });
+        name
+    }
+
+    pub fn seat_set_remember_keyboard_group(&self, seat: Seat, remember: bool) {
+        self.send(&ClientMessage::SeatSetRememberKeyboardGroup { seat, remember })
+    }
+
+    pub fn seat_on_keyboard_group_changed<F: Fn(u32) + 'static>(&self, seat: Seat, f: F) {
+        self.keyboard_group_handlers
+            .borrow_mut()
+            .insert(seat, Rc::new(f));
+    }
+
     pub fn seat_get_repeat_rate(&self, seat: Seat) -> (i32, i32) {
         let res = self.send_with_response(&ClientMessage::SeatGetRepeatRate { seat });
         get_response!(res, (25, 250), GetRepeatRate { rate, delay });
@@ -658,11 +982,14 @@ impl Client {
     }
 
     pub fn bind<T: Into<ModifiedKeySym>, F: Fn() + 'static>(&self, seat: Seat, mod_sym: T, f: F) {
-        let mod_sym = mod_sym.into();
+        self.bind_key(seat, KeyBinding::from(mod_sym.into()), f)
+    }
+
+    pub fn bind_key<F: Fn() + 'static>(&self, seat: Seat, binding: KeyBinding, f: F) {
         let register = {
             let mut kh = self.key_handlers.borrow_mut();
             let f = Rc::new(f);
-            match kh.entry((seat, mod_sym)) {
+            match kh.entry((seat, binding.clone())) {
                 Entry::Occupied(mut o) => {
                     *o.get_mut() = f;
                     false
@@ -674,11 +1001,7 @@ impl Client {
             }
         };
         if register {
-            self.send(&ClientMessage::AddShortcut {
-                seat,
-                mods: mod_sym.mods,
-                sym: mod_sym.sym,
-            });
+            self.send(&ClientMessage::AddKeyBinding { seat, binding });
         }
     }
 
@@ -691,6 +1014,17 @@ impl Client {
         })
     }
 
+    fn invoke_key_binding(&self, seat: Seat, binding: KeyBinding) {
+        let handler = self
+            .key_handlers
+            .borrow_mut()
+            .get(&(seat, binding))
+            .cloned();
+        if let Some(handler) = handler {
+            handler();
+        }
+    }
+
     fn handle_msg(&self, msg: &[u8]) {
         let res = bincode::borrow_decode_from_slice::<ServerMessage, _>(msg, bincode_ops());
         let (msg, _) = match res {
@@ -711,11 +1045,10 @@ impl Client {
                 self.response.borrow_mut().push(response);
             }
             ServerMessage::InvokeShortcut { seat, mods, sym } => {
-                let ms = ModifiedKeySym { mods, sym };
-                let handler = self.key_handlers.borrow_mut().get(&(seat, ms)).cloned();
-                if let Some(handler) = handler {
-                    handler();
-                }
+                self.invoke_key_binding(seat, ModifiedKeySym { mods, sym }.into());
+            }
+            ServerMessage::InvokeKeyBinding { seat, binding } => {
+                self.invoke_key_binding(seat, binding);
             }
             ServerMessage::NewInputDevice { device } => {
                 let handler = self.on_new_input_device.borrow_mut().clone();
@@ -775,6 +1108,78 @@ impl Client {
                     handler();
                 }
             }
+            ServerMessage::IdleStageEntered { stage } => {
+                let handler = self
+                    .idle_stage_entered_handlers
+                    .borrow_mut()
+                    .get(&stage)
+                    .cloned();
+                if let Some(handler) = handler {
+                    handler();
+                }
+            }
+            ServerMessage::IdleStageResumed { stage } => {
+                let handler = self
+                    .idle_stage_resumed_handlers
+                    .borrow_mut()
+                    .get(&stage)
+                    .cloned();
+                if let Some(handler) = handler {
+                    handler();
+                }
+            }
+            ServerMessage::KeyboardGroupChanged { seat, group } => {
+                let handler = self
+                    .keyboard_group_handlers
+                    .borrow_mut()
+                    .get(&seat)
+                    .cloned();
+                if let Some(handler) = handler {
+                    handler(group);
+                }
+            }
+            ServerMessage::InvokeButtonBinding { seat, mods, button } => {
+                let handler = self
+                    .button_handlers
+                    .borrow_mut()
+                    .get(&(seat, mods, button))
+                    .cloned();
+                if let Some(handler) = handler {
+                    handler();
+                }
+            }
+            ServerMessage::InvokeScrollBinding {
+                seat,
+                mods,
+                axis,
+                direction,
+            } => {
+                let handler = self
+                    .scroll_handlers
+                    .borrow_mut()
+                    .get(&(seat, mods, axis, direction))
+                    .cloned();
+                if let Some(handler) = handler {
+                    handler();
+                }
+            }
+            ServerMessage::LockerDied => {
+                let handler = self.on_locker_died.borrow_mut().clone();
+                if let Some(handler) = handler {
+                    handler();
+                }
+            }
+            ServerMessage::ProcessExited { process, status } => {
+                let handler = self.process_exit_handlers.borrow_mut().remove(&process);
+                match handler {
+                    Some(handler) => handler(status),
+                    _ => {
+                        self.process_exit_statuses
+                            .borrow_mut()
+                            .insert(process, status);
+                    }
+                }
+            }
         }
     }
 
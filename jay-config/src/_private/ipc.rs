@@ -1,12 +1,17 @@
 use {
     crate::{
-        input::{acceleration::AccelProfile, capability::Capability, InputDevice, Seat},
-        keyboard::{mods::Modifiers, syms::KeySym, Keymap},
+        exec::{ExitStatus, Process, Stdio},
+        idle::IdleStage,
+        input::{
+            acceleration::AccelProfile, button::Button, capability::Capability, InputDevice,
+            ScrollDirection, Seat,
+        },
+        keyboard::{mods::Modifiers, syms::KeySym, BindingMode, KeyBinding, Keymap},
         logging::LogLevel,
         theme::{colors::Colorable, sized::Resizable, Color},
         timer::Timer,
         video::{connector_type::ConnectorType, Connector, DrmDevice},
-        Axis, Direction, PciId, Workspace,
+        Axis, ContainerLayout, Direction, PciId, Workspace,
     },
     bincode::{BorrowDecode, Decode, Encode},
     std::time::Duration,
@@ -56,6 +61,36 @@ pub enum ServerMessage {
     },
     Idle,
     DevicesEnumerated,
+    LockerDied,
+    IdleStageEntered {
+        stage: IdleStage,
+    },
+    IdleStageResumed {
+        stage: IdleStage,
+    },
+    KeyboardGroupChanged {
+        seat: Seat,
+        group: u32,
+    },
+    InvokeButtonBinding {
+        seat: Seat,
+        mods: Modifiers,
+        button: Button,
+    },
+    InvokeScrollBinding {
+        seat: Seat,
+        mods: Modifiers,
+        axis: Axis,
+        direction: ScrollDirection,
+    },
+    ProcessExited {
+        process: Process,
+        status: ExitStatus,
+    },
+    InvokeKeyBinding {
+        seat: Seat,
+        binding: KeyBinding,
+    },
 }
 
 #[derive(Encode, BorrowDecode, Debug)]
@@ -330,6 +365,171 @@ pub enum ClientMessage<'a> {
     GetWorkspaceCapture {
         workspace: Workspace,
     },
+    SetLockFallbackText {
+        text: &'a str,
+    },
+    GetIdleStage {
+        name: &'a str,
+    },
+    RemoveIdleStage {
+        stage: IdleStage,
+    },
+    SetIdleStageTimeout {
+        stage: IdleStage,
+        timeout: Duration,
+    },
+    SetIdleStageDisableOutputs {
+        stage: IdleStage,
+        disable: bool,
+    },
+    SeatGetKeyboardGroup {
+        seat: Seat,
+    },
+    SeatSetKeyboardGroup {
+        seat: Seat,
+        group: u32,
+    },
+    SeatNextKeyboardGroup {
+        seat: Seat,
+    },
+    SeatPrevKeyboardGroup {
+        seat: Seat,
+    },
+    SeatGetKeyboardGroupName {
+        seat: Seat,
+        group: u32,
+    },
+    SeatSetRememberKeyboardGroup {
+        seat: Seat,
+        remember: bool,
+    },
+    DeviceSetKeymap {
+        device: InputDevice,
+        keymap: Keymap,
+    },
+    SeatSetBindingMode {
+        seat: Seat,
+        mode: BindingMode,
+    },
+    SeatGetBindingMode {
+        seat: Seat,
+    },
+    AddButtonBinding {
+        seat: Seat,
+        mods: Modifiers,
+        button: Button,
+    },
+    RemoveButtonBinding {
+        seat: Seat,
+        mods: Modifiers,
+        button: Button,
+    },
+    AddScrollBinding {
+        seat: Seat,
+        mods: Modifiers,
+        axis: Axis,
+        direction: ScrollDirection,
+    },
+    RemoveScrollBinding {
+        seat: Seat,
+        mods: Modifiers,
+        axis: Axis,
+        direction: ScrollDirection,
+    },
+    SeatSetWindowDragModifier {
+        seat: Seat,
+        mods: Modifiers,
+    },
+    Resize {
+        seat: Seat,
+        direction: Direction,
+        amount: i32,
+    },
+    GetProcess {
+        process: Process,
+    },
+    ProcessSignal {
+        process: Process,
+        signal: i32,
+    },
+    Equalize {
+        seat: Seat,
+    },
+    CreateVirtualOutput {
+        width: i32,
+        height: i32,
+        refresh_millihz: u32,
+    },
+    RemoveVirtualOutput {
+        connector: Connector,
+    },
+    SetSmartGaps {
+        enabled: bool,
+    },
+    SetHideTitles {
+        hide: bool,
+    },
+    SeatGetHideTitles {
+        seat: Seat,
+    },
+    SeatSetHideTitles {
+        seat: Seat,
+        hide: bool,
+    },
+    SeatMoveToScratchpad {
+        seat: Seat,
+    },
+    SeatToggleScratchpad {
+        seat: Seat,
+        filter: Option<&'a str>,
+    },
+    SeatMinimize {
+        seat: Seat,
+    },
+    SeatRestoreLastMinimized {
+        seat: Seat,
+    },
+    SeatGetLayout {
+        seat: Seat,
+    },
+    SeatSetLayout {
+        seat: Seat,
+        layout: ContainerLayout,
+    },
+    SetWorkspaceLayout {
+        workspace: Workspace,
+        layout: ContainerLayout,
+    },
+    SeatToggleSticky {
+        seat: Seat,
+    },
+    SetCursorTheme {
+        theme: Option<&'a str>,
+    },
+    AddKeyBinding {
+        seat: Seat,
+        binding: KeyBinding,
+    },
+    RemoveKeyBinding {
+        seat: Seat,
+        binding: KeyBinding,
+    },
+    Run2 {
+        prog: &'a str,
+        args: Vec<String>,
+        env: Vec<(String, String)>,
+        stdout: Stdio,
+        stderr: Stdio,
+        placement: Placement,
+    },
+}
+
+#[derive(Encode, Decode, Debug, Default)]
+pub struct Placement {
+    pub workspace: Option<Workspace>,
+    pub output: Option<Connector>,
+    pub floating: Option<bool>,
+    pub sticky: bool,
 }
 
 #[derive(Encode, Decode, Debug)]
@@ -432,6 +632,35 @@ pub enum Response {
     GetWorkspaceCapture {
         capture: bool,
     },
+    GetIdleStage {
+        stage: IdleStage,
+    },
+    GetKeyboardGroup {
+        group: u32,
+        num_groups: u32,
+    },
+    GetKeyboardGroupName {
+        name: String,
+    },
+    GetBindingMode {
+        mode: BindingMode,
+    },
+    Run {
+        process: Process,
+    },
+    GetProcess {
+        pid: Option<i32>,
+        alive: bool,
+    },
+    CreateVirtualOutput {
+        connector: Connector,
+    },
+    SeatGetHideTitles {
+        hide: bool,
+    },
+    SeatGetLayout {
+        layout: ContainerLayout,
+    },
 }
 
 #[derive(Encode, Decode, Debug)]
@@ -1,6 +1,10 @@
 //! Tools for spawning programs.
 
-use std::collections::HashMap;
+use {
+    crate::{video::Connector, Workspace},
+    bincode::{Decode, Encode},
+    std::{collections::HashMap, os::unix::io::RawFd},
+};
 
 /// Sets an environment variable.
 ///
@@ -14,6 +18,12 @@ pub struct Command {
     pub(crate) prog: String,
     pub(crate) args: Vec<String>,
     pub(crate) env: HashMap<String, String>,
+    pub(crate) stdout: Stdio,
+    pub(crate) stderr: Stdio,
+    pub(crate) workspace: Option<Workspace>,
+    pub(crate) output: Option<Connector>,
+    pub(crate) floating: Option<bool>,
+    pub(crate) sticky: bool,
 }
 
 impl Command {
@@ -28,6 +38,12 @@ impl Command {
             prog: prog.to_string(),
             args: vec![],
             env: Default::default(),
+            stdout: Stdio::Null,
+            stderr: Stdio::Null,
+            workspace: None,
+            output: None,
+            floating: None,
+            sticky: false,
         }
     }
 
@@ -43,8 +59,149 @@ impl Command {
         self
     }
 
+    /// Sets where the standard output of the command goes.
+    ///
+    /// The default is [`Stdio::Null`].
+    pub fn stdout(&mut self, stdio: Stdio) -> &mut Self {
+        self.stdout = stdio;
+        self
+    }
+
+    /// Sets where the standard error of the command goes.
+    ///
+    /// The default is [`Stdio::Null`].
+    pub fn stderr(&mut self, stdio: Stdio) -> &mut Self {
+        self.stderr = stdio;
+        self
+    }
+
+    /// Sets the workspace on which the first window of the program is placed.
+    ///
+    /// If the workspace does not exist, it is created on the output set with
+    /// [`Command::output`] or on the output of the last active seat.
+    ///
+    /// Windows are matched to the program via the pid of the client or one of its
+    /// ancestors and via the token that is passed to the program in the
+    /// `XDG_ACTIVATION_TOKEN` and `DESKTOP_STARTUP_ID` environment variables. Wayland
+    /// clients present the token with the xdg-activation protocol, X11 clients via the
+    /// startup id of their window. The placement is applied to the first matching window
+    /// only. If no window matches within a minute, the placement is discarded.
+    pub fn workspace(&mut self, workspace: Workspace) -> &mut Self {
+        self.workspace = Some(workspace);
+        self
+    }
+
+    /// Sets the output on which the first window of the program is placed.
+    ///
+    /// See [`Command::workspace`] for how windows are matched to the program.
+    pub fn output(&mut self, connector: Connector) -> &mut Self {
+        self.output = Some(connector);
+        self
+    }
+
+    /// Sets whether the first window of the program is floating.
+    ///
+    /// See [`Command::workspace`] for how windows are matched to the program.
+    pub fn floating(&mut self, floating: bool) -> &mut Self {
+        self.floating = Some(floating);
+        self
+    }
+
+    /// Sets whether the first window of the program is sticky.
+    ///
+    /// Sticky windows are floating and stay visible when the workspace of their output is
+    /// switched. Unless [`Command::floating`] is used to disable floating, this also makes
+    /// the window floating.
+    ///
+    /// See [`Command::workspace`] for how windows are matched to the program.
+    pub fn sticky(&mut self, sticky: bool) -> &mut Self {
+        self.sticky = sticky;
+        self
+    }
+
     /// Executes the command.
     pub fn spawn(&self) {
-        get!().spawn(self);
+        // Registering a callback ensures that the exit status is not retained.
+        self.spawn_process().on_exit(|_| ());
     }
+
+    /// Executes the command and returns a handle that can be used to monitor the spawned
+    /// process.
+    pub fn spawn_process(&self) -> Process {
+        get!(Process(0)).spawn(self)
+    }
+}
+
+/// The destination of an output stream of a spawned program.
+#[derive(Encode, Decode, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Stdio {
+    /// The output is discarded.
+    Null,
+    /// Each line of output is written to the compositor log.
+    Log,
+    /// The output is written to this file descriptor.
+    ///
+    /// The file descriptor is duplicated when the command is spawned. The caller retains
+    /// ownership of the original file descriptor and can close it once `spawn` returns.
+    Fd(RawFd),
+}
+
+/// A process spawned by the compositor.
+#[derive(Encode, Decode, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Process(pub u64);
+
+impl Process {
+    /// Returns the process id of the process.
+    ///
+    /// Returns `None` if the process has not been started yet or if it has already exited.
+    pub fn pid(self) -> Option<i32> {
+        get!(None).process_pid(self)
+    }
+
+    /// Returns whether the process is still running.
+    ///
+    /// A process that is still being started is considered to be running.
+    pub fn is_alive(self) -> bool {
+        get!(false).process_is_alive(self)
+    }
+
+    /// Returns the exit status of the process if it has exited.
+    ///
+    /// The exit status is only retained until it has been retrieved once, either by this
+    /// function or by a callback registered with [`Process::on_exit`]. Subsequent calls
+    /// return `None`.
+    pub fn exit_status(self) -> Option<ExitStatus> {
+        get!(None).process_exit_status(self)
+    }
+
+    /// Sends `SIGKILL` to the process.
+    pub fn kill(self) {
+        self.signal(9);
+    }
+
+    /// Sends a signal to the process.
+    pub fn signal(self, signal: i32) {
+        get!().process_signal(self, signal)
+    }
+
+    /// Sets a callback to be invoked when the process exits.
+    ///
+    /// If the process has already exited, the callback is invoked immediately. The exit
+    /// status is passed to the callback and is not retained afterwards.
+    pub fn on_exit<F: FnOnce(ExitStatus) + 'static>(self, f: F) {
+        get!().on_process_exit(self, f)
+    }
+}
+
+/// The exit status of a process.
+#[derive(Encode, Decode, Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ExitStatus {
+    /// The process exited with the contained exit code.
+    Exited(i32),
+    /// The process was terminated by the contained signal.
+    Signaled(i32),
+    /// The program could not be started.
+    SpawnFailed,
+    /// The exit status of the process could not be determined.
+    Unknown,
 }
@@ -0,0 +1,66 @@
+//! Multi-stage idle handling.
+//!
+//! Idle stages are entered after the user has been inactive for a certain period of time
+//! and left as soon as input occurs. A typical configuration dims the screen after 5
+//! minutes, locks the session after 10 minutes, turns the outputs off after 15 minutes,
+//! and suspends the system after 30 minutes.
+//!
+//! Idle stages are not entered while an application inhibits idling, e.g. while a video
+//! is playing.
+
+use {
+    bincode::{Decode, Encode},
+    std::time::Duration,
+};
+
+/// An idle stage.
+#[derive(Encode, Decode, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct IdleStage(pub u64);
+
+/// Creates a new idle stage or returns an existing one.
+///
+/// Idle stages are identified by their name and their lifetime is bound by the lifetime of
+/// the configuration. Reloading the configuration destroys all existing idle stages.
+///
+/// Within the same configuration, calling this function multiple times with the same name
+/// will return the same idle stage.
+///
+/// A newly created idle stage is disabled until a timeout is set via `set_timeout`.
+pub fn get_idle_stage(name: &str) -> IdleStage {
+    get!(IdleStage(0)).get_idle_stage(name)
+}
+
+impl IdleStage {
+    /// Sets the period of inactivity after which this stage is entered.
+    ///
+    /// A timeout of zero disables the stage.
+    pub fn set_timeout(self, timeout: Duration) {
+        get!().set_idle_stage_timeout(self, timeout);
+    }
+
+    /// Sets whether the outputs are turned off while this stage is active.
+    ///
+    /// The default is `false`.
+    pub fn set_disable_outputs(self, disable: bool) {
+        get!().set_idle_stage_disable_outputs(self, disable);
+    }
+
+    /// Removes the idle stage.
+    ///
+    /// This reference to the idle stage becomes invalid as do all other existing
+    /// references. A new idle stage with the same name can be created by calling
+    /// `get_idle_stage`.
+    pub fn remove(self) {
+        get!().remove_idle_stage(self);
+    }
+
+    /// Sets the function to be executed when this stage is entered.
+    pub fn on_idle<F: Fn() + 'static>(self, f: F) {
+        get!().on_idle_stage_entered(self, f);
+    }
+
+    /// Sets the function to be executed when input occurs while this stage is active.
+    pub fn on_resumed<F: Fn() + 'static>(self, f: F) {
+        get!().on_idle_stage_resumed(self, f);
+    }
+}
@@ -1,17 +1,27 @@
 //! Tools for configuring input devices.
 
 pub mod acceleration;
+pub mod button;
 pub mod capability;
 
 use {
     crate::{
-        input::{acceleration::AccelProfile, capability::Capability},
-        keyboard::Keymap,
-        Axis, Direction, ModifiedKeySym, Workspace,
+        input::{acceleration::AccelProfile, button::Button, capability::Capability},
+        keyboard::{mods::Modifiers, BindingMode, KeyBinding, Keymap},
+        Axis, ContainerLayout, Direction, ModifiedKeySym, Workspace,
     },
     bincode::{Decode, Encode},
 };
 
+/// The direction of a scroll event along an axis.
+#[derive(Encode, Decode, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum ScrollDirection {
+    /// Up or left.
+    Negative,
+    /// Down or right.
+    Positive,
+}
+
 /// An input device.
 #[derive(Encode, Decode, Copy, Clone, Debug, Hash, Eq, PartialEq)]
 pub struct InputDevice(pub u64);
@@ -67,6 +77,15 @@ impl InputDevice {
         get!().set_transform_matrix(self, matrix);
     }
 
+    /// Sets the keymap of the device.
+    ///
+    /// This overrides the keymap of the seat for key presses originating from this device.
+    /// Setting the keymap to [`Keymap::INVALID`] removes the override so that the device
+    /// uses the keymap of the seat again.
+    pub fn set_keymap(self, keymap: Keymap) {
+        get!().device_set_keymap(self, keymap)
+    }
+
     /// Returns the name of the device.
     pub fn name(self) -> String {
         get!(String::new()).device_name(self)
@@ -162,6 +181,89 @@ impl Seat {
         get!().unbind(self, mod_sym)
     }
 
+    /// Creates a key binding.
+    ///
+    /// This is a more general version of [`Seat::bind`] that supports binding modes,
+    /// release-triggered bindings, and key sequences. See [`KeyBinding`].
+    ///
+    /// While the keys of a sequence are being entered, all other key presses are
+    /// consumed. Pressing a key that does not continue any sequence aborts the sequence.
+    /// If a key both starts a sequence and is bound on its own, the sequence takes
+    /// precedence.
+    pub fn bind_key<T: Into<KeyBinding>, F: Fn() + 'static>(self, binding: T, f: F) {
+        get!().bind_key(self, binding.into(), f)
+    }
+
+    /// Removes a key binding.
+    pub fn unbind_key<T: Into<KeyBinding>>(self, binding: T) {
+        get!().unbind_key(self, binding.into())
+    }
+
+    /// Binds a pointer button.
+    ///
+    /// The closure is invoked when the button is pressed while exactly the specified
+    /// modifiers are pressed. The press and the corresponding release are not forwarded to
+    /// the window under the cursor.
+    ///
+    /// CapsLock and NumLock are ignored during modifier evaluation.
+    pub fn bind_button<F: Fn() + 'static>(self, mods: Modifiers, button: Button, f: F) {
+        get!().bind_button(self, mods, button, f)
+    }
+
+    /// Unbinds a pointer button.
+    pub fn unbind_button(self, mods: Modifiers, button: Button) {
+        get!().unbind_button(self, mods, button)
+    }
+
+    /// Binds scrolling.
+    ///
+    /// The closure is invoked once per scroll wheel detent in the specified direction
+    /// while exactly the specified modifiers are pressed. Such scroll events are not
+    /// forwarded to the window under the cursor.
+    ///
+    /// Only scroll wheels are considered. Continuous scrolling, e.g. on touchpads, is
+    /// always forwarded.
+    ///
+    /// CapsLock and NumLock are ignored during modifier evaluation.
+    pub fn bind_scroll<F: Fn() + 'static>(
+        self,
+        mods: Modifiers,
+        axis: Axis,
+        direction: ScrollDirection,
+        f: F,
+    ) {
+        get!().bind_scroll(self, mods, axis, direction, f)
+    }
+
+    /// Unbinds scrolling.
+    pub fn unbind_scroll(self, mods: Modifiers, axis: Axis, direction: ScrollDirection) {
+        get!().unbind_scroll(self, mods, axis, direction)
+    }
+
+    /// Sets the modifier that allows windows to be moved and resized with the pointer.
+    ///
+    /// While these modifiers are pressed, dragging any window with the left button moves
+    /// it and dragging it with the right button resizes it. Floating windows are moved and
+    /// resized freely. Tiled windows are resized by adjusting the split factors of their
+    /// containers and are moved next to the tiled window they are dropped on.
+    ///
+    /// Setting this to empty modifiers disables the feature. The default is disabled.
+    pub fn set_window_drag_modifier(self, mods: Modifiers) {
+        get!().seat_set_window_drag_modifier(self, mods)
+    }
+
+    /// Sets the active binding mode of the seat.
+    ///
+    /// This also aborts any partially entered key sequence.
+    pub fn set_binding_mode(self, mode: BindingMode) {
+        get!().seat_set_binding_mode(self, mode)
+    }
+
+    /// Returns the active binding mode of the seat.
+    pub fn binding_mode(self) -> BindingMode {
+        get!(BindingMode::DEFAULT).seat_get_binding_mode(self)
+    }
+
     /// Moves the keyboard focus of the seat in the specified direction.
     pub fn focus(self, direction: Direction) {
         get!().focus(self, direction)
@@ -172,6 +274,21 @@ impl Seat {
         get!().move_(self, direction)
     }
 
+    /// Resizes the focused window by moving its edge in the specified direction.
+    ///
+    /// A positive amount moves the edge outwards by that many pixels and a negative amount
+    /// moves it inwards. The size is taken from the neighbor in the nearest ancestor
+    /// container that is split along the axis of the direction. If there is no such
+    /// neighbor, the opposite edge is moved instead.
+    pub fn resize(self, direction: Direction, amount: i32) {
+        get!().resize(self, direction, amount)
+    }
+
+    /// Gives all children of the container of the focused window the same size.
+    pub fn equalize(self) {
+        get!().equalize(self)
+    }
+
     /// Sets the keymap of the seat.
     pub fn set_keymap(self, keymap: Keymap) {
         get!().seat_set_keymap(self, keymap)
@@ -190,6 +307,60 @@ impl Seat {
         get!().seat_set_repeat_rate(self, rate, delay)
     }
 
+    /// Returns the active keyboard group (layout) of the seat.
+    ///
+    /// Groups are numbered starting at 0 in the order in which they appear in the keymap.
+    pub fn keyboard_group(self) -> u32 {
+        get!(0).seat_get_keyboard_group(self).0
+    }
+
+    /// Returns the number of keyboard groups (layouts) in the keymap of the seat.
+    pub fn num_keyboard_groups(self) -> u32 {
+        get!(1).seat_get_keyboard_group(self).1
+    }
+
+    /// Returns the name of a keyboard group, e.g. `English (US)`.
+    ///
+    /// Returns an empty string if the group does not exist or has no name.
+    pub fn keyboard_group_name(self, group: u32) -> String {
+        get!(String::new()).seat_get_keyboard_group_name(self, group)
+    }
+
+    /// Sets the active keyboard group (layout) of the seat.
+    ///
+    /// If the group is out of range, it wraps around the number of groups in the keymap.
+    pub fn set_keyboard_group(self, group: u32) {
+        get!().seat_set_keyboard_group(self, group)
+    }
+
+    /// Switches to the next keyboard group (layout), wrapping around at the end.
+    pub fn next_keyboard_group(self) {
+        get!().seat_next_keyboard_group(self)
+    }
+
+    /// Switches to the previous keyboard group (layout), wrapping around at the start.
+    pub fn prev_keyboard_group(self) {
+        get!().seat_prev_keyboard_group(self)
+    }
+
+    /// Sets whether the keyboard group is remembered per window.
+    ///
+    /// If this is enabled, the active group is stored in a window when it loses the keyboard
+    /// focus and restored when the window regains the focus. Windows that have not been
+    /// focused before use the group that was active when they receive the focus.
+    ///
+    /// The default is `false`.
+    pub fn set_remember_keyboard_group_per_window(self, remember: bool) {
+        get!().seat_set_remember_keyboard_group(self, remember)
+    }
+
+    /// Sets a callback to run when the active keyboard group of the seat changes.
+    ///
+    /// The callback receives the new group. This can be used to update a status bar.
+    pub fn on_keyboard_group_changed<F: Fn(u32) + 'static>(self, f: F) {
+        get!().seat_on_keyboard_group_changed(self, f)
+    }
+
     /// Returns whether the parent-container of the currently focused window is in mono-mode.
     pub fn mono(self) -> bool {
         get!(false).mono(self)
@@ -205,6 +376,61 @@ impl Seat {
         self.set_mono(!self.mono());
     }
 
+    /// Returns whether the parent-container of the currently focused window hides its title
+    /// bars when it has a single child or is in mono-mode.
+    pub fn hide_titles(self) -> bool {
+        get!(false).hide_titles(self)
+    }
+
+    /// Sets whether the parent-container of the currently focused window hides its title
+    /// bars when it has a single child or is in mono-mode.
+    ///
+    /// This overrides the global setting from [`set_hide_titles`].
+    ///
+    /// [`set_hide_titles`]: crate::theme::set_hide_titles
+    pub fn set_hide_titles(self, hide: bool) {
+        get!().seat_set_hide_titles(self, hide)
+    }
+
+    /// Toggles whether the parent-container of the currently focused window hides its title
+    /// bars when it has a single child or is in mono-mode.
+    pub fn toggle_hide_titles(self) {
+        self.set_hide_titles(!self.hide_titles());
+    }
+
+    /// Minimizes the currently focused window.
+    ///
+    /// Minimized windows are hidden until they are restored with
+    /// [`Seat::restore_last_minimized`].
+    pub fn minimize(self) {
+        get!().minimize(self)
+    }
+
+    /// Restores the most recently minimized window of the current workspace of this seat.
+    pub fn restore_last_minimized(self) {
+        get!().restore_last_minimized(self)
+    }
+
+    /// Moves the currently focused window to the scratchpad.
+    ///
+    /// Windows in the scratchpad are hidden until they are shown with
+    /// [`Seat::toggle_scratchpad`].
+    pub fn move_to_scratchpad(self) {
+        get!().move_to_scratchpad(self)
+    }
+
+    /// Toggles the scratchpad on the current workspace of this seat.
+    ///
+    /// If a window from the scratchpad is shown on the current workspace, it is moved back
+    /// to the scratchpad. Otherwise the first window from the scratchpad is shown as a
+    /// centered floating window on the current workspace.
+    ///
+    /// If `filter` is not `None`, only windows whose title contains `filter` are
+    /// considered.
+    pub fn toggle_scratchpad(self, filter: Option<&str>) {
+        get!().toggle_scratchpad(self, filter)
+    }
+
     /// Returns the split axis of the parent-container of the currently focused window.
     pub fn split(self) -> Axis {
         get!(Axis::Horizontal).split(self)
@@ -220,6 +446,16 @@ impl Seat {
         self.set_split(self.split().other());
     }
 
+    /// Returns the layout of the parent-container of the currently focused window.
+    pub fn layout(self) -> ContainerLayout {
+        get!(ContainerLayout::Split).layout(self)
+    }
+
+    /// Sets the layout of the parent-container of the currently focused window.
+    pub fn set_layout(self, layout: ContainerLayout) {
+        get!().set_layout(self, layout)
+    }
+
     /// Returns the input devices assigned to this seat.
     pub fn input_devices(self) -> Vec<InputDevice> {
         get!().get_input_devices(Some(self))
@@ -254,6 +490,14 @@ impl Seat {
         get!().toggle_floating(self);
     }
 
+    /// Toggles whether the currently focused window is sticky.
+    ///
+    /// Sticky windows stay visible when the workspace of their output is switched. Only
+    /// floating windows can be sticky.
+    pub fn toggle_sticky(self) {
+        get!().toggle_sticky(self);
+    }
+
     /// Returns the workspace that is currently active on the output that contains the seat's
     /// cursor.
     ///
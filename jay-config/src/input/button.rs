@@ -0,0 +1,17 @@
+//! Constants specifying pointer buttons.
+//!
+//! These are the button codes used by the Linux kernel.
+
+use bincode::{Decode, Encode};
+
+/// A pointer button.
+#[derive(Encode, Decode, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub struct Button(pub u32);
+
+pub const BTN_LEFT: Button = Button(0x110);
+pub const BTN_RIGHT: Button = Button(0x111);
+pub const BTN_MIDDLE: Button = Button(0x112);
+pub const BTN_SIDE: Button = Button(0x113);
+pub const BTN_EXTRA: Button = Button(0x114);
+pub const BTN_FORWARD: Button = Button(0x115);
+pub const BTN_BACK: Button = Button(0x116);
@@ -42,6 +42,95 @@ impl BitOrAssign<Modifiers> for ModifiedKeySym {
     }
 }
 
+/// A binding mode.
+///
+/// Each seat has an active binding mode. Only bindings of the active mode are evaluated.
+/// Initially, the [`BindingMode::DEFAULT`] mode is active.
+#[derive(Encode, Decode, Copy, Clone, Debug, Eq, PartialEq, Hash, Default)]
+pub struct BindingMode(pub u64);
+
+impl BindingMode {
+    /// The default binding mode.
+    pub const DEFAULT: Self = Self(0);
+}
+
+/// Returns the binding mode with the given name.
+///
+/// Calling this function multiple times with the same name returns the same mode. The name
+/// `default` refers to [`BindingMode::DEFAULT`].
+pub fn get_binding_mode(name: &str) -> BindingMode {
+    get!(BindingMode::DEFAULT).get_binding_mode(name)
+}
+
+/// When a key binding is triggered.
+#[derive(Encode, Decode, Copy, Clone, Debug, Eq, PartialEq, Hash, Default)]
+pub enum KeyTrigger {
+    /// The binding is triggered when the key is pressed.
+    #[default]
+    Press,
+    /// The binding is triggered when the key is released without any other key having
+    /// been pressed since it was pressed.
+    ///
+    /// The modifiers are evaluated before the key is released. Therefore, to run an
+    /// action when the logo key is tapped on its own, use `LOGO | SYM_Super_L`.
+    Release,
+}
+
+/// A key binding.
+///
+/// Simple bindings can be created from a [`ModifiedKeySym`]. The builder methods can
+/// then be used to modify the binding:
+///
+/// ```rust,ignore
+/// let resize = get_binding_mode("resize");
+/// // Runs when `h` is pressed while the resize mode is active.
+/// let binding = KeyBinding::from(SYM_h).in_mode(resize);
+/// // Runs when `t` is pressed after `LOGO+x` was pressed.
+/// let binding = KeyBinding::from(SYM_t).after(&[LOGO | SYM_x]);
+/// ```
+#[derive(Encode, Decode, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct KeyBinding {
+    /// The binding mode in which this binding is active.
+    pub mode: BindingMode,
+    /// When this binding is triggered.
+    pub trigger: KeyTrigger,
+    /// The keys that have to be pressed, in order, before the key of this binding.
+    pub prefix: Vec<ModifiedKeySym>,
+    /// The key of this binding.
+    pub key: ModifiedKeySym,
+}
+
+impl KeyBinding {
+    /// Makes the binding active only in the specified mode.
+    pub fn in_mode(mut self, mode: BindingMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    /// Makes the binding trigger when the key is released.
+    pub fn on_release(mut self) -> Self {
+        self.trigger = KeyTrigger::Release;
+        self
+    }
+
+    /// Makes the binding trigger only after the specified keys have been pressed in order.
+    pub fn after(mut self, prefix: &[ModifiedKeySym]) -> Self {
+        self.prefix = prefix.to_vec();
+        self
+    }
+}
+
+impl<T: Into<ModifiedKeySym>> From<T> for KeyBinding {
+    fn from(key: T) -> Self {
+        Self {
+            mode: BindingMode::DEFAULT,
+            trigger: KeyTrigger::Press,
+            prefix: vec![],
+            key: key.into(),
+        }
+    }
+}
+
 /// A keymap.
 #[derive(Encode, Decode, Copy, Clone, Debug, Eq, PartialEq, Hash)]
 pub struct Keymap(pub u64);
@@ -100,3 +189,55 @@ impl Keymap {
 pub fn parse_keymap(keymap: &str) -> Keymap {
     get!(Keymap::INVALID).parse_keymap(keymap)
 }
+
+/// The maximum number of layouts in a keymap. xkb supports at most 4 groups.
+const MAX_LAYOUTS: usize = 4;
+
+/// Creates a keymap with one group per layout.
+///
+/// Each layout is the name of an xkb symbols file with an optional variant, e.g. `us` or
+/// `us(dvp)`. At most 4 layouts are supported. If more layouts are passed, an error is
+/// logged and an invalid keymap is returned. Use [`Seat::next_keyboard_group`] and
+/// related functions to switch between the layouts.
+///
+/// For example, `keymap_from_layouts(&["us", "ru"])` creates the keymap
+///
+/// ```text
+/// xkb_keymap {
+///     xkb_keycodes  { include "evdev+aliases(qwerty)" };
+///     xkb_types     { include "complete" };
+///     xkb_compat    { include "complete" };
+///     xkb_symbols   { include "pc+us+ru:2+inet(evdev)" };
+/// };
+/// ```
+///
+/// [`Seat::next_keyboard_group`]: crate::input::Seat::next_keyboard_group
+pub fn keymap_from_layouts(layouts: &[&str]) -> Keymap {
+    if layouts.len() > MAX_LAYOUTS {
+        log::error!(
+            "At most {} keyboard layouts are supported but {} were passed",
+            MAX_LAYOUTS,
+            layouts.len()
+        );
+        return Keymap::INVALID;
+    }
+    let mut symbols = "pc".to_string();
+    for (idx, layout) in layouts.iter().enumerate() {
+        symbols.push('+');
+        symbols.push_str(layout);
+        if idx > 0 {
+            symbols.push_str(&format!(":{}", idx + 1));
+        }
+    }
+    symbols.push_str("+inet(evdev)");
+    let keymap = format!(
+        r#"xkb_keymap {{
+    xkb_keycodes  {{ include "evdev+aliases(qwerty)" }};
+    xkb_types     {{ include "complete" }};
+    xkb_compat    {{ include "complete" }};
+    xkb_symbols   {{ include "{}" }};
+}};"#,
+        symbols
+    );
+    parse_keymap(&keymap)
+}
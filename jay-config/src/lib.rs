@@ -52,6 +52,7 @@ mod macros;
 pub mod _private;
 pub mod embedded;
 pub mod exec;
+pub mod idle;
 pub mod input;
 pub mod keyboard;
 pub mod logging;
@@ -86,6 +87,25 @@ impl Axis {
     }
 }
 
+/// The layout of a container.
+///
+/// Tabbed stacks are provided by mono mode (see [`Seat::set_mono`](input::Seat::set_mono)),
+/// which shows a single child together with the titles of all children. Mono mode takes
+/// precedence over the layout.
+#[derive(Encode, Decode, Copy, Clone, Debug, Hash, Eq, PartialEq)]
+pub enum ContainerLayout {
+    /// The children are placed next to each other along the split axis of the container.
+    Split,
+    /// The first child occupies the left half of the container. The other children are
+    /// stacked on top of each other in the right half.
+    MasterStack,
+    /// Each child occupies half of the space left over by the previous children. The
+    /// children spiral inwards.
+    Spiral,
+    /// Each child is placed in its own column. All columns have the same width.
+    Columns,
+}
+
 /// Exits the compositor.
 pub fn quit() {
     get!().quit()
@@ -155,6 +175,15 @@ impl Workspace {
         let get = get!();
         get.set_workspace_capture(self, !get.get_workspace_capture(self));
     }
+
+    /// Sets the layout of the top-level container of this workspace.
+    ///
+    /// The layout is also used for the top-level container when it is re-created later.
+    ///
+    /// The default is [`ContainerLayout::Split`].
+    pub fn set_layout(self, layout: ContainerLayout) {
+        get!().set_workspace_layout(self, layout)
+    }
 }
 
 /// Returns the workspace with the given name.
@@ -187,6 +216,23 @@ pub fn on_idle<F: Fn() + 'static>(f: F) {
     get!().on_idle(f)
 }
 
+/// Sets the callback to be called when the client holding the session lock dies.
+///
+/// The screens stay locked and show the fallback lock screen until a new locker locks the
+/// session or the session is unlocked via `jay unlock`. This is a good place to spawn a
+/// replacement locker.
+pub fn on_locker_died<F: Fn() + 'static>(f: F) {
+    get!().on_locker_died(f)
+}
+
+/// Sets the text shown on the fallback lock screen.
+///
+/// The fallback lock screen is shown on all outputs while the session is locked but no
+/// locker is running, e.g. after the locker crashed.
+pub fn set_lock_fallback_text(text: &str) {
+    get!().set_lock_fallback_text(text)
+}
+
 /// Sets the callback to be called when all devices have been enumerated.
 ///
 /// This callback is only invoked once during the lifetime of the compositor. This is a
@@ -128,6 +128,28 @@ pub fn reset_sizes() {
     get!().reset_sizes();
 }
 
+/// Enables or disables smart gaps.
+///
+/// If smart gaps are enabled, the inner and outer gaps are not applied to workspaces that
+/// contain only a single window.
+///
+/// Default: `false`.
+pub fn set_smart_gaps(enabled: bool) {
+    get!().set_smart_gaps(enabled)
+}
+
+/// Enables or disables hiding of title bars.
+///
+/// If enabled, containers with a single child and containers in mono-mode do not show
+/// title bars. This can be overridden per container with [`Seat::set_hide_titles`].
+///
+/// Default: `false`.
+///
+/// [`Seat::set_hide_titles`]: crate::input::Seat::set_hide_titles
+pub fn set_hide_titles(hide: bool) {
+    get!().set_hide_titles(hide)
+}
+
 /// Resets all colors to their defaults.
 pub fn reset_colors() {
     get!().reset_colors();
@@ -156,6 +178,22 @@ pub fn reset_font() {
     get!().reset_font()
 }
 
+/// Sets the xcursor theme used for compositor-drawn cursors.
+///
+/// This includes the cursors requested by clients via the cursor-shape protocol.
+/// Cursors that are not part of the theme are looked up in its parent themes and in
+/// the `default` theme.
+pub fn set_cursor_theme(theme: &str) {
+    get!().set_cursor_theme(Some(theme))
+}
+
+/// Resets the cursor theme to the default.
+///
+/// The default is the value of the `XCURSOR_THEME` environment variable.
+pub fn reset_cursor_theme() {
+    get!().set_cursor_theme(None)
+}
+
 /// Elements of the compositor whose color can be changed.
 pub mod colors {
     use {
@@ -294,5 +332,13 @@ pub mod sized {
         ///
         /// Default: 4
         const 02 => BORDER_WIDTH,
+        /// The gap between tiled windows.
+        ///
+        /// Default: 0
+        const 03 => INNER_GAP,
+        /// The gap between the edges of a workspace and its windows.
+        ///
+        /// Default: 0
+        const 04 => OUTER_GAP,
     }
 }
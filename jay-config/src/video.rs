@@ -158,6 +158,17 @@ impl Connector {
         }
         get!().connector_set_enabled(self, enabled);
     }
+
+    /// Removes a virtual output.
+    ///
+    /// This only has an effect on connectors created with `create_virtual_output`.
+    pub fn remove_virtual_output(self) {
+        if !self.exists() {
+            log::warn!("remove_virtual_output called on a connector that does not exist");
+            return;
+        }
+        get!().remove_virtual_output(self);
+    }
 }
 
 /// Returns all available DRM devices.
@@ -165,6 +176,18 @@ pub fn drm_devices() -> Vec<DrmDevice> {
     get!().drm_devices()
 }
 
+/// Creates a virtual output with the given mode.
+///
+/// `refresh_millihz` is the refresh rate in mhz, e.g., 60_000 for 60hz. Virtual outputs are
+/// only supported by the headless backend. If the output cannot be created, a sentinel value
+/// is returned. This can be checked by calling `exists()` on the returned connector.
+///
+/// The returned connector behaves like any other connector. It can be removed again with
+/// `Connector::remove_virtual_output`.
+pub fn create_virtual_output(width: i32, height: i32, refresh_millihz: u32) -> Connector {
+    get!(Connector(0)).create_virtual_output(width, height, refresh_millihz)
+}
+
 /// Sets the callback to be called when a new DRM device appears.
 pub fn on_new_drm_device<F: Fn(DrmDevice) + 'static>(f: F) {
     get!().on_new_drm_device(f)
@@ -42,6 +42,16 @@ pub trait Backend {
     fn supports_presentation_feedback(&self) -> bool {
         false
     }
+
+    fn create_virtual_output(&self, mode: Mode) -> Option<ConnectorId> {
+        let _ = mode;
+        None
+    }
+
+    fn remove_virtual_output(&self, id: ConnectorId) -> bool {
+        let _ = id;
+        false
+    }
 }
 
 #[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
@@ -1,3 +1,5 @@
 pub mod dummy;
+pub mod headless;
 pub mod metal;
+pub mod wayland;
 pub mod x;
@@ -0,0 +1,480 @@
+#[cfg(test)]
+mod tests;
+
+use {
+    crate::{
+        async_engine::{Phase, SpawnedFuture},
+        backend::{
+            Backend, BackendEvent, Connector, ConnectorEvent, ConnectorId, ConnectorKernelId,
+            DrmDeviceId, Mode, MonitorInfo,
+        },
+        format::XRGB8888,
+        render::{Framebuffer, RenderContext, RenderError, RenderResult, Texture},
+        state::State,
+        time::Time,
+        utils::{
+            asyncevent::AsyncEvent,
+            clonecell::CloneCell,
+            copyhashmap::CopyHashMap,
+            errorfmt::ErrorFmt,
+            numcell::NumCell,
+            oserror::OsError,
+            syncqueue::SyncQueue,
+            timer::{TimerError, TimerFd},
+        },
+        video::{
+            drm::{ConnectorType, Drm},
+            gbm::{GbmDevice, GbmError, GBM_BO_USE_RENDERING},
+            ModifiedFormat, INVALID_MODIFIER,
+        },
+    },
+    bstr::ByteSlice,
+    std::{
+        any::Any,
+        cell::{Cell, RefCell},
+        error::Error,
+        future::pending,
+        io,
+        ops::DerefMut,
+        os::unix::ffi::OsStrExt,
+        rc::Rc,
+        time::Duration,
+    },
+    thiserror::Error,
+    uapi::c,
+};
+
+const DEFAULT_MODE: Mode = Mode {
+    width: 1920,
+    height: 1080,
+    refresh_rate_millihz: 60_000,
+};
+
+#[derive(Debug, Error)]
+pub enum HeadlessBackendError {
+    #[error("Could not parse the output mode `{0}`")]
+    InvalidMode(String),
+    #[error("Could not read /dev/dri")]
+    ReadDri(#[source] io::Error),
+    #[error("There are no render nodes in /dev/dri")]
+    NoRenderNode,
+    #[error("Could not open drm node {0}")]
+    OpenDrmNode(String, #[source] OsError),
+    #[error("Could not create a render context")]
+    RenderContext(#[source] RenderError),
+    #[error("The gbm subsystem returned an error")]
+    GbmError(#[from] GbmError),
+    #[error("Could not create a buffer")]
+    CreateImage(#[source] RenderError),
+    #[error("Could not create a framebuffer from an EGL image")]
+    CreateFramebuffer(#[source] RenderError),
+    #[error("Could not create a texture from an EGL image")]
+    CreateTexture(#[source] RenderError),
+    #[error("Could not create a frame timer")]
+    CreateTimer(#[source] TimerError),
+}
+
+pub fn create(state: &Rc<State>) -> Result<Rc<HeadlessBackend>, HeadlessBackendError> {
+    let mut modes = vec![];
+    for mode in &state.run_args.headless_outputs {
+        modes.push(parse_mode(mode)?);
+    }
+    if modes.is_empty() {
+        modes.push(DEFAULT_MODE);
+    }
+    let render = match create_gpu_render() {
+        Ok(r) => r,
+        Err(e) => {
+            log::warn!(
+                "Could not create a GPU render context, using the software renderer: {}",
+                ErrorFmt(e)
+            );
+            HeadlessRender {
+                ctx: Rc::new(RenderContext::software()),
+                gbm: None,
+            }
+        }
+    };
+    Ok(Rc::new(HeadlessBackend {
+        state: state.clone(),
+        render: Rc::new(render),
+        initial_modes: modes,
+        next_idx: NumCell::new(1),
+        outputs: Default::default(),
+    }))
+}
+
+/// Parses a mode of the form `WIDTHxHEIGHT` or `WIDTHxHEIGHT@REFRESH`.
+///
+/// The refresh rate is given in Hz and defaults to 60.
+fn parse_mode(s: &str) -> Result<Mode, HeadlessBackendError> {
+    let err = || HeadlessBackendError::InvalidMode(s.to_string());
+    let (size, refresh) = match s.split_once('@') {
+        Some((size, refresh)) => (size, Some(refresh)),
+        _ => (s, None),
+    };
+    let (width, height) = size.split_once('x').ok_or_else(err)?;
+    let width: i32 = width.trim().parse().map_err(|_| err())?;
+    let height: i32 = height.trim().parse().map_err(|_| err())?;
+    let refresh_rate_millihz = match refresh {
+        Some(r) => {
+            let hz: f64 = r.trim().parse().map_err(|_| err())?;
+            let millihz = (hz * 1000.0).round();
+            if !(1.0..=u32::MAX as f64).contains(&millihz) {
+                return Err(err());
+            }
+            millihz as u32
+        }
+        _ => DEFAULT_MODE.refresh_rate_millihz,
+    };
+    if width <= 0 || height <= 0 || refresh_rate_millihz == 0 {
+        return Err(err());
+    }
+    Ok(Mode {
+        width,
+        height,
+        refresh_rate_millihz,
+    })
+}
+
+fn create_gpu_render() -> Result<HeadlessRender, HeadlessBackendError> {
+    let drm = open_render_node()?;
+    let ctx = match RenderContext::from_drm_device(&drm) {
+        Ok(ctx) => Rc::new(ctx),
+        Err(e) => return Err(HeadlessBackendError::RenderContext(e)),
+    };
+    let gbm = GbmDevice::new(&drm)?;
+    Ok(HeadlessRender {
+        ctx,
+        gbm: Some(gbm),
+    })
+}
+
+pub(super) fn open_render_node() -> Result<Drm, HeadlessBackendError> {
+    let dri = match std::fs::read_dir("/dev/dri") {
+        Ok(d) => d,
+        Err(e) => return Err(HeadlessBackendError::ReadDri(e)),
+    };
+    let mut files = vec![];
+    for f in dri {
+        match f {
+            Ok(f) => files.push(f.path()),
+            Err(e) => return Err(HeadlessBackendError::ReadDri(e)),
+        }
+    }
+    files.sort();
+    let node = files.iter().find(|f| match f.file_name() {
+        Some(file) => file.as_bytes().starts_with_str("renderD"),
+        _ => false,
+    });
+    let node = match node {
+        Some(n) => n,
+        _ => return Err(HeadlessBackendError::NoRenderNode),
+    };
+    match uapi::open(node.as_path(), c::O_RDWR | c::O_CLOEXEC, 0) {
+        Ok(f) => Ok(Drm::open_existing(Rc::new(f))),
+        Err(e) => Err(HeadlessBackendError::OpenDrmNode(
+            node.as_os_str().as_bytes().as_bstr().to_string(),
+            e.into(),
+        )),
+    }
+}
+
+struct HeadlessRender {
+    ctx: Rc<RenderContext>,
+    gbm: Option<GbmDevice>,
+}
+
+pub struct HeadlessBackend {
+    state: Rc<State>,
+    render: Rc<HeadlessRender>,
+    initial_modes: Vec<Mode>,
+    next_idx: NumCell<u32>,
+    outputs: CopyHashMap<ConnectorId, Rc<HeadlessOutput>>,
+}
+
+impl HeadlessBackend {
+    async fn run(self: Rc<Self>) -> Result<(), HeadlessBackendError> {
+        self.state.set_render_ctx(Some(&self.render.ctx));
+        for mode in &self.initial_modes {
+            self.add_output(*mode)?;
+        }
+        self.state
+            .backend_events
+            .push(BackendEvent::DevicesEnumerated);
+        pending().await
+    }
+
+    fn add_output(&self, mode: Mode) -> Result<Rc<HeadlessOutput>, HeadlessBackendError> {
+        let timer = match TimerFd::new(c::CLOCK_MONOTONIC) {
+            Ok(t) => t,
+            Err(e) => return Err(HeadlessBackendError::CreateTimer(e)),
+        };
+        let buffer = self.render.create_buffer(mode)?;
+        let output = Rc::new(HeadlessOutput {
+            id: self.state.connector_ids.next(),
+            idx: self.next_idx.fetch_add(1),
+            state: self.state.clone(),
+            mode,
+            events: Default::default(),
+            cb: Default::default(),
+            enabled: Cell::new(true),
+            damaged: Cell::new(true),
+            render_pending: Default::default(),
+            sequence: NumCell::new(0),
+            buffer,
+            render_result: Default::default(),
+            frame_task: Default::default(),
+        });
+        let task = self
+            .state
+            .eng
+            .spawn2(Phase::Present, output.clone().frame_loop(timer));
+        output.frame_task.set(Some(task));
+        output.render_pending.trigger();
+        self.outputs.set(output.id, output.clone());
+        self.state
+            .backend_events
+            .push(BackendEvent::NewConnector(output.clone()));
+        output.connect();
+        Ok(output)
+    }
+}
+
+impl Backend for HeadlessBackend {
+    fn run(self: Rc<Self>) -> SpawnedFuture<Result<(), Box<dyn Error>>> {
+        let slf = self.clone();
+        self.state.eng.spawn(async move {
+            slf.run().await?;
+            Ok(())
+        })
+    }
+
+    fn into_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn supports_presentation_feedback(&self) -> bool {
+        true
+    }
+
+    fn create_virtual_output(&self, mode: Mode) -> Option<ConnectorId> {
+        match self.add_output(mode) {
+            Ok(o) => Some(o.id),
+            Err(e) => {
+                log::error!("Could not create a virtual output: {}", ErrorFmt(e));
+                None
+            }
+        }
+    }
+
+    fn remove_virtual_output(&self, id: ConnectorId) -> bool {
+        match self.outputs.remove(&id) {
+            Some(output) => {
+                output.remove();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl HeadlessRender {
+    fn create_buffer(&self, mode: Mode) -> Result<HeadlessBuffer, HeadlessBackendError> {
+        let img = match &self.gbm {
+            Some(gbm) => {
+                let format = ModifiedFormat {
+                    format: XRGB8888,
+                    modifier: INVALID_MODIFIER,
+                };
+                let bo = gbm.create_bo(mode.width, mode.height, &format, GBM_BO_USE_RENDERING)?;
+                self.ctx.dmabuf_img(bo.dmabuf())
+            }
+            _ => self.ctx.cpu_img(mode.width, mode.height),
+        };
+        let img = match img {
+            Ok(i) => i,
+            Err(e) => return Err(HeadlessBackendError::CreateImage(e)),
+        };
+        let fb = match img.to_framebuffer() {
+            Ok(f) => f,
+            Err(e) => return Err(HeadlessBackendError::CreateFramebuffer(e)),
+        };
+        let tex = match img.to_texture() {
+            Ok(t) => t,
+            Err(e) => return Err(HeadlessBackendError::CreateTexture(e)),
+        };
+        Ok(HeadlessBuffer { fb, tex })
+    }
+}
+
+struct HeadlessBuffer {
+    fb: Rc<Framebuffer>,
+    tex: Rc<Texture>,
+}
+
+struct HeadlessOutput {
+    id: ConnectorId,
+    idx: u32,
+    state: Rc<State>,
+    mode: Mode,
+    events: SyncQueue<ConnectorEvent>,
+    cb: CloneCell<Option<Rc<dyn Fn()>>>,
+    enabled: Cell<bool>,
+    damaged: Cell<bool>,
+    render_pending: AsyncEvent,
+    sequence: NumCell<u64>,
+    buffer: HeadlessBuffer,
+    render_result: RefCell<RenderResult>,
+    frame_task: Cell<Option<SpawnedFuture<()>>>,
+}
+
+impl HeadlessOutput {
+    fn changed(&self) {
+        if let Some(cb) = self.cb.get() {
+            cb();
+        }
+    }
+
+    fn connect(&self) {
+        self.events.push(ConnectorEvent::Connected(MonitorInfo {
+            modes: vec![self.mode],
+            manufacturer: "jay".to_string(),
+            product: "Headless".to_string(),
+            serial_number: self.idx.to_string(),
+            initial_mode: self.mode,
+            width_mm: 0,
+            height_mm: 0,
+        }));
+        self.changed();
+    }
+
+    fn remove(&self) {
+        self.frame_task.take();
+        if self.enabled.get() {
+            self.events.push(ConnectorEvent::Disconnected);
+        }
+        self.events.push(ConnectorEvent::Removed);
+        self.changed();
+    }
+
+    fn refresh_nsec(&self) -> u64 {
+        1_000_000_000_000 / self.mode.refresh_rate_millihz as u64
+    }
+
+    /// Presents damaged frames at most once per refresh period.
+    ///
+    /// The timer is only armed while a frame is pending so that idle outputs don't wake
+    /// up the compositor.
+    async fn frame_loop(self: Rc<Self>, timer: TimerFd) {
+        let period = Duration::from_nanos(self.refresh_nsec());
+        let mut next_frame = Time::now_unchecked();
+        loop {
+            self.render_pending.triggered().await;
+            if !self.enabled.get() || !self.damaged.get() {
+                continue;
+            }
+            let now = Time::now_unchecked();
+            if next_frame > now {
+                if let Err(e) = timer.program(Some(next_frame - now), None) {
+                    log::error!("Could not program the frame timer: {}", ErrorFmt(e));
+                    return;
+                }
+                if let Err(e) = timer.expired(&self.state.ring).await {
+                    log::error!("Could not wait for the frame timer: {}", ErrorFmt(e));
+                    return;
+                }
+            }
+            if self.enabled.get() && self.damaged.replace(false) {
+                self.present();
+                next_frame = Time::now_unchecked() + period;
+            }
+        }
+    }
+
+    fn present(&self) {
+        let node = match self.state.root.outputs.get(&self.id) {
+            Some(n) => n,
+            _ => return,
+        };
+        let mut rr = self.render_result.borrow_mut();
+        self.buffer.fb.render(
+            &*node,
+            &self.state,
+            Some(node.global.pos.get()),
+            true,
+            rr.deref_mut(),
+            node.preferred_scale.get(),
+            true,
+        );
+        node.perform_screencopies(&self.buffer.fb, &self.buffer.tex);
+        for fr in rr.frame_requests.drain(..) {
+            fr.send_done();
+            let _ = fr.client.remove_obj(&*fr);
+        }
+        let now = Time::now_unchecked();
+        let sequence = self.sequence.fetch_add(1);
+        let refresh = self.refresh_nsec() as u32;
+        let bindings = node.global.bindings.borrow_mut();
+        for fb in rr.presentation_feedbacks.drain(..) {
+            if let Some(bindings) = bindings.get(&fb.client.id) {
+                for binding in bindings.values() {
+                    fb.send_sync_output(binding);
+                }
+            }
+            fb.send_presented(
+                now.0.tv_sec as _,
+                now.0.tv_nsec as _,
+                refresh,
+                sequence,
+                // Frames are paced by a timer and are not synchronized to any display.
+                0,
+            );
+            let _ = fb.client.remove_obj(&*fb);
+        }
+    }
+}
+
+impl Connector for HeadlessOutput {
+    fn id(&self) -> ConnectorId {
+        self.id
+    }
+
+    fn kernel_id(&self) -> ConnectorKernelId {
+        ConnectorKernelId {
+            ty: ConnectorType::VIRTUAL,
+            idx: self.idx,
+        }
+    }
+
+    fn event(&self) -> Option<ConnectorEvent> {
+        self.events.pop()
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        self.cb.set(Some(cb));
+    }
+
+    fn damage(&self) {
+        self.damaged.set(true);
+        self.render_pending.trigger();
+    }
+
+    fn drm_dev(&self) -> Option<DrmDeviceId> {
+        None
+    }
+
+    fn set_enabled(&self, enabled: bool) {
+        if self.enabled.replace(enabled) == enabled {
+            return;
+        }
+        if enabled {
+            self.damaged.set(true);
+            self.render_pending.trigger();
+            self.connect();
+        } else {
+            self.events.push(ConnectorEvent::Disconnected);
+            self.changed();
+        }
+    }
+}
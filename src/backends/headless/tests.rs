@@ -0,0 +1,65 @@
+use crate::backends::headless::{parse_mode, Mode};
+
+fn mode(width: i32, height: i32, refresh_rate_millihz: u32) -> Mode {
+    Mode {
+        width,
+        height,
+        refresh_rate_millihz,
+    }
+}
+
+#[test]
+fn valid() {
+    assert_eq!(parse_mode("800x600").unwrap(), mode(800, 600, 60_000));
+    assert_eq!(parse_mode("800x600@144").unwrap(), mode(800, 600, 144_000));
+    assert_eq!(parse_mode("800x600@59.94").unwrap(), mode(800, 600, 59_940));
+    assert_eq!(
+        parse_mode(" 800 x 600 @ 30 ").unwrap(),
+        mode(800, 600, 30_000)
+    );
+}
+
+#[test]
+fn bad_separators() {
+    for s in [
+        "800",
+        "800*600",
+        "800x600x400",
+        "800,600@60",
+        "800x600@60@60",
+        "x600",
+        "800x",
+    ] {
+        assert!(parse_mode(s).is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn bad_sizes() {
+    for s in [
+        "0x600",
+        "800x0",
+        "-800x600",
+        "800x-600",
+        "2147483648x600",
+        "800x99999999999",
+    ] {
+        assert!(parse_mode(s).is_err(), "{}", s);
+    }
+}
+
+#[test]
+fn bad_refresh_rates() {
+    for s in [
+        "800x600@",
+        "800x600@0",
+        "800x600@0.0001",
+        "800x600@-60",
+        "800x600@NaN",
+        "800x600@inf",
+        "800x600@5000000",
+        "800x600@abc",
+    ] {
+        assert!(parse_mode(s).is_err(), "{}", s);
+    }
+}
@@ -1618,7 +1618,11 @@ impl MetalBackend {
         } else {
             // Create a _bridge_ BO in the render device
             usage = GBM_BO_USE_RENDERING | GBM_BO_USE_LINEAR;
-            let render_bo = render_ctx.egl.gbm.create_bo(width, height, format, usage);
+            let render_gbm = match render_ctx.egl.gbm() {
+                Ok(gbm) => gbm,
+                Err(e) => return Err(MetalError::ImportImage(e)),
+            };
+            let render_bo = render_gbm.create_bo(width, height, format, usage);
             let render_bo = match render_bo {
                 Ok(b) => b,
                 Err(e) => return Err(MetalError::ScanoutBuffer(e)),
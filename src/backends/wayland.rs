@@ -0,0 +1,1217 @@
+#[cfg(test)]
+mod tests;
+
+use {
+    crate::{
+        async_engine::{Phase, SpawnedFuture},
+        backend::{
+            AxisSource, Backend, BackendEvent, Connector, ConnectorEvent, ConnectorId,
+            ConnectorKernelId, DrmDeviceId, InputDevice, InputDeviceAccelProfile,
+            InputDeviceCapability, InputDeviceId, InputEvent, KeyState, Mode, MonitorInfo,
+            ScrollAxis, TransformMatrix,
+        },
+        backends::headless::{self, HeadlessBackendError},
+        fixed::Fixed,
+        format::XRGB8888,
+        ifs::wl_seat::{
+            wl_pointer::{PendingScroll, CONTINUOUS, FINGER},
+            KEYBOARD, POINTER,
+        },
+        render::{Framebuffer, RenderContext, RenderError, RenderResult, Texture},
+        state::State,
+        time::now_usec,
+        utils::{
+            asyncevent::AsyncEvent,
+            bitflags::BitflagsExt,
+            clonecell::CloneCell,
+            copyhashmap::CopyHashMap,
+            errorfmt::ErrorFmt,
+            mmap::{mmap, Mmapped},
+            numcell::NumCell,
+            oserror::OsError,
+            syncqueue::SyncQueue,
+        },
+        video::{
+            drm::ConnectorType,
+            gbm::{GbmDevice, GbmError, GBM_BO_USE_RENDERING},
+            ModifiedFormat, INVALID_MODIFIER,
+        },
+        wire::{
+            wl_keyboard, wl_pointer, WlCompositor, WlSeat, WlShm, WlSurfaceId, XdgWmBase,
+            ZwpLinuxBufferParamsV1Id, ZwpLinuxDmabufV1,
+        },
+        wl_usr::{
+            usr_ifs::{
+                usr_linux_buffer_params::{UsrLinuxBufferParams, UsrLinuxBufferParamsOwner},
+                usr_linux_dmabuf::UsrLinuxDmabuf,
+                usr_wl_buffer::{UsrWlBuffer, UsrWlBufferOwner},
+                usr_wl_compositor::UsrWlCompositor,
+                usr_wl_keyboard::{UsrWlKeyboard, UsrWlKeyboardOwner},
+                usr_wl_pointer::{UsrWlPointer, UsrWlPointerOwner},
+                usr_wl_registry::{UsrWlRegistry, UsrWlRegistryOwner},
+                usr_wl_seat::{UsrWlSeat, UsrWlSeatOwner},
+                usr_wl_shm::UsrWlShm,
+                usr_wl_surface::UsrWlSurface,
+                usr_xdg_surface::{UsrXdgSurface, UsrXdgSurfaceOwner},
+                usr_xdg_toplevel::{UsrXdgToplevel, UsrXdgToplevelOwner},
+                usr_xdg_wm_base::UsrXdgWmBase,
+            },
+            UsrCon, UsrConError, UsrConOwner,
+        },
+    },
+    std::{
+        any::Any,
+        cell::{Cell, RefCell},
+        collections::VecDeque,
+        error::Error,
+        future::pending,
+        ops::{Deref, DerefMut},
+        rc::{Rc, Weak},
+    },
+    thiserror::Error,
+    uapi::c,
+};
+
+const DEFAULT_WIDTH: i32 = 800;
+const DEFAULT_HEIGHT: i32 = 600;
+const REFRESH_RATE_MILLIHZ: u32 = 60_000;
+const NUM_BUFFERS: usize = 2;
+
+#[derive(Debug, Error)]
+pub enum WaylandBackendError {
+    #[error("WAYLAND_DISPLAY is not set")]
+    WaylandDisplayNotSet,
+    #[error("XDG_RUNTIME_DIR is not set")]
+    XrdNotSet,
+    #[error("Could not connect to the parent compositor")]
+    Connect(#[source] UsrConError),
+    #[error("The connection to the parent compositor was lost")]
+    ConnectionLost,
+    #[error("The parent compositor does not support {0}")]
+    MissingGlobal(&'static str),
+    #[error("Could not open a render node")]
+    RenderNode(#[source] HeadlessBackendError),
+    #[error("Could not create a render context")]
+    RenderContext(#[source] RenderError),
+    #[error("The gbm subsystem returned an error")]
+    GbmError(#[from] GbmError),
+    #[error("Could not create an image")]
+    CreateImage(#[source] RenderError),
+    #[error("Could not create a framebuffer from an image")]
+    CreateFramebuffer(#[source] RenderError),
+    #[error("Could not create a texture from an image")]
+    CreateTexture(#[source] RenderError),
+    #[error("Could not create a memfd")]
+    CreateMemfd(#[source] OsError),
+    #[error("Could not resize a memfd")]
+    ResizeMemfd(#[source] OsError),
+    #[error("Could not map a memfd")]
+    MapMemfd(#[source] OsError),
+}
+
+pub async fn create(state: &Rc<State>) -> Result<Rc<WaylandBackend>, WaylandBackendError> {
+    let path = socket_path()?;
+    let con = match UsrCon::new(&state.ring, &state.wheel, &state.eng, &path, 0).await {
+        Ok(c) => c,
+        Err(e) => return Err(WaylandBackendError::Connect(e)),
+    };
+    let registry = con.get_registry();
+    let prelude = Rc::new(WaylandPrelude {
+        globals: Default::default(),
+        done: Default::default(),
+        killed: Cell::new(false),
+    });
+    registry.owner.set(Some(prelude.clone()));
+    con.owner.set(Some(prelude.clone()));
+    con.sync({
+        let prelude = prelude.clone();
+        move || prelude.done.trigger()
+    });
+    prelude.done.triggered().await;
+    if prelude.killed.get() {
+        return Err(WaylandBackendError::ConnectionLost);
+    }
+    registry.owner.take();
+    con.owner.take();
+    let globals = prelude.globals.take();
+    let find = |interface: &str| {
+        globals
+            .iter()
+            .find(|g| g.interface == interface)
+            .map(|g| (g.name, g.version))
+    };
+    macro_rules! bind {
+        ($iface:expr, $max:expr, $obj:expr) => {{
+            match find($iface.name()) {
+                Some((name, version)) => {
+                    let obj = Rc::new($obj);
+                    con.add_object(obj.clone());
+                    registry.request_bind(name, version.min($max), obj.deref());
+                    Some(obj)
+                }
+                _ => None,
+            }
+        }};
+    }
+    let comp = bind!(
+        WlCompositor,
+        4,
+        UsrWlCompositor {
+            id: con.id(),
+            con: con.clone(),
+        }
+    );
+    let xdg = bind!(
+        XdgWmBase,
+        1,
+        UsrXdgWmBase {
+            id: con.id(),
+            con: con.clone(),
+        }
+    );
+    let shm = bind!(
+        WlShm,
+        1,
+        UsrWlShm {
+            id: con.id(),
+            con: con.clone(),
+            formats: Default::default(),
+        }
+    );
+    let dmabuf = bind!(
+        ZwpLinuxDmabufV1,
+        3,
+        UsrLinuxDmabuf {
+            id: con.id(),
+            con: con.clone(),
+            owner: Default::default(),
+        }
+    );
+    let comp = comp.ok_or(WaylandBackendError::MissingGlobal("wl_compositor"))?;
+    let xdg = xdg.ok_or(WaylandBackendError::MissingGlobal("xdg_wm_base"))?;
+    let shm = shm.ok_or(WaylandBackendError::MissingGlobal("wl_shm"))?;
+    let mut ctx = None;
+    if dmabuf.is_some() {
+        match create_gpu_render() {
+            Ok(r) => ctx = Some(r),
+            Err(e) => log::warn!(
+                "Could not create a GPU render context, using the software renderer: {}",
+                ErrorFmt(e)
+            ),
+        }
+    }
+    let (ctx, gbm) = match ctx {
+        Some((ctx, gbm)) => (ctx, Some(gbm)),
+        _ => (Rc::new(RenderContext::software()), None),
+    };
+    let use_dmabuf = dmabuf.is_some() && gbm.is_some();
+    Ok(Rc::new_cyclic(|slf| WaylandBackend {
+        slf: slf.clone(),
+        state: state.clone(),
+        con,
+        registry,
+        initial_globals: RefCell::new(globals),
+        comp,
+        xdg,
+        shm,
+        dmabuf,
+        use_dmabuf: Cell::new(use_dmabuf),
+        ctx,
+        gbm,
+        next_idx: NumCell::new(1),
+        outputs: Default::default(),
+        surfaces: Default::default(),
+        seats: Default::default(),
+    }))
+}
+
+fn socket_path() -> Result<String, WaylandBackendError> {
+    resolve_socket_path(
+        std::env::var("WAYLAND_DISPLAY").ok().as_deref(),
+        std::env::var("XDG_RUNTIME_DIR").ok().as_deref(),
+    )
+}
+
+/// Resolves the socket path from the values of `WAYLAND_DISPLAY` and `XDG_RUNTIME_DIR`.
+fn resolve_socket_path(
+    display: Option<&str>,
+    xrd: Option<&str>,
+) -> Result<String, WaylandBackendError> {
+    let display = match display {
+        Some(d) => d,
+        _ => return Err(WaylandBackendError::WaylandDisplayNotSet),
+    };
+    if display.starts_with('/') {
+        return Ok(display.to_string());
+    }
+    match xrd {
+        Some(xrd) => Ok(format!("{}/{}", xrd, display)),
+        _ => Err(WaylandBackendError::XrdNotSet),
+    }
+}
+
+/// Returns the mode of an output whose window has the given size.
+///
+/// The parent compositor does not tell us its refresh rate, so a fixed rate is reported.
+fn window_mode(width: i32, height: i32) -> Mode {
+    Mode {
+        width,
+        height,
+        refresh_rate_millihz: REFRESH_RATE_MILLIHZ,
+    }
+}
+
+/// Returns the size requested by a toplevel configure event.
+///
+/// A size of 0 means that the parent compositor leaves the size to us. In that case the
+/// current size is kept.
+fn configured_size(width: i32, height: i32) -> Option<(i32, i32)> {
+    (width > 0 && height > 0).then_some((width, height))
+}
+
+fn axis_source(source: u32) -> AxisSource {
+    match source {
+        FINGER => AxisSource::Finger,
+        CONTINUOUS => AxisSource::Continuous,
+        _ => AxisSource::Wheel,
+    }
+}
+
+fn button_state(state: u32) -> KeyState {
+    match state {
+        0 => KeyState::Released,
+        _ => KeyState::Pressed,
+    }
+}
+
+/// Updates the list of pressed keys with a key event of the parent compositor.
+///
+/// Returns `None` if the event does not change the state of the key.
+fn key_state(pressed: &mut Vec<u32>, key: u32, state: u32) -> Option<KeyState> {
+    let idx = pressed.iter().position(|k| *k == key);
+    match (state, idx) {
+        (0, Some(idx)) => {
+            pressed.swap_remove(idx);
+            Some(KeyState::Released)
+        }
+        (0, None) => None,
+        (_, None) => {
+            pressed.push(key);
+            Some(KeyState::Pressed)
+        }
+        (_, Some(_)) => None,
+    }
+}
+
+/// Translates a scroll frame of the parent compositor into input events.
+fn scroll_events(ps: &PendingScroll, time_usec: u64) -> Vec<InputEvent> {
+    let mut events = vec![];
+    if let Some(source) = ps.source.get() {
+        events.push(InputEvent::AxisSource {
+            source: axis_source(source),
+        });
+    }
+    for (idx, axis) in [ScrollAxis::Vertical, ScrollAxis::Horizontal]
+        .into_iter()
+        .enumerate()
+    {
+        if let Some(dist) = ps.v120[idx].get() {
+            events.push(InputEvent::Axis120 { dist, axis });
+        }
+        if let Some(dist) = ps.px[idx].get() {
+            events.push(InputEvent::AxisPx { dist, axis });
+        }
+        if ps.stop[idx].get() {
+            events.push(InputEvent::AxisStop { axis });
+        }
+    }
+    events.push(InputEvent::AxisFrame { time_usec });
+    events
+}
+
+fn create_gpu_render() -> Result<(Rc<RenderContext>, GbmDevice), WaylandBackendError> {
+    let drm = match headless::open_render_node() {
+        Ok(d) => d,
+        Err(e) => return Err(WaylandBackendError::RenderNode(e)),
+    };
+    let ctx = match RenderContext::from_drm_device(&drm) {
+        Ok(ctx) => Rc::new(ctx),
+        Err(e) => return Err(WaylandBackendError::RenderContext(e)),
+    };
+    let gbm = GbmDevice::new(&drm)?;
+    Ok((ctx, gbm))
+}
+
+struct WaylandGlobal {
+    name: u32,
+    interface: String,
+    version: u32,
+}
+
+struct WaylandPrelude {
+    globals: RefCell<Vec<WaylandGlobal>>,
+    done: AsyncEvent,
+    killed: Cell<bool>,
+}
+
+impl UsrWlRegistryOwner for WaylandPrelude {
+    fn global(self: Rc<Self>, name: u32, interface: &str, version: u32) {
+        self.globals.borrow_mut().push(WaylandGlobal {
+            name,
+            interface: interface.to_string(),
+            version,
+        });
+    }
+}
+
+impl UsrConOwner for WaylandPrelude {
+    fn killed(&self) {
+        self.killed.set(true);
+        self.done.trigger();
+    }
+}
+
+pub struct WaylandBackend {
+    slf: Weak<Self>,
+    state: Rc<State>,
+    con: Rc<UsrCon>,
+    registry: Rc<UsrWlRegistry>,
+    initial_globals: RefCell<Vec<WaylandGlobal>>,
+    comp: Rc<UsrWlCompositor>,
+    xdg: Rc<UsrXdgWmBase>,
+    shm: Rc<UsrWlShm>,
+    dmabuf: Option<Rc<UsrLinuxDmabuf>>,
+    use_dmabuf: Cell<bool>,
+    ctx: Rc<RenderContext>,
+    gbm: Option<GbmDevice>,
+    next_idx: NumCell<u32>,
+    outputs: CopyHashMap<ConnectorId, Rc<WaylandOutput>>,
+    surfaces: CopyHashMap<WlSurfaceId, Rc<WaylandOutput>>,
+    seats: CopyHashMap<u32, Rc<WaylandSeat>>,
+}
+
+impl WaylandBackend {
+    async fn run(self: Rc<Self>) -> Result<(), WaylandBackendError> {
+        self.con.owner.set(Some(self.clone()));
+        self.registry.owner.set(Some(self.clone()));
+        self.state.set_render_ctx(Some(&self.ctx));
+        let globals = self.initial_globals.take();
+        for global in globals {
+            self.clone()
+                .global(global.name, &global.interface, global.version);
+        }
+        self.add_output(DEFAULT_WIDTH, DEFAULT_HEIGHT);
+        self.state
+            .backend_events
+            .push(BackendEvent::DevicesEnumerated);
+        pending().await
+    }
+
+    fn add_output(self: &Rc<Self>, width: i32, height: i32) -> Rc<WaylandOutput> {
+        let surface = self.comp.create_surface();
+        let xdg = self.xdg.get_xdg_surface(&surface);
+        let toplevel = xdg.get_toplevel();
+        let output = Rc::new(WaylandOutput {
+            id: self.state.connector_ids.next(),
+            idx: self.next_idx.fetch_add(1),
+            backend: self.clone(),
+            surface,
+            xdg,
+            toplevel,
+            events: Default::default(),
+            cb: Default::default(),
+            width: Cell::new(width),
+            height: Cell::new(height),
+            pending_size: Cell::new(None),
+            configured: Cell::new(false),
+            enabled: Cell::new(true),
+            damaged: Cell::new(true),
+            have_frame: Cell::new(true),
+            buffers: Default::default(),
+            pending_buffers: Default::default(),
+            render_trigger: Default::default(),
+            render_task: Default::default(),
+            render_result: Default::default(),
+        });
+        output.xdg.owner.set(Some(output.clone()));
+        output.toplevel.owner.set(Some(output.clone()));
+        output.toplevel.set_title(&format!("Jay {}", output.idx));
+        output.toplevel.set_app_id("jay");
+        output.surface.commit();
+        output.render_task.set(Some(
+            self.state
+                .eng
+                .spawn2(Phase::Present, output.clone().render_task()),
+        ));
+        self.outputs.set(output.id, output.clone());
+        self.surfaces.set(output.surface.id, output.clone());
+        self.state
+            .backend_events
+            .push(BackendEvent::NewConnector(output.clone()));
+        output
+    }
+
+    fn add_seat(self: &Rc<Self>, name: u32, version: u32) {
+        let wl = Rc::new(UsrWlSeat {
+            id: self.con.id(),
+            con: self.con.clone(),
+            owner: Default::default(),
+        });
+        self.con.add_object(wl.clone());
+        self.registry.request_bind(name, version.min(8), wl.deref());
+        let seat = Rc::new(WaylandSeat {
+            kb_id: self.state.input_device_ids.next(),
+            mouse_id: self.state.input_device_ids.next(),
+            backend: self.clone(),
+            wl,
+            capabilities: Cell::new(0),
+            pointer: Default::default(),
+            keyboard: Default::default(),
+            pointer_focus: Default::default(),
+            pressed_keys: Default::default(),
+            removed: Cell::new(false),
+            kb_cb: Default::default(),
+            mouse_cb: Default::default(),
+            kb_events: Default::default(),
+            mouse_events: Default::default(),
+            kb_name: Rc::new(format!("wayland-kb{}", name)),
+            mouse_name: Rc::new(format!("wayland-mouse{}", name)),
+        });
+        seat.wl.owner.set(Some(seat.clone()));
+        self.seats.set(name, seat.clone());
+        self.state
+            .backend_events
+            .push(BackendEvent::NewInputDevice(Rc::new(WaylandSeatMouse(
+                seat.clone(),
+            ))));
+        self.state
+            .backend_events
+            .push(BackendEvent::NewInputDevice(Rc::new(WaylandSeatKeyboard(
+                seat,
+            ))));
+    }
+
+    fn create_buffer(
+        &self,
+        output: &Rc<WaylandOutput>,
+        width: i32,
+        height: i32,
+    ) -> Result<(), WaylandBackendError> {
+        let format = ModifiedFormat {
+            format: XRGB8888,
+            modifier: INVALID_MODIFIER,
+        };
+        let mut bo = None;
+        let img = match &self.gbm {
+            Some(gbm) => {
+                let b = gbm.create_bo(width, height, &format, GBM_BO_USE_RENDERING)?;
+                let img = self.ctx.dmabuf_img(b.dmabuf());
+                bo = Some(b);
+                img
+            }
+            _ => self.ctx.cpu_img(width, height),
+        };
+        let img = match img {
+            Ok(i) => i,
+            Err(e) => return Err(WaylandBackendError::CreateImage(e)),
+        };
+        let fb = match img.to_framebuffer() {
+            Ok(f) => f,
+            Err(e) => return Err(WaylandBackendError::CreateFramebuffer(e)),
+        };
+        let tex = match img.to_texture() {
+            Ok(t) => t,
+            Err(e) => return Err(WaylandBackendError::CreateTexture(e)),
+        };
+        if let (Some(dmabuf), Some(bo), true) = (&self.dmabuf, &bo, self.use_dmabuf.get()) {
+            let params = dmabuf.create_params();
+            params.create(bo.dmabuf());
+            let pending = Rc::new(WaylandPendingBuffer {
+                output: output.clone(),
+                fb,
+                tex,
+                params,
+                size: (width, height),
+            });
+            pending.params.owner.set(Some(pending.clone()));
+            output
+                .pending_buffers
+                .set(pending.params.id, pending.clone());
+            return Ok(());
+        }
+        let stride = width * 4;
+        let size = stride * height;
+        let fd = match uapi::memfd_create("jay-wayland-backend", c::MFD_CLOEXEC) {
+            Ok(fd) => Rc::new(fd),
+            Err(e) => return Err(WaylandBackendError::CreateMemfd(e.into())),
+        };
+        if let Err(e) = uapi::ftruncate(fd.raw(), size as _) {
+            return Err(WaylandBackendError::ResizeMemfd(e.into()));
+        }
+        let mem = match mmap(
+            size as _,
+            c::PROT_READ | c::PROT_WRITE,
+            c::MAP_SHARED,
+            fd.raw(),
+            0,
+        ) {
+            Ok(m) => m,
+            Err(e) => return Err(WaylandBackendError::MapMemfd(e)),
+        };
+        let pool = self.shm.create_pool(&fd, size);
+        let wl = pool.create_buffer(
+            0,
+            width,
+            height,
+            stride,
+            XRGB8888.wl_id.unwrap_or(XRGB8888.drm),
+        );
+        self.con.remove_obj(pool.deref());
+        output.add_buffer(wl, fb, tex, Some(mem), (width, height));
+        Ok(())
+    }
+}
+
+impl Backend for WaylandBackend {
+    fn run(self: Rc<Self>) -> SpawnedFuture<Result<(), Box<dyn Error>>> {
+        let slf = self.clone();
+        self.state.eng.spawn(async move {
+            slf.run().await?;
+            Ok(())
+        })
+    }
+
+    fn into_any(self: Rc<Self>) -> Rc<dyn Any> {
+        self
+    }
+
+    fn create_virtual_output(&self, mode: Mode) -> Option<ConnectorId> {
+        let slf = self.slf.upgrade()?;
+        Some(slf.add_output(mode.width, mode.height).id)
+    }
+
+    fn remove_virtual_output(&self, id: ConnectorId) -> bool {
+        match self.outputs.get(&id) {
+            Some(output) => {
+                output.remove();
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
+impl UsrConOwner for WaylandBackend {
+    fn killed(&self) {
+        log::error!("The connection to the parent compositor was lost");
+        self.state.ring.stop();
+    }
+}
+
+impl UsrWlRegistryOwner for WaylandBackend {
+    fn global(self: Rc<Self>, name: u32, interface: &str, version: u32) {
+        if interface == WlSeat.name() {
+            self.add_seat(name, version);
+        }
+    }
+
+    fn global_remove(&self, name: u32) {
+        if let Some(seat) = self.seats.remove(&name) {
+            seat.remove();
+        }
+    }
+}
+
+struct WaylandOutput {
+    id: ConnectorId,
+    idx: u32,
+    backend: Rc<WaylandBackend>,
+    surface: Rc<UsrWlSurface>,
+    xdg: Rc<UsrXdgSurface>,
+    toplevel: Rc<UsrXdgToplevel>,
+    events: SyncQueue<ConnectorEvent>,
+    cb: CloneCell<Option<Rc<dyn Fn()>>>,
+    width: Cell<i32>,
+    height: Cell<i32>,
+    pending_size: Cell<Option<(i32, i32)>>,
+    configured: Cell<bool>,
+    enabled: Cell<bool>,
+    damaged: Cell<bool>,
+    have_frame: Cell<bool>,
+    buffers: RefCell<Vec<Rc<WaylandBuffer>>>,
+    pending_buffers: CopyHashMap<ZwpLinuxBufferParamsV1Id, Rc<WaylandPendingBuffer>>,
+    render_trigger: AsyncEvent,
+    render_task: Cell<Option<SpawnedFuture<()>>>,
+    render_result: RefCell<RenderResult>,
+}
+
+impl WaylandOutput {
+    fn changed(&self) {
+        if let Some(cb) = self.cb.get() {
+            cb();
+        }
+    }
+
+    fn mode(&self) -> Mode {
+        window_mode(self.width.get(), self.height.get())
+    }
+
+    fn connect(&self) {
+        self.events.push(ConnectorEvent::Connected(MonitorInfo {
+            modes: vec![],
+            manufacturer: "jay".to_string(),
+            product: format!("Wayland-Window-{}", self.idx),
+            serial_number: self.idx.to_string(),
+            initial_mode: self.mode(),
+            width_mm: 0,
+            height_mm: 0,
+        }));
+        self.changed();
+    }
+
+    fn schedule_render(&self) {
+        self.render_trigger.trigger();
+    }
+
+    async fn render_task(self: Rc<Self>) {
+        loop {
+            self.render_trigger.triggered().await;
+            self.render();
+        }
+    }
+
+    fn render(self: &Rc<Self>) {
+        if !self.configured.get() || !self.enabled.get() || !self.damaged.get() {
+            return;
+        }
+        if !self.have_frame.get() {
+            return;
+        }
+        let node = match self.backend.state.root.outputs.get(&self.id) {
+            Some(n) => n,
+            _ => return,
+        };
+        let buf = {
+            let bufs = self.buffers.borrow();
+            match bufs.iter().find(|b| b.free.get()) {
+                Some(b) => b.clone(),
+                _ => return,
+            }
+        };
+        self.damaged.set(false);
+        self.have_frame.set(false);
+        self.surface.frame({
+            let slf = self.clone();
+            move || {
+                slf.have_frame.set(true);
+                if slf.damaged.get() {
+                    slf.schedule_render();
+                }
+            }
+        });
+        let mut rr = self.render_result.borrow_mut();
+        buf.fb.render(
+            &*node,
+            &self.backend.state,
+            Some(node.global.pos.get()),
+            true,
+            rr.deref_mut(),
+            node.preferred_scale.get(),
+            true,
+        );
+        node.perform_screencopies(&buf.fb, &buf.tex);
+        for fr in rr.frame_requests.drain(..) {
+            fr.send_done();
+            let _ = fr.client.remove_obj(&*fr);
+        }
+        if let Some(mem) = &buf.mem {
+            let (width, height) = buf.size;
+            let mem = unsafe { &*(mem.ptr as *const [Cell<u8>]) };
+            buf.fb.copy_to_shm(0, 0, width, height, XRGB8888, mem);
+        }
+        buf.free.set(false);
+        self.surface.attach(&buf.wl);
+        self.surface.damage_buffer(0, 0, buf.size.0, buf.size.1);
+        self.surface.commit();
+    }
+
+    fn add_buffer(
+        self: &Rc<Self>,
+        wl: Rc<UsrWlBuffer>,
+        fb: Rc<Framebuffer>,
+        tex: Rc<Texture>,
+        mem: Option<Mmapped>,
+        size: (i32, i32),
+    ) {
+        let buf = Rc::new(WaylandBuffer {
+            output: self.clone(),
+            wl,
+            fb,
+            tex,
+            mem,
+            free: Cell::new(true),
+            size,
+        });
+        buf.wl.owner.set(Some(buf.clone()));
+        self.buffers.borrow_mut().push(buf);
+        self.schedule_render();
+    }
+
+    fn clear_buffers(&self) {
+        let con = &self.backend.con;
+        for (_, pb) in self.pending_buffers.lock().drain() {
+            con.remove_obj(pb.params.deref());
+        }
+        for buf in self.buffers.borrow_mut().drain(..) {
+            con.remove_obj(buf.wl.deref());
+        }
+    }
+
+    fn allocate_buffers(self: &Rc<Self>) {
+        self.clear_buffers();
+        let (width, height) = (self.width.get(), self.height.get());
+        for _ in 0..NUM_BUFFERS {
+            if let Err(e) = self.backend.create_buffer(self, width, height) {
+                log::error!("Could not allocate a buffer: {}", ErrorFmt(e));
+                return;
+            }
+        }
+    }
+
+    fn remove(&self) {
+        let backend = &self.backend;
+        backend.outputs.remove(&self.id);
+        backend.surfaces.remove(&self.surface.id);
+        self.render_task.take();
+        self.clear_buffers();
+        backend.con.remove_obj(self.toplevel.deref());
+        backend.con.remove_obj(self.xdg.deref());
+        backend.con.remove_obj(self.surface.deref());
+        if self.configured.get() && self.enabled.get() {
+            self.events.push(ConnectorEvent::Disconnected);
+        }
+        self.events.push(ConnectorEvent::Removed);
+        self.changed();
+    }
+}
+
+impl UsrXdgToplevelOwner for WaylandOutput {
+    fn configure(&self, width: i32, height: i32) {
+        if let Some(size) = configured_size(width, height) {
+            self.pending_size.set(Some(size));
+        }
+    }
+
+    fn close(&self) {
+        self.remove();
+        if self.backend.outputs.is_empty() {
+            log::info!("The last window was closed");
+            self.backend.state.ring.stop();
+        }
+    }
+}
+
+impl UsrXdgSurfaceOwner for WaylandOutput {
+    fn configure(&self) {
+        let slf = match self.backend.outputs.get(&self.id) {
+            Some(o) => o,
+            _ => return,
+        };
+        let mut resized = false;
+        if let Some((width, height)) = self.pending_size.take() {
+            resized |= self.width.replace(width) != width;
+            resized |= self.height.replace(height) != height;
+        }
+        if !self.configured.replace(true) {
+            slf.allocate_buffers();
+            if self.enabled.get() {
+                self.connect();
+            }
+        } else if resized {
+            slf.allocate_buffers();
+            self.events.push(ConnectorEvent::ModeChanged(self.mode()));
+            self.changed();
+        }
+        self.damaged.set(true);
+        self.schedule_render();
+    }
+}
+
+impl Connector for WaylandOutput {
+    fn id(&self) -> ConnectorId {
+        self.id
+    }
+
+    fn kernel_id(&self) -> ConnectorKernelId {
+        ConnectorKernelId {
+            ty: ConnectorType::EmbeddedWindow,
+            idx: self.idx,
+        }
+    }
+
+    fn event(&self) -> Option<ConnectorEvent> {
+        self.events.pop()
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        self.cb.set(Some(cb));
+    }
+
+    fn damage(&self) {
+        self.damaged.set(true);
+        self.schedule_render();
+    }
+
+    fn drm_dev(&self) -> Option<DrmDeviceId> {
+        None
+    }
+
+    fn set_enabled(&self, enabled: bool) {
+        if self.enabled.replace(enabled) == enabled || !self.configured.get() {
+            return;
+        }
+        if enabled {
+            self.damaged.set(true);
+            self.connect();
+        } else {
+            self.events.push(ConnectorEvent::Disconnected);
+            self.changed();
+        }
+    }
+}
+
+struct WaylandBuffer {
+    output: Rc<WaylandOutput>,
+    wl: Rc<UsrWlBuffer>,
+    fb: Rc<Framebuffer>,
+    tex: Rc<Texture>,
+    mem: Option<Mmapped>,
+    free: Cell<bool>,
+    size: (i32, i32),
+}
+
+impl UsrWlBufferOwner for WaylandBuffer {
+    fn release(&self) {
+        self.free.set(true);
+        if self.output.damaged.get() {
+            self.output.schedule_render();
+        }
+    }
+}
+
+struct WaylandPendingBuffer {
+    output: Rc<WaylandOutput>,
+    fb: Rc<Framebuffer>,
+    tex: Rc<Texture>,
+    params: Rc<UsrLinuxBufferParams>,
+    size: (i32, i32),
+}
+
+impl UsrLinuxBufferParamsOwner for WaylandPendingBuffer {
+    fn created(&self, buffer: Rc<UsrWlBuffer>) {
+        let output = &self.output;
+        output.pending_buffers.remove(&self.params.id);
+        output.backend.con.remove_obj(self.params.deref());
+        output.add_buffer(buffer, self.fb.clone(), self.tex.clone(), None, self.size);
+    }
+
+    fn failed(&self) {
+        let output = &self.output;
+        output.pending_buffers.remove(&self.params.id);
+        output.backend.con.remove_obj(self.params.deref());
+        if output.backend.use_dmabuf.replace(false) {
+            log::warn!("The parent compositor could not import a dma-buf. Falling back to shm.");
+        }
+        output.allocate_buffers();
+    }
+}
+
+struct WaylandSeat {
+    kb_id: InputDeviceId,
+    mouse_id: InputDeviceId,
+    backend: Rc<WaylandBackend>,
+    wl: Rc<UsrWlSeat>,
+    capabilities: Cell<u32>,
+    pointer: CloneCell<Option<Rc<UsrWlPointer>>>,
+    keyboard: CloneCell<Option<Rc<UsrWlKeyboard>>>,
+    pointer_focus: CloneCell<Option<Rc<WaylandOutput>>>,
+    pressed_keys: RefCell<Vec<u32>>,
+    removed: Cell<bool>,
+    kb_cb: CloneCell<Option<Rc<dyn Fn()>>>,
+    mouse_cb: CloneCell<Option<Rc<dyn Fn()>>>,
+    kb_events: RefCell<VecDeque<InputEvent>>,
+    mouse_events: RefCell<VecDeque<InputEvent>>,
+    kb_name: Rc<String>,
+    mouse_name: Rc<String>,
+}
+
+struct WaylandSeatKeyboard(Rc<WaylandSeat>);
+
+struct WaylandSeatMouse(Rc<WaylandSeat>);
+
+impl WaylandSeat {
+    fn kb_changed(&self) {
+        if let Some(cb) = self.kb_cb.get() {
+            cb();
+        }
+    }
+
+    fn mouse_changed(&self) {
+        if let Some(cb) = self.mouse_cb.get() {
+            cb();
+        }
+    }
+
+    fn mouse_event(&self, event: InputEvent) {
+        self.mouse_events.borrow_mut().push_back(event);
+        self.mouse_changed();
+    }
+
+    fn kb_event(&self, event: InputEvent) {
+        self.kb_events.borrow_mut().push_back(event);
+        self.kb_changed();
+    }
+
+    fn release_keys(&self) {
+        let keys = std::mem::take(&mut *self.pressed_keys.borrow_mut());
+        for key in keys {
+            self.kb_event(InputEvent::Key {
+                time_usec: now_usec(),
+                key,
+                state: KeyState::Released,
+            });
+        }
+    }
+
+    fn position(&self, output: &WaylandOutput, x: Fixed, y: Fixed) {
+        self.mouse_event(InputEvent::ConnectorPosition {
+            time_usec: now_usec(),
+            connector: output.id,
+            x,
+            y,
+        });
+    }
+
+    fn remove(&self) {
+        self.release_keys();
+        let con = &self.backend.con;
+        if let Some(pointer) = self.pointer.take() {
+            con.remove_obj(pointer.deref());
+        }
+        if let Some(keyboard) = self.keyboard.take() {
+            con.remove_obj(keyboard.deref());
+        }
+        con.remove_obj(self.wl.deref());
+        self.pointer_focus.take();
+        self.removed.set(true);
+        self.kb_changed();
+        self.mouse_changed();
+    }
+}
+
+impl UsrWlSeatOwner for WaylandSeat {
+    fn capabilities(self: Rc<Self>, value: u32) {
+        let old = self.capabilities.replace(value);
+        let con = &self.backend.con;
+        if old.contains(POINTER) != value.contains(POINTER) {
+            if let Some(pointer) = self.pointer.take() {
+                con.remove_obj(pointer.deref());
+                self.pointer_focus.take();
+            }
+            if value.contains(POINTER) {
+                let pointer = self.wl.get_pointer();
+                pointer.owner.set(Some(self.clone()));
+                self.pointer.set(Some(pointer));
+            }
+        }
+        if old.contains(KEYBOARD) != value.contains(KEYBOARD) {
+            if let Some(keyboard) = self.keyboard.take() {
+                con.remove_obj(keyboard.deref());
+                self.release_keys();
+            }
+            if value.contains(KEYBOARD) {
+                let keyboard = self.wl.get_keyboard();
+                keyboard.owner.set(Some(self.clone()));
+                self.keyboard.set(Some(keyboard));
+            }
+        }
+    }
+}
+
+impl UsrWlPointerOwner for WaylandSeat {
+    fn enter(&self, ev: &wl_pointer::Enter) {
+        let output = match self.backend.surfaces.get(&ev.surface) {
+            Some(o) => o,
+            _ => return,
+        };
+        if let Some(pointer) = self.pointer.get() {
+            pointer.hide_cursor(ev.serial);
+        }
+        self.position(&output, ev.surface_x, ev.surface_y);
+        self.pointer_focus.set(Some(output));
+    }
+
+    fn leave(&self, _ev: &wl_pointer::Leave) {
+        self.pointer_focus.take();
+    }
+
+    fn motion(&self, ev: &wl_pointer::Motion) {
+        if let Some(output) = self.pointer_focus.get() {
+            self.position(&output, ev.surface_x, ev.surface_y);
+        }
+    }
+
+    fn button(&self, ev: &wl_pointer::Button) {
+        self.mouse_event(InputEvent::Button {
+            time_usec: now_usec(),
+            button: ev.button,
+            state: button_state(ev.state),
+        });
+    }
+
+    fn scroll(&self, ps: &PendingScroll) {
+        for event in scroll_events(ps, now_usec()) {
+            self.mouse_event(event);
+        }
+    }
+}
+
+impl UsrWlKeyboardOwner for WaylandSeat {
+    fn leave(&self, _ev: &wl_keyboard::Leave) {
+        self.release_keys();
+    }
+
+    fn key(&self, ev: &wl_keyboard::Key) {
+        let state = match key_state(&mut self.pressed_keys.borrow_mut(), ev.key, ev.state) {
+            Some(state) => state,
+            _ => return,
+        };
+        self.kb_event(InputEvent::Key {
+            time_usec: now_usec(),
+            key: ev.key,
+            state,
+        });
+    }
+}
+
+impl InputDevice for WaylandSeatKeyboard {
+    fn id(&self) -> InputDeviceId {
+        self.0.kb_id
+    }
+
+    fn removed(&self) -> bool {
+        self.0.removed.get()
+    }
+
+    fn event(&self) -> Option<InputEvent> {
+        self.0.kb_events.borrow_mut().pop_front()
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        self.0.kb_cb.set(Some(cb));
+    }
+
+    fn grab(&self, _grab: bool) {
+        log::error!("Cannot grab the keyboard of the parent compositor");
+    }
+
+    fn has_capability(&self, cap: InputDeviceCapability) -> bool {
+        match cap {
+            InputDeviceCapability::Keyboard => true,
+            _ => false,
+        }
+    }
+
+    fn set_left_handed(&self, left_handed: bool) {
+        let _ = left_handed;
+    }
+
+    fn set_accel_profile(&self, profile: InputDeviceAccelProfile) {
+        let _ = profile;
+    }
+
+    fn set_accel_speed(&self, speed: f64) {
+        let _ = speed;
+    }
+
+    fn set_transform_matrix(&self, matrix: TransformMatrix) {
+        let _ = matrix;
+    }
+
+    fn name(&self) -> Rc<String> {
+        self.0.kb_name.clone()
+    }
+
+    fn set_tap_enabled(&self, enabled: bool) {
+        let _ = enabled;
+    }
+
+    fn set_drag_enabled(&self, enabled: bool) {
+        let _ = enabled;
+    }
+
+    fn set_drag_lock_enabled(&self, enabled: bool) {
+        let _ = enabled;
+    }
+}
+
+impl InputDevice for WaylandSeatMouse {
+    fn id(&self) -> InputDeviceId {
+        self.0.mouse_id
+    }
+
+    fn removed(&self) -> bool {
+        self.0.removed.get()
+    }
+
+    fn event(&self) -> Option<InputEvent> {
+        self.0.mouse_events.borrow_mut().pop_front()
+    }
+
+    fn on_change(&self, cb: Rc<dyn Fn()>) {
+        self.0.mouse_cb.set(Some(cb));
+    }
+
+    fn grab(&self, _grab: bool) {
+        log::error!("Cannot grab the pointer of the parent compositor");
+    }
+
+    fn has_capability(&self, cap: InputDeviceCapability) -> bool {
+        match cap {
+            InputDeviceCapability::Pointer => true,
+            _ => false,
+        }
+    }
+
+    fn set_left_handed(&self, left_handed: bool) {
+        let _ = left_handed;
+    }
+
+    fn set_accel_profile(&self, profile: InputDeviceAccelProfile) {
+        let _ = profile;
+    }
+
+    fn set_accel_speed(&self, speed: f64) {
+        let _ = speed;
+    }
+
+    fn set_transform_matrix(&self, matrix: TransformMatrix) {
+        let _ = matrix;
+    }
+
+    fn name(&self) -> Rc<String> {
+        self.0.mouse_name.clone()
+    }
+
+    fn set_tap_enabled(&self, enabled: bool) {
+        let _ = enabled;
+    }
+
+    fn set_drag_enabled(&self, enabled: bool) {
+        let _ = enabled;
+    }
+
+    fn set_drag_lock_enabled(&self, enabled: bool) {
+        let _ = enabled;
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    backend::{AxisSource, InputEvent, KeyState, Mode, ScrollAxis},
+    backends::wayland::{
+        axis_source, button_state, configured_size, key_state, resolve_socket_path, scroll_events,
+        window_mode, WaylandBackendError,
+    },
+    fixed::Fixed,
+    ifs::wl_seat::wl_pointer::{PendingScroll, CONTINUOUS, FINGER, WHEEL, WHEEL_TILT},
+};
+
+#[test]
+fn socket_path() {
+    assert_eq!(
+        resolve_socket_path(Some("wayland-1"), Some("/run/user/1000")).unwrap(),
+        "/run/user/1000/wayland-1"
+    );
+    assert_eq!(
+        resolve_socket_path(Some("/tmp/wayland-1"), None).unwrap(),
+        "/tmp/wayland-1"
+    );
+    assert!(matches!(
+        resolve_socket_path(None, Some("/run/user/1000")),
+        Err(WaylandBackendError::WaylandDisplayNotSet)
+    ));
+    assert!(matches!(
+        resolve_socket_path(Some("wayland-1"), None),
+        Err(WaylandBackendError::XrdNotSet)
+    ));
+}
+
+#[test]
+fn mode() {
+    assert_eq!(
+        window_mode(1024, 768),
+        Mode {
+            width: 1024,
+            height: 768,
+            refresh_rate_millihz: 60_000,
+        }
+    );
+}
+
+#[test]
+fn configure_size() {
+    assert_eq!(configured_size(1024, 768), Some((1024, 768)));
+    assert_eq!(configured_size(0, 0), None);
+    assert_eq!(configured_size(1024, 0), None);
+    assert_eq!(configured_size(0, 768), None);
+    assert_eq!(configured_size(-1, 768), None);
+}
+
+#[test]
+fn buttons_and_axis_sources() {
+    assert_eq!(button_state(0), KeyState::Released);
+    assert_eq!(button_state(1), KeyState::Pressed);
+    assert_eq!(axis_source(WHEEL), AxisSource::Wheel);
+    assert_eq!(axis_source(FINGER), AxisSource::Finger);
+    assert_eq!(axis_source(CONTINUOUS), AxisSource::Continuous);
+    assert_eq!(axis_source(WHEEL_TILT), AxisSource::Wheel);
+}
+
+#[test]
+fn keys() {
+    let mut pressed = vec![];
+    assert_eq!(key_state(&mut pressed, 30, 1), Some(KeyState::Pressed));
+    assert_eq!(key_state(&mut pressed, 30, 1), None);
+    assert_eq!(key_state(&mut pressed, 31, 1), Some(KeyState::Pressed));
+    assert_eq!(pressed, [30, 31]);
+    assert_eq!(key_state(&mut pressed, 30, 0), Some(KeyState::Released));
+    assert_eq!(key_state(&mut pressed, 30, 0), None);
+    assert_eq!(pressed, [31]);
+}
+
+#[test]
+fn scroll() {
+    let ps = PendingScroll::default();
+    ps.source.set(Some(FINGER));
+    ps.v120[0].set(Some(120));
+    ps.px[1].set(Some(Fixed::from_int(5)));
+    ps.stop[1].set(true);
+    let events = scroll_events(&ps, 1234);
+    assert_eq!(events.len(), 5, "{:?}", events);
+    assert!(matches!(
+        events[0],
+        InputEvent::AxisSource {
+            source: AxisSource::Finger
+        }
+    ));
+    assert!(matches!(
+        events[1],
+        InputEvent::Axis120 {
+            dist: 120,
+            axis: ScrollAxis::Vertical
+        }
+    ));
+    assert!(matches!(
+        events[2],
+        InputEvent::AxisPx {
+            dist,
+            axis: ScrollAxis::Horizontal
+        } if dist == Fixed::from_int(5)
+    ));
+    assert!(matches!(
+        events[3],
+        InputEvent::AxisStop {
+            axis: ScrollAxis::Horizontal
+        }
+    ));
+    assert!(matches!(
+        events[4],
+        InputEvent::AxisFrame { time_usec: 1234 }
+    ));
+}
+
+#[test]
+fn empty_scroll() {
+    let events = scroll_events(&PendingScroll::default(), 1);
+    assert_eq!(events.len(), 1, "{:?}", events);
+    assert!(matches!(events[0], InputEvent::AxisFrame { time_usec: 1 }));
+}
@@ -126,12 +126,20 @@ pub struct RunArgs {
     /// The backends to try.
     ///
     /// By default, jay will try to start the available backends in this order: x11,metal.
-    /// The first backend that can be started will be used.
+    /// The first backend that can be started will be used. The wayland and headless backends
+    /// are only used if they are requested explicitly, e.g. with `--backends wayland`.
     ///
     /// Using this option, you can change which backends will be tried and change the order in
     /// which they will be tried. Multiple backends can be supplied as a comma-separated list.
     #[clap(value_enum, use_value_delimiter = true, long)]
     pub backends: Vec<CliBackend>,
+    /// The virtual outputs to create when using the headless backend.
+    ///
+    /// Each output is specified as WIDTHxHEIGHT or WIDTHxHEIGHT@REFRESH where the refresh
+    /// rate is given in Hz, e.g. 1920x1080@60. Multiple outputs can be supplied as a
+    /// comma-separated list. By default, a single 1920x1080@60 output is created.
+    #[clap(use_value_delimiter = true, long)]
+    pub headless_outputs: Vec<String>,
 }
 
 #[derive(Args, Debug)]
@@ -167,6 +175,8 @@ pub struct SeatTestArgs {
 pub enum CliBackend {
     X11,
     Metal,
+    Headless,
+    Wayland,
 }
 
 #[derive(ValueEnum, Debug, Copy, Clone, Hash)]
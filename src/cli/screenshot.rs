@@ -3,7 +3,7 @@ use {
         cli::{GlobalArgs, ScreenshotArgs},
         format::XRGB8888,
         tools::tool_client::{with_tool_client, Handle, ToolClient},
-        utils::{errorfmt::ErrorFmt, queue::AsyncQueue},
+        utils::{errorfmt::ErrorFmt, mmap::mmap, ptr_ext::PtrExt, queue::AsyncQueue},
         video::{
             dmabuf::{DmaBuf, DmaBufPlane},
             drm::Drm,
@@ -12,12 +12,13 @@ use {
         },
         wire::{
             jay_compositor::TakeScreenshot,
-            jay_screenshot::{Dmabuf, Error},
+            jay_screenshot::{Dmabuf, Error, Shm},
         },
     },
     algorithms::qoi::xrgb8888_encode_qoi,
     chrono::Local,
     std::rc::Rc,
+    uapi::c,
 };
 
 pub fn main(global: GlobalArgs, args: ScreenshotArgs) {
@@ -48,15 +49,17 @@ async fn run(screenshot: Rc<Screenshot>) {
         res.push(Err(err.msg.to_owned()));
     });
     Dmabuf::handle(tc, sid, result.clone(), |res, buf| {
-        res.push(Ok(buf));
+        res.push(Ok(buf_to_qoi(&buf)));
     });
-    let buf = match result.pop().await {
-        Ok(b) => b,
+    Shm::handle(tc, sid, result.clone(), |res, buf| {
+        res.push(Ok(shm_to_qoi(&buf)));
+    });
+    let data = match result.pop().await {
+        Ok(d) => d,
         Err(e) => {
             fatal!("Could not take a screenshot: {}", e);
         }
     };
-    let data = buf_to_qoi(&buf);
     let filename = screenshot
         .args
         .filename
@@ -107,3 +110,15 @@ pub fn buf_to_qoi(buf: &Dmabuf) -> Vec<u8> {
     let data = unsafe { bo_map.data() };
     xrgb8888_encode_qoi(data, buf.width, buf.height, buf.stride)
 }
+
+pub fn shm_to_qoi(buf: &Shm) -> Vec<u8> {
+    let len = buf.stride as usize * buf.height as usize;
+    let map = match mmap(len, c::PROT_READ, c::MAP_PRIVATE, buf.fd.raw(), 0) {
+        Ok(map) => map,
+        Err(e) => {
+            fatal!("Could not map the screenshot: {}", ErrorFmt(e));
+        }
+    };
+    let data = unsafe { map.ptr.deref() };
+    xrgb8888_encode_qoi(data, buf.width, buf.height, buf.stride)
+}
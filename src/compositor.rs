@@ -7,7 +7,7 @@ use {
         backend::{self, Backend},
         backends::{
             dummy::{DummyBackend, DummyOutput},
-            metal, x,
+            headless, metal, wayland, x,
         },
         cli::{CliBackend, GlobalArgs, RunArgs},
         client::{ClientId, Clients},
@@ -23,11 +23,14 @@ use {
         render::{self, RenderError},
         scale::Scale,
         sighand::{self, SighandError},
-        state::{ConnectorData, IdleState, ScreenlockState, State, XWaylandState},
+        state::{
+            ConnectorData, IdleState, ScreenlockState, State, XWaylandState,
+            DEFAULT_LOCK_FALLBACK_TEXT,
+        },
         tasks::{self, idle},
         tree::{
             container_layout, container_render_data, float_layout, float_titles,
-            output_render_data, DisplayNode, NodeIds, OutputNode, WorkspaceNode,
+            output_render_data, ContainerLayout, DisplayNode, NodeIds, OutputNode, WorkspaceNode,
         },
         user_session::import_environment,
         utils::{
@@ -63,8 +66,11 @@ pub fn start_compositor(global: GlobalArgs, args: RunArgs) {
 }
 
 #[cfg(feature = "it")]
-pub fn start_compositor_for_test(future: TestFuture) -> Result<(), CompositorError> {
-    let res = start_compositor2(None, None, RunArgs::default(), Some(future));
+pub fn start_compositor_for_test(
+    forker: Option<Rc<ForkerProxy>>,
+    future: TestFuture,
+) -> Result<(), CompositorError> {
+    let res = start_compositor2(forker, None, RunArgs::default(), Some(future));
     leaks::log_leaked();
     res
 }
@@ -94,6 +100,8 @@ pub enum CompositorError {
 
 pub const WAYLAND_DISPLAY: &str = "WAYLAND_DISPLAY";
 pub const DISPLAY: &str = "DISPLAY";
+pub const DESKTOP_STARTUP_ID: &str = "DESKTOP_STARTUP_ID";
+pub const XDG_ACTIVATION_TOKEN: &str = "XDG_ACTIVATION_TOKEN";
 
 const STATIC_VARS: &[(&str, &str)] = &[
     ("XDG_CURRENT_DESKTOP", "jay"),
@@ -171,6 +179,8 @@ fn start_compositor2(
             timeout_changed: Default::default(),
             inhibitors: Default::default(),
             inhibitors_changed: Default::default(),
+            stages: Default::default(),
+            stages_changed: Default::default(),
         },
         run_args,
         xwayland: XWaylandState {
@@ -191,17 +201,22 @@ fn start_compositor2(
         lock: ScreenlockState {
             locked: Cell::new(false),
             lock: Default::default(),
+            fallback_text: CloneCell::new(Rc::new(DEFAULT_LOCK_FALLBACK_TEXT.to_string())),
         },
         scales,
         cursor_sizes: Default::default(),
+        cursor_theme: Default::default(),
         hardware_tick_cursor: Default::default(),
         testers: Default::default(),
         render_ctx_watchers: Default::default(),
         workspace_watchers: Default::default(),
         default_workspace_capture: Cell::new(true),
+        placements: Default::default(),
+        scratchpad: Default::default(),
     });
     state.tracker.register(ClientId::from_raw(0));
     create_dummy_output(&state);
+    create_scratchpad(&state);
     let (acceptor, _acceptor_future) = Acceptor::install(&state)?;
     if let Some(forker) = forker {
         forker.install(&state);
@@ -247,7 +262,9 @@ async fn start_compositor3(state: Rc<State>, test_future: Option<TestFuture>) {
     state.config.set(Some(Rc::new(config)));
 
     let _geh = start_global_event_handlers(&state, &backend);
-    state.start_xwayland();
+    if !is_test {
+        state.start_xwayland();
+    }
 
     match backend.run().await {
         Err(e) => log::error!("Backend failed: {}", ErrorFmt(e.deref())),
@@ -326,6 +343,24 @@ async fn create_backend(
                     }
                 }
             }
+            CliBackend::Wayland => {
+                log::info!("Trying to create wayland backend");
+                match wayland::create(state).await {
+                    Ok(b) => return Some(b),
+                    Err(e) => {
+                        log::error!("Could not create wayland backend: {}", ErrorFmt(e));
+                    }
+                }
+            }
+            CliBackend::Headless => {
+                log::info!("Trying to create headless backend");
+                match headless::create(state) {
+                    Ok(b) => return Some(b),
+                    Err(e) => {
+                        log::error!("Could not create headless backend: {}", ErrorFmt(e));
+                    }
+                }
+            }
         }
     }
     None
@@ -402,6 +437,8 @@ fn create_dummy_output(state: &Rc<State>) {
         position: Default::default(),
         container: Default::default(),
         stacked: Default::default(),
+        minimized: Default::default(),
+        layout: Cell::new(ContainerLayout::Split),
         seat_state: Default::default(),
         name: "dummy".to_string(),
         output_link: Default::default(),
@@ -419,6 +456,30 @@ fn create_dummy_output(state: &Rc<State>) {
     state.dummy_output.set(Some(dummy_output));
 }
 
+fn create_scratchpad(state: &Rc<State>) {
+    let dummy_output = state.dummy_output.get().unwrap();
+    let scratchpad = Rc::new(WorkspaceNode {
+        id: state.node_ids.next(),
+        is_dummy: true,
+        output: CloneCell::new(dummy_output.clone()),
+        position: Default::default(),
+        container: Default::default(),
+        stacked: Default::default(),
+        minimized: Default::default(),
+        layout: Cell::new(ContainerLayout::Split),
+        seat_state: Default::default(),
+        name: "scratchpad".to_string(),
+        output_link: Default::default(),
+        visible: Default::default(),
+        fullscreen: Default::default(),
+        visible_on_desired_output: Default::default(),
+        desired_output: CloneCell::new(dummy_output.global.output_id.clone()),
+        jay_workspaces: Default::default(),
+        capture: Cell::new(false),
+    });
+    state.scratchpad.set(Some(scratchpad));
+}
+
 fn config_dir() -> Option<String> {
     if let Ok(xdg) = env::var("XDG_CONFIG_HOME") {
         Some(format!("{}/jay", xdg))
@@ -19,9 +19,11 @@ use {
             ipc::{InitMessage, ServerMessage, V1InitMessage},
             ConfigEntry, VERSION,
         },
-        input::{InputDevice, Seat},
-        keyboard::ModifiedKeySym,
+        idle::IdleStage,
+        input::{button::Button, InputDevice, ScrollDirection, Seat},
+        keyboard::{mods::Modifiers, BindingMode, KeyBinding, KeyTrigger},
         video::{Connector, DrmDevice},
+        Axis,
     },
     libloading::Library,
     std::{cell::Cell, mem, ptr, rc::Rc},
@@ -62,12 +64,26 @@ impl ConfigProxy {
         }
     }
 
-    pub fn invoke_shortcut(&self, seat: SeatId, modsym: &ModifiedKeySym) {
-        self.send(&ServerMessage::InvokeShortcut {
-            seat: Seat(seat.raw() as _),
-            mods: modsym.mods,
-            sym: modsym.sym,
-        });
+    pub fn invoke_shortcut(&self, seat: SeatId, binding: &KeyBinding) {
+        let seat = Seat(seat.raw() as _);
+        // Bindings that can be expressed as plain shortcuts are sent in the old format
+        // so that configs built against older versions of jay-config still understand
+        // them.
+        let is_plain = binding.mode == BindingMode::DEFAULT
+            && binding.trigger == KeyTrigger::Press
+            && binding.prefix.is_empty();
+        if is_plain {
+            self.send(&ServerMessage::InvokeShortcut {
+                seat,
+                mods: binding.key.mods,
+                sym: binding.key.sym,
+            });
+        } else {
+            self.send(&ServerMessage::InvokeKeyBinding {
+                seat,
+                binding: binding.clone(),
+            });
+        }
     }
 
     pub fn new_drm_dev(&self, dev: DrmDeviceId) {
@@ -133,6 +149,52 @@ impl ConfigProxy {
     pub fn idle(&self) {
         self.send(&ServerMessage::Idle);
     }
+
+    pub fn locker_died(&self) {
+        self.send(&ServerMessage::LockerDied);
+    }
+
+    pub fn idle_stage_entered(&self, stage: u64) {
+        self.send(&ServerMessage::IdleStageEntered {
+            stage: IdleStage(stage),
+        });
+    }
+
+    pub fn idle_stage_resumed(&self, stage: u64) {
+        self.send(&ServerMessage::IdleStageResumed {
+            stage: IdleStage(stage),
+        });
+    }
+
+    pub fn invoke_button_binding(&self, seat: SeatId, mods: Modifiers, button: Button) {
+        self.send(&ServerMessage::InvokeButtonBinding {
+            seat: Seat(seat.raw() as _),
+            mods,
+            button,
+        });
+    }
+
+    pub fn invoke_scroll_binding(
+        &self,
+        seat: SeatId,
+        mods: Modifiers,
+        axis: Axis,
+        direction: ScrollDirection,
+    ) {
+        self.send(&ServerMessage::InvokeScrollBinding {
+            seat: Seat(seat.raw() as _),
+            mods,
+            axis,
+            direction,
+        });
+    }
+
+    pub fn keyboard_group_changed(&self, seat: SeatId, group: u32) {
+        self.send(&ServerMessage::KeyboardGroupChanged {
+            seat: Seat(seat.raw() as _),
+            group,
+        });
+    }
 }
 
 impl Drop for ConfigProxy {
@@ -174,6 +236,11 @@ impl ConfigProxy {
             timer_ids: NumCell::new(1),
             timers_by_name: Default::default(),
             timers_by_id: Default::default(),
+            idle_stage_ids: NumCell::new(1),
+            idle_stages_by_name: Default::default(),
+            idle_stages_by_id: Default::default(),
+            process_ids: NumCell::new(1),
+            processes: Default::default(),
         });
         let init_msg =
             bincode::encode_to_vec(&InitMessage::V1(V1InitMessage {}), bincode_ops()).unwrap();
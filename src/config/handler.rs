@@ -5,49 +5,59 @@ use {
             self, ConnectorId, DrmDeviceId, InputDeviceAccelProfile, InputDeviceCapability,
             InputDeviceId,
         },
-        compositor::MAX_EXTENTS,
+        compositor::{DESKTOP_STARTUP_ID, MAX_EXTENTS, XDG_ACTIVATION_TOKEN},
         config::ConfigProxy,
+        forker::{ForkerError, SpawnedProcess},
         ifs::wl_seat::{SeatId, WlSeatGlobal},
         scale::Scale,
-        state::{ConnectorData, DeviceHandlerData, DrmDevData, OutputData, State},
+        state::{ConnectorData, DeviceHandlerData, DrmDevData, IdleStage, OutputData, State},
         theme::{Color, ThemeSized, DEFAULT_FONT},
-        tree::{ContainerNode, ContainerSplit, FloatNode, Node, NodeVisitorBase, OutputNode},
+        tree::{
+            ContainerNode, ContainerSplit, FloatNode, Node, NodeVisitorBase, OutputNode,
+            PlacementIntent,
+        },
         utils::{
+            buf::Buf,
             copyhashmap::CopyHashMap,
             debug_fn::debug_fn,
             errorfmt::ErrorFmt,
             numcell::NumCell,
+            oserror::OsError,
             stack::Stack,
             timer::{TimerError, TimerFd},
         },
         xkbcommon::{XkbCommonError, XkbKeymap},
     },
     bincode::error::DecodeError,
+    bstr::ByteSlice,
     jay_config::{
         _private::{
             bincode_ops,
-            ipc::{ClientMessage, Response, ServerMessage},
+            ipc::{ClientMessage, Placement, Response, ServerMessage},
         },
+        exec::{Process, Stdio},
+        idle::IdleStage as JayIdleStage,
         input::{
             acceleration::{AccelProfile, ACCEL_PROFILE_ADAPTIVE, ACCEL_PROFILE_FLAT},
+            button::Button,
             capability::{
                 Capability, CAP_GESTURE, CAP_KEYBOARD, CAP_POINTER, CAP_SWITCH, CAP_TABLET_PAD,
                 CAP_TABLET_TOOL, CAP_TOUCH,
             },
-            InputDevice, Seat,
+            InputDevice, ScrollDirection, Seat,
         },
-        keyboard::{mods::Modifiers, syms::KeySym, Keymap},
+        keyboard::{mods::Modifiers, BindingMode, KeyBinding, Keymap, ModifiedKeySym},
         logging::LogLevel,
         theme::{colors::Colorable, sized::Resizable},
         timer::Timer as JayTimer,
         video::{Connector, DrmDevice},
-        Axis, Direction, Workspace,
+        Axis, ContainerLayout, Direction, Workspace,
     },
     libloading::Library,
     log::Level,
     std::{cell::Cell, ops::Deref, rc::Rc, time::Duration},
     thiserror::Error,
-    uapi::c,
+    uapi::{c, OwnedFd},
 };
 
 pub(super) struct ConfigProxyHandler {
@@ -69,6 +79,18 @@ pub(super) struct ConfigProxyHandler {
     pub timer_ids: NumCell<u64>,
     pub timers_by_name: CopyHashMap<Rc<String>, Rc<TimerData>>,
     pub timers_by_id: CopyHashMap<u64, Rc<TimerData>>,
+
+    pub idle_stage_ids: NumCell<u64>,
+    pub idle_stages_by_name: CopyHashMap<Rc<String>, Rc<IdleStage>>,
+    pub idle_stages_by_id: CopyHashMap<u64, Rc<IdleStage>>,
+
+    pub process_ids: NumCell<u64>,
+    pub processes: CopyHashMap<u64, Rc<ProcessData>>,
+}
+
+pub(super) struct ProcessData {
+    process: Rc<SpawnedProcess>,
+    _handler: SpawnedFuture<()>,
 }
 
 pub(super) struct TimerData {
@@ -84,6 +106,13 @@ impl ConfigProxyHandler {
 
         self.timers_by_name.clear();
         self.timers_by_id.clear();
+
+        for (_, stage) in self.idle_stages_by_id.lock().drain() {
+            self.state.idle.remove_stage(&stage);
+        }
+        self.idle_stages_by_name.clear();
+
+        self.processes.clear();
     }
 
     pub fn send(&self, msg: &ServerMessage) {
@@ -263,6 +292,10 @@ impl ConfigProxyHandler {
         self.state.set_status(status);
     }
 
+    fn handle_set_lock_fallback_text(&self, text: &str) {
+        self.state.set_lock_fallback_text(text);
+    }
+
     fn get_timer(&self, timer: JayTimer) -> Result<Rc<TimerData>, CphError> {
         match self.timers_by_id.get(&timer.0) {
             Some(t) => Ok(t),
@@ -277,6 +310,67 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
+    fn get_idle_stage(&self, stage: JayIdleStage) -> Result<Rc<IdleStage>, CphError> {
+        match self.idle_stages_by_id.get(&stage.0) {
+            Some(s) => Ok(s),
+            _ => Err(CphError::IdleStageDoesNotExist(stage)),
+        }
+    }
+
+    fn handle_get_idle_stage(&self, name: &str) {
+        let name = Rc::new(name.to_owned());
+        if let Some(s) = self.idle_stages_by_name.get(&name) {
+            self.respond(Response::GetIdleStage {
+                stage: JayIdleStage(s.id),
+            });
+            return;
+        }
+        let id = self.idle_stage_ids.fetch_add(1);
+        let stage = Rc::new(IdleStage {
+            id,
+            name: name.clone(),
+            timeout: Cell::new(Duration::ZERO),
+            disable_outputs: Cell::new(false),
+            active: Cell::new(false),
+        });
+        self.idle_stages_by_name.set(name, stage.clone());
+        self.idle_stages_by_id.set(id, stage.clone());
+        self.state.idle.add_stage(&stage);
+        self.respond(Response::GetIdleStage {
+            stage: JayIdleStage(id),
+        });
+    }
+
+    fn handle_remove_idle_stage(&self, stage: JayIdleStage) -> Result<(), CphError> {
+        let stage = self.get_idle_stage(stage)?;
+        self.idle_stages_by_id.remove(&stage.id);
+        self.idle_stages_by_name.remove(&stage.name);
+        self.state.idle.remove_stage(&stage);
+        Ok(())
+    }
+
+    fn handle_set_idle_stage_timeout(
+        &self,
+        stage: JayIdleStage,
+        timeout: Duration,
+    ) -> Result<(), CphError> {
+        let stage = self.get_idle_stage(stage)?;
+        stage.timeout.set(timeout);
+        self.state.idle.stages_changed();
+        Ok(())
+    }
+
+    fn handle_set_idle_stage_disable_outputs(
+        &self,
+        stage: JayIdleStage,
+        disable: bool,
+    ) -> Result<(), CphError> {
+        let stage = self.get_idle_stage(stage)?;
+        stage.disable_outputs.set(disable);
+        self.state.idle.stages_changed();
+        Ok(())
+    }
+
     fn handle_set_env(&self, key: &str, val: &str) {
         if let Some(f) = self.state.forker.get() {
             f.setenv(key.as_bytes(), val.as_bytes());
@@ -356,6 +450,18 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
+    fn handle_resize(&self, seat: Seat, direction: Direction, amount: i32) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.resize_focused(direction.into(), amount);
+        Ok(())
+    }
+
+    fn handle_equalize(&self, seat: Seat) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.equalize();
+        Ok(())
+    }
+
     fn handle_get_repeat_rate(&self, seat: Seat) -> Result<(), CphError> {
         let seat = self.get_seat(seat)?;
         let (rate, delay) = seat.get_rate();
@@ -363,6 +469,51 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
+    fn handle_get_keyboard_group(&self, seat: Seat) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        self.respond(Response::GetKeyboardGroup {
+            group: seat.kb_group(),
+            num_groups: seat.num_kb_groups(),
+        });
+        Ok(())
+    }
+
+    fn handle_set_keyboard_group(&self, seat: Seat, group: u32) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.set_kb_group(group);
+        Ok(())
+    }
+
+    fn handle_next_keyboard_group(&self, seat: Seat) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.next_kb_group();
+        Ok(())
+    }
+
+    fn handle_prev_keyboard_group(&self, seat: Seat) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.prev_kb_group();
+        Ok(())
+    }
+
+    fn handle_get_keyboard_group_name(&self, seat: Seat, group: u32) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        self.respond(Response::GetKeyboardGroupName {
+            name: seat.kb_group_name(group).unwrap_or_default(),
+        });
+        Ok(())
+    }
+
+    fn handle_set_remember_keyboard_group(
+        &self,
+        seat: Seat,
+        remember: bool,
+    ) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.set_remember_kb_group(remember);
+        Ok(())
+    }
+
     fn handle_set_repeat_rate(&self, seat: Seat, rate: i32, delay: i32) -> Result<(), CphError> {
         let seat = self.get_seat(seat)?;
         if rate < 0 {
@@ -494,6 +645,21 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
+    fn handle_set_device_keymap(
+        &self,
+        device: InputDevice,
+        keymap: Keymap,
+    ) -> Result<(), CphError> {
+        let dev = self.get_device_handler_data(device)?;
+        let keymap = if keymap.is_invalid() {
+            None
+        } else {
+            Some(self.get_keymap(keymap)?)
+        };
+        dev.keymap.set(keymap);
+        Ok(())
+    }
+
     fn handle_set_px_per_wheel_scroll(&self, device: InputDevice, px: f64) -> Result<(), CphError> {
         let dev = self.get_device_handler_data(device)?;
         dev.px_per_scroll_wheel.set(px);
@@ -753,6 +919,42 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
+    fn handle_create_virtual_output(
+        &self,
+        width: i32,
+        height: i32,
+        refresh_millihz: u32,
+    ) -> Result<(), CphError> {
+        if width <= 0 || height <= 0 || refresh_millihz == 0 {
+            return Err(CphError::InvalidVirtualOutputMode(
+                width,
+                height,
+                refresh_millihz,
+            ));
+        }
+        let mode = backend::Mode {
+            width,
+            height,
+            refresh_rate_millihz: refresh_millihz,
+        };
+        let id = match self.state.backend.get().create_virtual_output(mode) {
+            Some(id) => id,
+            _ => return Err(CphError::CreateVirtualOutput),
+        };
+        self.respond(Response::CreateVirtualOutput {
+            connector: Connector(id.raw() as _),
+        });
+        Ok(())
+    }
+
+    fn handle_remove_virtual_output(&self, connector: Connector) -> Result<(), CphError> {
+        let id = ConnectorId::from_raw(connector.0 as _);
+        if !self.state.backend.get().remove_virtual_output(id) {
+            return Err(CphError::NotAVirtualOutput(connector));
+        }
+        Ok(())
+    }
+
     fn handle_get_connector(
         &self,
         ty: jay_config::video::connector_type::ConnectorType,
@@ -813,6 +1015,55 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
+    fn handle_seat_get_hide_titles(&self, seat: Seat) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        let hide = seat
+            .get_hide_titles()
+            .unwrap_or_else(|| self.state.theme.hide_titles.get());
+        self.respond(Response::SeatGetHideTitles { hide });
+        Ok(())
+    }
+
+    fn handle_seat_set_hide_titles(&self, seat: Seat, hide: bool) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.set_hide_titles(hide);
+        Ok(())
+    }
+
+    fn handle_seat_toggle_sticky(&self, seat: Seat) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.toggle_sticky();
+        Ok(())
+    }
+
+    fn handle_seat_minimize(&self, seat: Seat) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.minimize();
+        Ok(())
+    }
+
+    fn handle_seat_restore_last_minimized(&self, seat: Seat) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.restore_last_minimized();
+        Ok(())
+    }
+
+    fn handle_seat_move_to_scratchpad(&self, seat: Seat) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.move_to_scratchpad();
+        Ok(())
+    }
+
+    fn handle_seat_toggle_scratchpad(
+        &self,
+        seat: Seat,
+        filter: Option<&str>,
+    ) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.toggle_scratchpad(filter);
+        Ok(())
+    }
+
     fn handle_get_split(&self, seat: Seat) -> Result<(), CphError> {
         let seat = self.get_seat(seat)?;
         self.respond(Response::GetSplit {
@@ -824,31 +1075,118 @@ impl ConfigProxyHandler {
         Ok(())
     }
 
+    fn handle_seat_get_layout(&self, seat: Seat) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        let layout = seat
+            .get_layout()
+            .map(Into::into)
+            .unwrap_or(ContainerLayout::Split);
+        self.respond(Response::SeatGetLayout { layout });
+        Ok(())
+    }
+
+    fn handle_seat_set_layout(&self, seat: Seat, layout: ContainerLayout) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.set_layout(layout.into());
+        Ok(())
+    }
+
+    fn handle_set_workspace_layout(
+        &self,
+        workspace: Workspace,
+        layout: ContainerLayout,
+    ) -> Result<(), CphError> {
+        let name = self.get_workspace(workspace)?;
+        if let Some(ws) = self.state.workspaces.get(name.as_str()) {
+            ws.layout.set(layout.into());
+            if let Some(c) = ws.container.get() {
+                c.set_layout(layout.into());
+            }
+        }
+        Ok(())
+    }
+
     fn handle_set_split(&self, seat: Seat, axis: Axis) -> Result<(), CphError> {
         let seat = self.get_seat(seat)?;
         seat.set_split(axis.into());
         Ok(())
     }
 
-    fn handle_add_shortcut(
+    fn handle_add_shortcut(&self, seat: Seat, binding: KeyBinding) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.add_shortcut(binding);
+        Ok(())
+    }
+
+    fn handle_remove_shortcut(&self, seat: Seat, binding: KeyBinding) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.remove_shortcut(&binding);
+        Ok(())
+    }
+
+    fn handle_add_button_binding(
         &self,
         seat: Seat,
         mods: Modifiers,
-        sym: KeySym,
+        button: Button,
     ) -> Result<(), CphError> {
         let seat = self.get_seat(seat)?;
-        seat.add_shortcut(mods, sym);
+        seat.add_button_binding(mods, button);
         Ok(())
     }
 
-    fn handle_remove_shortcut(
+    fn handle_remove_button_binding(
         &self,
         seat: Seat,
         mods: Modifiers,
-        sym: KeySym,
+        button: Button,
     ) -> Result<(), CphError> {
         let seat = self.get_seat(seat)?;
-        seat.remove_shortcut(mods, sym);
+        seat.remove_button_binding(mods, button);
+        Ok(())
+    }
+
+    fn handle_add_scroll_binding(
+        &self,
+        seat: Seat,
+        mods: Modifiers,
+        axis: Axis,
+        direction: ScrollDirection,
+    ) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.add_scroll_binding(mods, axis, direction);
+        Ok(())
+    }
+
+    fn handle_remove_scroll_binding(
+        &self,
+        seat: Seat,
+        mods: Modifiers,
+        axis: Axis,
+        direction: ScrollDirection,
+    ) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.remove_scroll_binding(mods, axis, direction);
+        Ok(())
+    }
+
+    fn handle_set_window_drag_modifier(&self, seat: Seat, mods: Modifiers) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.set_window_drag_modifier(mods);
+        Ok(())
+    }
+
+    fn handle_set_binding_mode(&self, seat: Seat, mode: BindingMode) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        seat.set_binding_mode(mode);
+        Ok(())
+    }
+
+    fn handle_get_binding_mode(&self, seat: Seat) -> Result<(), CphError> {
+        let seat = self.get_seat(seat)?;
+        self.respond(Response::GetBindingMode {
+            mode: seat.binding_mode(),
+        });
         Ok(())
     }
 
@@ -897,7 +1235,128 @@ impl ConfigProxyHandler {
             Some(f) => f,
             _ => return Err(CphError::NoForker),
         };
-        forker.spawn(prog.to_string(), args, env, None);
+        forker.spawn(prog.to_string(), args, env, None, None);
+        Ok(())
+    }
+
+    fn handle_run2(
+        self: &Rc<Self>,
+        prog: &str,
+        args: Vec<String>,
+        env: Vec<(String, String)>,
+        stdout: Stdio,
+        stderr: Stdio,
+        placement: Placement,
+    ) -> Result<(), CphError> {
+        let forker = match self.state.forker.get() {
+            Some(f) => f,
+            _ => return Err(CphError::NoForker),
+        };
+        let workspace = match placement.workspace {
+            Some(ws) => Some(self.get_workspace(ws)?),
+            _ => None,
+        };
+        let has_placement = workspace.is_some()
+            || placement.output.is_some()
+            || placement.floating.is_some()
+            || placement.sticky;
+        let mut env = env;
+        let mut token = None;
+        if has_placement {
+            let t = self.state.placements.next_token();
+            env.push((DESKTOP_STARTUP_ID.to_string(), t.clone()));
+            env.push((XDG_ACTIVATION_TOKEN.to_string(), t.clone()));
+            token = Some(t);
+        }
+        let mut logs = vec![];
+        let stdout = self.get_stdio(prog, stdout, &mut logs)?;
+        let stderr = self.get_stdio(prog, stderr, &mut logs)?;
+        let process = forker.spawn(prog.to_string(), args, env, Some(stdout), Some(stderr));
+        if let Some(token) = token {
+            self.state.placements.add(PlacementIntent {
+                process: process.clone(),
+                token,
+                workspace,
+                output: placement.output.map(|c| ConnectorId::from_raw(c.0 as _)),
+                floating: placement.floating,
+                sticky: placement.sticky,
+            });
+        }
+        let id = self.process_ids.fetch_add(1);
+        let handler = {
+            let slf = self.clone();
+            let process = process.clone();
+            self.state.eng.spawn(async move {
+                let status = process.exited().await;
+                slf.send(&ServerMessage::ProcessExited {
+                    process: Process(id),
+                    status,
+                });
+                for log in logs {
+                    log.await;
+                }
+                slf.processes.remove(&id);
+            })
+        };
+        self.processes.set(
+            id,
+            Rc::new(ProcessData {
+                process,
+                _handler: handler,
+            }),
+        );
+        self.respond(Response::Run {
+            process: Process(id),
+        });
+        Ok(())
+    }
+
+    fn get_stdio(
+        &self,
+        prog: &str,
+        stdio: Stdio,
+        logs: &mut Vec<SpawnedFuture<()>>,
+    ) -> Result<Rc<OwnedFd>, CphError> {
+        let fd = match stdio {
+            Stdio::Null => match uapi::open("/dev/null", c::O_WRONLY | c::O_CLOEXEC, 0) {
+                Ok(fd) => fd,
+                Err(e) => return Err(CphError::OpenDevNull(e.into())),
+            },
+            Stdio::Log => {
+                let (read, write) = match uapi::pipe2(c::O_CLOEXEC) {
+                    Ok(p) => p,
+                    Err(e) => return Err(CphError::CreatePipe(e.into())),
+                };
+                let log = log_output(self.state.clone(), prog.to_string(), read);
+                logs.push(self.state.eng.spawn(log));
+                write
+            }
+            Stdio::Fd(fd) => match uapi::fcntl_dupfd_cloexec(fd, 0) {
+                Ok(fd) => fd,
+                Err(e) => return Err(CphError::DupFd(e.into())),
+            },
+        };
+        Ok(Rc::new(fd))
+    }
+
+    fn get_process(&self, process: Process) -> Result<Rc<ProcessData>, CphError> {
+        match self.processes.get(&process.0) {
+            Some(p) => Ok(p),
+            _ => Err(CphError::ProcessDoesNotExist(process)),
+        }
+    }
+
+    fn handle_get_process(&self, process: Process) {
+        let (pid, alive) = match self.processes.get(&process.0) {
+            Some(p) => (p.process.pid(), p.process.exit_status().is_none()),
+            _ => (None, false),
+        };
+        self.respond(Response::GetProcess { pid, alive });
+    }
+
+    fn handle_process_signal(&self, process: Process, signal: i32) -> Result<(), CphError> {
+        let process = self.get_process(process)?;
+        process.process.signal(signal)?;
         Ok(())
     }
 
@@ -981,6 +1440,8 @@ impl ConfigProxyHandler {
         let sized = match sized {
             TITLE_HEIGHT => ThemeSized::title_height,
             BORDER_WIDTH => ThemeSized::border_width,
+            INNER_GAP => ThemeSized::inner_gap,
+            OUTER_GAP => ThemeSized::outer_gap,
             _ => return Err(CphError::UnknownSized(sized.0)),
         };
         Ok(sized)
@@ -1016,6 +1477,16 @@ impl ConfigProxyHandler {
         self.spaces_change();
     }
 
+    fn handle_set_smart_gaps(&self, enabled: bool) {
+        self.state.theme.smart_gaps.set(enabled);
+        self.spaces_change();
+    }
+
+    fn handle_set_hide_titles(&self, hide: bool) {
+        self.state.theme.hide_titles.set(hide);
+        self.spaces_change();
+    }
+
     fn handle_reset_font(&self) {
         *self.state.theme.font.borrow_mut() = DEFAULT_FONT.to_string();
     }
@@ -1029,6 +1500,10 @@ impl ConfigProxyHandler {
         self.respond(Response::GetFont { font });
     }
 
+    fn handle_set_cursor_theme(&self, theme: Option<&str>) {
+        self.state.set_cursor_theme(theme.map(|t| t.to_string()));
+    }
+
     fn get_color(&self, colorable: Colorable) -> Result<&Cell<Color>, CphError> {
         let colors = &self.state.theme.colors;
         use jay_config::theme::colors::*;
@@ -1103,6 +1578,24 @@ impl ConfigProxyHandler {
             ClientMessage::SeatSetRepeatRate { seat, rate, delay } => self
                 .handle_set_repeat_rate(seat, rate, delay)
                 .wrn("set_repeat_rate")?,
+            ClientMessage::SeatGetKeyboardGroup { seat } => self
+                .handle_get_keyboard_group(seat)
+                .wrn("get_keyboard_group")?,
+            ClientMessage::SeatSetKeyboardGroup { seat, group } => self
+                .handle_set_keyboard_group(seat, group)
+                .wrn("set_keyboard_group")?,
+            ClientMessage::SeatNextKeyboardGroup { seat } => self
+                .handle_next_keyboard_group(seat)
+                .wrn("next_keyboard_group")?,
+            ClientMessage::SeatPrevKeyboardGroup { seat } => self
+                .handle_prev_keyboard_group(seat)
+                .wrn("prev_keyboard_group")?,
+            ClientMessage::SeatGetKeyboardGroupName { seat, group } => self
+                .handle_get_keyboard_group_name(seat, group)
+                .wrn("get_keyboard_group_name")?,
+            ClientMessage::SeatSetRememberKeyboardGroup { seat, remember } => self
+                .handle_set_remember_keyboard_group(seat, remember)
+                .wrn("set_remember_keyboard_group")?,
             ClientMessage::SetSeat { device, seat } => {
                 self.handle_set_seat(device, seat).wrn("set_seat")?
             }
@@ -1115,23 +1608,106 @@ impl ConfigProxyHandler {
                 self.handle_set_split(seat, axis).wrn("set_split")?
             }
             ClientMessage::AddShortcut { seat, mods, sym } => self
-                .handle_add_shortcut(seat, mods, sym)
+                .handle_add_shortcut(seat, ModifiedKeySym { mods, sym }.into())
                 .wrn("add_shortcut")?,
             ClientMessage::RemoveShortcut { seat, mods, sym } => self
-                .handle_remove_shortcut(seat, mods, sym)
+                .handle_remove_shortcut(seat, ModifiedKeySym { mods, sym }.into())
                 .wrn("remove_shortcut")?,
+            ClientMessage::AddButtonBinding { seat, mods, button } => self
+                .handle_add_button_binding(seat, mods, button)
+                .wrn("add_button_binding")?,
+            ClientMessage::RemoveButtonBinding { seat, mods, button } => self
+                .handle_remove_button_binding(seat, mods, button)
+                .wrn("remove_button_binding")?,
+            ClientMessage::AddScrollBinding {
+                seat,
+                mods,
+                axis,
+                direction,
+            } => self
+                .handle_add_scroll_binding(seat, mods, axis, direction)
+                .wrn("add_scroll_binding")?,
+            ClientMessage::RemoveScrollBinding {
+                seat,
+                mods,
+                axis,
+                direction,
+            } => self
+                .handle_remove_scroll_binding(seat, mods, axis, direction)
+                .wrn("remove_scroll_binding")?,
+            ClientMessage::SeatSetWindowDragModifier { seat, mods } => self
+                .handle_set_window_drag_modifier(seat, mods)
+                .wrn("set_window_drag_modifier")?,
+            ClientMessage::SeatSetBindingMode { seat, mode } => self
+                .handle_set_binding_mode(seat, mode)
+                .wrn("set_binding_mode")?,
+            ClientMessage::SeatGetBindingMode { seat } => {
+                self.handle_get_binding_mode(seat).wrn("get_binding_mode")?
+            }
             ClientMessage::Focus { seat, direction } => {
                 self.handle_focus(seat, direction).wrn("focus")?
             }
             ClientMessage::Move { seat, direction } => {
                 self.handle_move(seat, direction).wrn("move")?
             }
+            ClientMessage::Resize {
+                seat,
+                direction,
+                amount,
+            } => self.handle_resize(seat, direction, amount).wrn("resize")?,
+            ClientMessage::Equalize { seat } => self.handle_equalize(seat).wrn("equalize")?,
+            ClientMessage::CreateVirtualOutput {
+                width,
+                height,
+                refresh_millihz,
+            } => self
+                .handle_create_virtual_output(width, height, refresh_millihz)
+                .wrn("create_virtual_output")?,
+            ClientMessage::RemoveVirtualOutput { connector } => self
+                .handle_remove_virtual_output(connector)
+                .wrn("remove_virtual_output")?,
+            ClientMessage::SetSmartGaps { enabled } => self.handle_set_smart_gaps(enabled),
+            ClientMessage::SetHideTitles { hide } => self.handle_set_hide_titles(hide),
+            ClientMessage::SeatGetHideTitles { seat } => self
+                .handle_seat_get_hide_titles(seat)
+                .wrn("seat_get_hide_titles")?,
+            ClientMessage::SeatSetHideTitles { seat, hide } => self
+                .handle_seat_set_hide_titles(seat, hide)
+                .wrn("seat_set_hide_titles")?,
+            ClientMessage::SeatMoveToScratchpad { seat } => self
+                .handle_seat_move_to_scratchpad(seat)
+                .wrn("seat_move_to_scratchpad")?,
+            ClientMessage::SeatToggleScratchpad { seat, filter } => self
+                .handle_seat_toggle_scratchpad(seat, filter)
+                .wrn("seat_toggle_scratchpad")?,
+            ClientMessage::SeatGetLayout { seat } => {
+                self.handle_seat_get_layout(seat).wrn("seat_get_layout")?
+            }
+            ClientMessage::SeatSetLayout { seat, layout } => self
+                .handle_seat_set_layout(seat, layout)
+                .wrn("seat_set_layout")?,
+            ClientMessage::SetWorkspaceLayout { workspace, layout } => self
+                .handle_set_workspace_layout(workspace, layout)
+                .wrn("set_workspace_layout")?,
+            ClientMessage::SeatToggleSticky { seat } => self
+                .handle_seat_toggle_sticky(seat)
+                .wrn("seat_toggle_sticky")?,
+            ClientMessage::SeatMinimize { seat } => {
+                self.handle_seat_minimize(seat).wrn("seat_minimize")?
+            }
+            ClientMessage::SeatRestoreLastMinimized { seat } => self
+                .handle_seat_restore_last_minimized(seat)
+                .wrn("seat_restore_last_minimized")?,
             ClientMessage::GetInputDevices { seat } => self.handle_get_input_devices(seat),
             ClientMessage::GetSeats => self.handle_get_seats(),
             ClientMessage::RemoveSeat { .. } => {}
             ClientMessage::Run { prog, args, env } => {
                 self.handle_run(prog, args, env).wrn("run")?
             }
+            ClientMessage::GetProcess { process } => self.handle_get_process(process),
+            ClientMessage::ProcessSignal { process, signal } => self
+                .handle_process_signal(process, signal)
+                .wrn("process_signal")?,
             ClientMessage::GrabKb { kb, grab } => self.handle_grab(kb, grab).wrn("grab")?,
             ClientMessage::SetColor { colorable, color } => {
                 self.handle_set_color(colorable, color).wrn("set_color")?
@@ -1245,6 +1821,26 @@ impl ConfigProxyHandler {
             ClientMessage::ResetFont => self.handle_reset_font(),
             ClientMessage::GetFont => self.handle_get_font(),
             ClientMessage::SetFont { font } => self.handle_set_font(font),
+            ClientMessage::AddKeyBinding { seat, binding } => self
+                .handle_add_shortcut(seat, binding)
+                .wrn("add_key_binding")?,
+            ClientMessage::RemoveKeyBinding { seat, binding } => self
+                .handle_remove_shortcut(seat, binding)
+                .wrn("remove_key_binding")?,
+            ClientMessage::Run2 {
+                prog,
+                args,
+                env,
+                stdout,
+                stderr,
+                placement,
+            } => self
+                .handle_run2(prog, args, env, stdout, stderr, placement)
+                .wrn("run2")?,
+            ClientMessage::SetCursorTheme { theme } => self.handle_set_cursor_theme(theme),
+            ClientMessage::DeviceSetKeymap { device, keymap } => self
+                .handle_set_device_keymap(device, keymap)
+                .wrn("set_device_keymap")?,
             ClientMessage::SetPxPerWheelScroll { device, px } => self
                 .handle_set_px_per_wheel_scroll(device, px)
                 .wrn("set_px_per_wheel_scroll")?,
@@ -1296,6 +1892,17 @@ impl ConfigProxyHandler {
             ClientMessage::GetWorkspaceCapture { workspace } => self
                 .handle_get_workspace_capture(workspace)
                 .wrn("get_workspace_capture")?,
+            ClientMessage::GetIdleStage { name } => self.handle_get_idle_stage(name),
+            ClientMessage::RemoveIdleStage { stage } => self
+                .handle_remove_idle_stage(stage)
+                .wrn("remove_idle_stage")?,
+            ClientMessage::SetIdleStageTimeout { stage, timeout } => self
+                .handle_set_idle_stage_timeout(stage, timeout)
+                .wrn("set_idle_stage_timeout")?,
+            ClientMessage::SetIdleStageDisableOutputs { stage, disable } => self
+                .handle_set_idle_stage_disable_outputs(stage, disable)
+                .wrn("set_idle_stage_disable_outputs")?,
+            ClientMessage::SetLockFallbackText { text } => self.handle_set_lock_fallback_text(text),
         }
         Ok(())
     }
@@ -1323,6 +1930,8 @@ enum CphError {
     ConnectorDoesNotExist(Connector),
     #[error("Timer {0:?} does not exist")]
     TimerDoesNotExist(JayTimer),
+    #[error("Idle stage {0:?} does not exist")]
+    IdleStageDoesNotExist(JayIdleStage),
     #[error("Connector {0:?} does not exist or is not connected")]
     OutputDoesNotExist(Connector),
     #[error("{0}x{1} is not a valid connector position")]
@@ -1353,6 +1962,47 @@ enum CphError {
     ScaleTooLarge(f64),
     #[error("Tried to set a negative cursor size")]
     NegativeCursorSize,
+    #[error("Process {0:?} does not exist or has already exited")]
+    ProcessDoesNotExist(Process),
+    #[error("Could not create a pipe")]
+    CreatePipe(#[source] OsError),
+    #[error("Could not duplicate the file descriptor")]
+    DupFd(#[source] OsError),
+    #[error("Could not open /dev/null")]
+    OpenDevNull(#[source] OsError),
+    #[error(transparent)]
+    ForkerError(#[from] ForkerError),
+    #[error("The mode {0}x{1}@{2}mhz is not a valid mode for a virtual output")]
+    InvalidVirtualOutputMode(i32, i32, u32),
+    #[error("The backend could not create a virtual output")]
+    CreateVirtualOutput,
+    #[error("Connector {0:?} is not a virtual output")]
+    NotAVirtualOutput(Connector),
+}
+
+async fn log_output(state: Rc<State>, prog: String, fd: OwnedFd) {
+    let fd = Rc::new(fd);
+    let mut buf = Buf::new(1024);
+    let mut line = vec![];
+    loop {
+        match state.ring.read(&fd, buf.clone()).await {
+            Ok(n) if n > 0 => {
+                line.extend_from_slice(&buf[..n]);
+                while let Some(pos) = line.iter().position(|&b| b == b'\n') {
+                    log::info!("{}: {}", prog, line[..pos].as_bstr());
+                    line.drain(..=pos);
+                }
+            }
+            Ok(_) => break,
+            Err(e) => {
+                log::error!("Could not read the output of `{}`: {}", prog, ErrorFmt(e));
+                return;
+            }
+        }
+    }
+    if line.len() > 0 {
+        log::info!("{}: {}", prog, line.as_bstr());
+    }
 }
 
 trait WithRequestName {
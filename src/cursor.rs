@@ -15,6 +15,7 @@ use {
     byteorder::{LittleEndian, ReadBytesExt},
     isnt::std_1::primitive::IsntSliceExt,
     num_derive::FromPrimitive,
+    num_traits::FromPrimitive,
     std::{
         cell::Cell,
         convert::TryInto,
@@ -59,18 +60,7 @@ pub trait Cursor {
 }
 
 pub struct ServerCursors {
-    pub default: ServerCursorTemplate,
-    pub pointer: ServerCursorTemplate,
-    pub resize_right: ServerCursorTemplate,
-    pub resize_left: ServerCursorTemplate,
-    pub resize_top: ServerCursorTemplate,
-    pub resize_bottom: ServerCursorTemplate,
-    pub resize_top_bottom: ServerCursorTemplate,
-    pub resize_left_right: ServerCursorTemplate,
-    pub resize_top_left: ServerCursorTemplate,
-    pub resize_top_right: ServerCursorTemplate,
-    pub resize_bottom_left: ServerCursorTemplate,
-    pub resize_bottom_right: ServerCursorTemplate,
+    templates: Vec<Rc<ServerCursorTemplate>>,
 }
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, FromPrimitive)]
@@ -83,6 +73,81 @@ pub enum KnownCursor {
     ResizeTopRight,
     ResizeBottomLeft,
     ResizeBottomRight,
+    ContextMenu,
+    Help,
+    Progress,
+    Wait,
+    Cell,
+    Crosshair,
+    Text,
+    VerticalText,
+    Alias,
+    Copy,
+    Move,
+    NoDrop,
+    NotAllowed,
+    Grab,
+    Grabbing,
+    ResizeRight,
+    ResizeTop,
+    ResizeBottom,
+    ResizeLeft,
+    ResizeTopRightBottomLeft,
+    ResizeTopLeftBottomRight,
+    ColResize,
+    RowResize,
+    AllScroll,
+    ZoomIn,
+    ZoomOut,
+}
+
+impl KnownCursor {
+    /// Returns the xcursor names of the cursor in order of preference.
+    ///
+    /// Themes name their cursors either after the CSS cursor names or after the legacy
+    /// X11 cursor font. The first name that exists in the theme is used.
+    fn xcursor_names(self) -> &'static [&'static str] {
+        match self {
+            KnownCursor::Default => &["left_ptr", "default"],
+            KnownCursor::Pointer => &["hand2", "pointer", "hand1", "pointing_hand"],
+            KnownCursor::ResizeLeftRight => &["h_double_arrow", "ew-resize", "sb_h_double_arrow"],
+            KnownCursor::ResizeTopBottom => &["v_double_arrow", "ns-resize", "sb_v_double_arrow"],
+            KnownCursor::ResizeTopLeft => &["top_left_corner", "nw-resize"],
+            KnownCursor::ResizeTopRight => &["top_right_corner", "ne-resize"],
+            KnownCursor::ResizeBottomLeft => &["bottom_left_corner", "sw-resize"],
+            KnownCursor::ResizeBottomRight => &["bottom_right_corner", "se-resize"],
+            KnownCursor::ContextMenu => &["context-menu"],
+            KnownCursor::Help => &["help", "question_arrow", "whats_this"],
+            KnownCursor::Progress => &["progress", "left_ptr_watch", "half-busy"],
+            KnownCursor::Wait => &["wait", "watch"],
+            KnownCursor::Cell => &["cell", "plus"],
+            KnownCursor::Crosshair => &["crosshair", "cross", "tcross"],
+            KnownCursor::Text => &["text", "xterm", "ibeam"],
+            KnownCursor::VerticalText => &["vertical-text"],
+            KnownCursor::Alias => &["alias", "dnd-link", "link"],
+            KnownCursor::Copy => &["copy", "dnd-copy"],
+            KnownCursor::Move => &["move", "dnd-move", "fleur"],
+            KnownCursor::NoDrop => &["no-drop", "dnd-no-drop"],
+            KnownCursor::NotAllowed => &["not-allowed", "crossed_circle", "forbidden"],
+            KnownCursor::Grab => &["grab", "openhand"],
+            KnownCursor::Grabbing => &["grabbing", "closedhand"],
+            KnownCursor::ResizeRight => &["e-resize", "right_side"],
+            KnownCursor::ResizeTop => &["n-resize", "top_side"],
+            KnownCursor::ResizeBottom => &["s-resize", "bottom_side"],
+            KnownCursor::ResizeLeft => &["w-resize", "left_side"],
+            KnownCursor::ResizeTopRightBottomLeft => {
+                &["nesw-resize", "fd_double_arrow", "size_bdiag"]
+            }
+            KnownCursor::ResizeTopLeftBottomRight => {
+                &["nwse-resize", "bd_double_arrow", "size_fdiag"]
+            }
+            KnownCursor::ColResize => &["col-resize", "sb_h_double_arrow", "split_h"],
+            KnownCursor::RowResize => &["row-resize", "sb_v_double_arrow", "split_v"],
+            KnownCursor::AllScroll => &["all-scroll", "fleur"],
+            KnownCursor::ZoomIn => &["zoom-in"],
+            KnownCursor::ZoomOut => &["zoom-out"],
+        }
+    }
 }
 
 impl ServerCursors {
@@ -94,26 +159,35 @@ impl ServerCursors {
         if sizes.is_empty() || scales.is_empty() {
             return Ok(None);
         }
+        let config_theme = state.cursor_theme.borrow().clone();
         let xcursor_theme = env::var_os(XCURSOR_THEME);
-        let theme = xcursor_theme.as_ref().map(|theme| BStr::new(theme.bytes()));
-
-        let load =
-            |name: &str| ServerCursorTemplate::load(name, theme, &scales, &sizes, &paths, ctx);
-        Ok(Some(Self {
-            default: load("left_ptr")?,
-            pointer: load("hand2")?,
-            // default: load("left_ptr_watch")?,
-            resize_right: load("right_side")?,
-            resize_left: load("left_side")?,
-            resize_top: load("top_side")?,
-            resize_bottom: load("bottom_side")?,
-            resize_top_bottom: load("v_double_arrow")?,
-            resize_left_right: load("h_double_arrow")?,
-            resize_top_left: load("top_left_corner")?,
-            resize_top_right: load("top_right_corner")?,
-            resize_bottom_left: load("bottom_left_corner")?,
-            resize_bottom_right: load("bottom_right_corner")?,
-        }))
+        let theme = match &config_theme {
+            Some(theme) => Some(BStr::new(theme.as_bytes())),
+            _ => xcursor_theme.as_ref().map(|theme| BStr::new(theme.bytes())),
+        };
+
+        let load = |cursor: KnownCursor| {
+            ServerCursorTemplate::load(cursor.xcursor_names(), theme, &scales, &sizes, &paths, ctx)
+        };
+        let default = match load(KnownCursor::Default)? {
+            Some(d) => Rc::new(d),
+            _ => Rc::new(ServerCursorTemplate::empty(&scales, &sizes, ctx)?),
+        };
+        let mut templates = vec![default.clone()];
+        let mut idx = 1;
+        while let Some(cursor) = KnownCursor::from_usize(idx) {
+            let template = match load(cursor)? {
+                Some(t) => Rc::new(t),
+                _ => default.clone(),
+            };
+            templates.push(template);
+            idx += 1;
+        }
+        Ok(Some(Self { templates }))
+    }
+
+    pub fn get(&self, cursor: KnownCursor) -> &Rc<ServerCursorTemplate> {
+        &self.templates[cursor as usize]
     }
 }
 
@@ -128,75 +202,87 @@ enum ServerCursorTemplateVariant {
 }
 
 impl ServerCursorTemplate {
+    /// Loads the first of the named cursors that can be found in the theme.
     fn load(
-        name: &str,
+        names: &[&str],
         theme: Option<&BStr>,
         scales: &[Scale],
         sizes: &[u32],
         paths: &[BString],
         ctx: &Rc<RenderContext>,
-    ) -> Result<Self, CursorError> {
-        match open_cursor(name, theme, scales, sizes, paths) {
-            Ok(cs) => {
-                if cs.images.len() == 1 {
-                    let mut sizes = SmallMapMut::new();
-                    for (k, c) in &cs.images[0] {
-                        sizes.insert(
-                            *k,
-                            CursorImageScaled::from_bytes(
-                                ctx, &c.pixels, c.width, c.height, c.xhot, c.yhot,
-                            )?,
-                        );
-                    }
-                    let cursor = CursorImage::from_sizes(0, sizes)?;
-                    Ok(ServerCursorTemplate {
-                        var: ServerCursorTemplateVariant::Static(Rc::new(cursor)),
-                        xcursor: cs.images,
-                    })
-                } else {
-                    let mut images = vec![];
-                    for image in &cs.images {
-                        let mut sizes = SmallMapMut::new();
-                        let mut delay_ms = 0;
-                        for (k, c) in image {
-                            delay_ms = c.delay;
-                            sizes.insert(
-                                *k,
-                                CursorImageScaled::from_bytes(
-                                    ctx, &c.pixels, c.width, c.height, c.xhot, c.yhot,
-                                )?,
-                            );
-                        }
-                        let img = CursorImage::from_sizes(delay_ms as _, sizes)?;
-                        images.push(img);
-                    }
-                    Ok(ServerCursorTemplate {
-                        var: ServerCursorTemplateVariant::Animated(Rc::new(images)),
-                        xcursor: cs.images,
-                    })
-                }
+    ) -> Result<Option<Self>, CursorError> {
+        for name in names {
+            match open_cursor(name, theme, scales, sizes, paths) {
+                Ok(cs) => return Self::from_xcursor(cs, ctx).map(Some),
+                Err(e) => log::debug!("Could not load cursor {}: {}", name, ErrorFmt(e)),
+            }
+        }
+        log::warn!("Could not load cursor {}", names[0]);
+        Ok(None)
+    }
+
+    fn from_xcursor(cs: OpenCursorResult, ctx: &Rc<RenderContext>) -> Result<Self, CursorError> {
+        if cs.images.len() == 1 {
+            let mut sizes = SmallMapMut::new();
+            for (k, c) in &cs.images[0] {
+                sizes.insert(
+                    *k,
+                    CursorImageScaled::from_bytes(
+                        ctx, &c.pixels, c.width, c.height, c.xhot, c.yhot,
+                    )?,
+                );
             }
-            Err(e) => {
-                log::warn!("Could not load cursor {}: {}", name, ErrorFmt(e));
-                let empty: [Cell<u8>; 4] = unsafe { MaybeUninit::zeroed().assume_init() };
-                let mut img_sizes = SmallMapMut::new();
-                for scale in scales {
-                    for size in sizes {
-                        img_sizes.insert(
-                            (*scale, *size),
-                            CursorImageScaled::from_bytes(ctx, &empty, 1, 1, 0, 0)?,
-                        );
-                    }
+            let cursor = CursorImage::from_sizes(0, sizes)?;
+            Ok(ServerCursorTemplate {
+                var: ServerCursorTemplateVariant::Static(Rc::new(cursor)),
+                xcursor: cs.images,
+            })
+        } else {
+            let mut images = vec![];
+            for image in &cs.images {
+                let mut sizes = SmallMapMut::new();
+                let mut delay_ms = 0;
+                for (k, c) in image {
+                    delay_ms = c.delay;
+                    sizes.insert(
+                        *k,
+                        CursorImageScaled::from_bytes(
+                            ctx, &c.pixels, c.width, c.height, c.xhot, c.yhot,
+                        )?,
+                    );
                 }
-                let cursor = CursorImage::from_sizes(0, img_sizes)?;
-                Ok(ServerCursorTemplate {
-                    var: ServerCursorTemplateVariant::Static(Rc::new(cursor)),
-                    xcursor: Default::default(),
-                })
+                let img = CursorImage::from_sizes(delay_ms as _, sizes)?;
+                images.push(img);
             }
+            Ok(ServerCursorTemplate {
+                var: ServerCursorTemplateVariant::Animated(Rc::new(images)),
+                xcursor: cs.images,
+            })
         }
     }
 
+    fn empty(
+        scales: &[Scale],
+        sizes: &[u32],
+        ctx: &Rc<RenderContext>,
+    ) -> Result<Self, CursorError> {
+        let empty: [Cell<u8>; 4] = unsafe { MaybeUninit::zeroed().assume_init() };
+        let mut img_sizes = SmallMapMut::new();
+        for scale in scales {
+            for size in sizes {
+                img_sizes.insert(
+                    (*scale, *size),
+                    CursorImageScaled::from_bytes(ctx, &empty, 1, 1, 0, 0)?,
+                );
+            }
+        }
+        let cursor = CursorImage::from_sizes(0, img_sizes)?;
+        Ok(ServerCursorTemplate {
+            var: ServerCursorTemplateVariant::Static(Rc::new(cursor)),
+            xcursor: Default::default(),
+        })
+    }
+
     pub fn instantiate(&self, size: u32) -> Rc<dyn Cursor> {
         match &self.var {
             ServerCursorTemplateVariant::Static(s) => Rc::new(StaticCursor {
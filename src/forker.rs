@@ -12,8 +12,8 @@ use {
         io_uring::IoUring,
         state::State,
         utils::{
-            buffd::BufFdError, copyhashmap::CopyHashMap, errorfmt::ErrorFmt, numcell::NumCell,
-            queue::AsyncQueue,
+            buffd::BufFdError, clonecell::CloneCell, copyhashmap::CopyHashMap, errorfmt::ErrorFmt,
+            numcell::NumCell, queue::AsyncQueue,
         },
         xwayland,
     },
@@ -21,7 +21,7 @@ use {
         error::{DecodeError, EncodeError},
         Decode, Encode,
     },
-    jay_config::_private::bincode_ops,
+    jay_config::{_private::bincode_ops, exec::ExitStatus},
     log::Level,
     std::{
         cell::{Cell, RefCell},
@@ -29,6 +29,7 @@ use {
         ffi::OsStr,
         io::{Read, Write},
         os::unix::ffi::OsStrExt,
+        ptr,
         rc::{Rc, Weak},
         task::{Poll, Waker},
     },
@@ -46,6 +47,7 @@ pub struct ForkerProxy {
     outgoing: AsyncQueue<ServerMessage>,
     next_id: NumCell<u32>,
     pending_pidfds: CopyHashMap<u32, Weak<PidfdHandoff>>,
+    processes: CopyHashMap<u32, Rc<SpawnedProcess>>,
     fds: RefCell<Vec<Rc<OwnedFd>>>,
 }
 
@@ -54,6 +56,67 @@ struct PidfdHandoff {
     waiter: Cell<Option<Waker>>,
 }
 
+/// A process spawned via [`ForkerProxy::spawn`].
+#[derive(Default)]
+pub struct SpawnedProcess {
+    pid: Cell<Option<c::pid_t>>,
+    pidfd: CloneCell<Option<Rc<OwnedFd>>>,
+    exit_status: Cell<Option<ExitStatus>>,
+    waiter: Cell<Option<Waker>>,
+}
+
+impl SpawnedProcess {
+    /// Returns the pid of the process if it has been started and has not yet exited.
+    pub fn pid(&self) -> Option<c::pid_t> {
+        self.pid.get()
+    }
+
+    pub fn exit_status(&self) -> Option<ExitStatus> {
+        self.exit_status.get()
+    }
+
+    pub fn signal(&self, signal: c::c_int) -> Result<(), ForkerError> {
+        let pidfd = match self.pidfd.get() {
+            Some(pidfd) => pidfd,
+            _ => return Err(ForkerError::NotRunning),
+        };
+        let res = unsafe {
+            c::syscall(
+                c::SYS_pidfd_send_signal,
+                pidfd.raw(),
+                signal,
+                ptr::null::<c::siginfo_t>(),
+                0,
+            )
+        };
+        if let Err(e) = uapi::map_err!(res) {
+            return Err(ForkerError::Signal(e.into()));
+        }
+        Ok(())
+    }
+
+    pub async fn exited(&self) -> ExitStatus {
+        futures_util::future::poll_fn(|ctx| {
+            if let Some(status) = self.exit_status.get() {
+                Poll::Ready(status)
+            } else {
+                self.waiter.set(Some(ctx.waker().clone()));
+                Poll::Pending
+            }
+        })
+        .await
+    }
+
+    fn set_exited(&self, status: ExitStatus) {
+        self.pid.take();
+        self.pidfd.take();
+        self.exit_status.set(Some(status));
+        if let Some(w) = self.waiter.take() {
+            w.wake();
+        }
+    }
+}
+
 #[derive(Debug, Error)]
 pub enum ForkerError {
     #[error("Could not create a socketpair")]
@@ -70,6 +133,10 @@ pub enum ForkerError {
     EncodeFailed(#[source] EncodeError),
     #[error("Could not fork")]
     PidfdForkFailed,
+    #[error("The process is not running")]
+    NotRunning,
+    #[error("Could not send a signal to the process")]
+    Signal(#[source] crate::utils::oserror::OsError),
 }
 
 impl ForkerProxy {
@@ -98,6 +165,7 @@ impl ForkerProxy {
                 outgoing: Default::default(),
                 next_id: Default::default(),
                 pending_pidfds: Default::default(),
+                processes: Default::default(),
                 fds: Default::default(),
             }),
             Forked::Child { .. } => {
@@ -170,18 +238,28 @@ impl ForkerProxy {
         prog: String,
         args: Vec<String>,
         env: Vec<(String, String)>,
+        stdout: Option<Rc<OwnedFd>>,
         stderr: Option<Rc<OwnedFd>>,
-    ) {
+    ) -> Rc<SpawnedProcess> {
+        let have_stdout = stdout.is_some();
         let have_stderr = stderr.is_some();
-        if let Some(stderr) = stderr {
-            self.fds.borrow_mut().push(stderr);
+        {
+            let mut fds = self.fds.borrow_mut();
+            fds.extend(stdout);
+            fds.extend(stderr);
         }
+        let id = self.next_id.fetch_add(1);
+        let process = Rc::new(SpawnedProcess::default());
+        self.processes.set(id, process.clone());
         self.outgoing.push(ServerMessage::Spawn {
+            id,
             prog,
             args,
             env,
+            stdout: have_stdout,
             stderr: have_stderr,
-        })
+        });
+        process
     }
 
     async fn incoming(self: Rc<Self>, state: Rc<State>) {
@@ -203,6 +281,13 @@ impl ForkerProxy {
         match msg {
             ForkerMessage::Log { level, msg } => self.handle_log(level, &msg),
             ForkerMessage::PidFd { id, success, pid } => self.handle_pidfd(id, success, io, pid),
+            ForkerMessage::Exited { id, status } => self.handle_exited(id, status),
+        }
+    }
+
+    fn handle_exited(&self, id: u32, status: ExitStatus) {
+        if let Some(process) = self.processes.remove(&id) {
+            process.set_exited(status);
         }
     }
 
@@ -211,6 +296,19 @@ impl ForkerProxy {
             true => Ok((io.pop_fd().unwrap(), pid)),
             _ => Err(ForkerError::PidfdForkFailed),
         };
+        if let Some(process) = self.processes.get(&id) {
+            match res {
+                Ok((pidfd, pid)) => {
+                    process.pid.set(Some(pid));
+                    process.pidfd.set(Some(pidfd));
+                }
+                Err(_) => {
+                    self.processes.remove(&id);
+                    process.set_exited(ExitStatus::SpawnFailed);
+                }
+            }
+            return;
+        }
         if let Some(handoff) = self.pending_pidfds.remove(&id) {
             if let Some(handoff) = handoff.upgrade() {
                 handoff.pidfd.set(Some(res));
@@ -271,9 +369,11 @@ enum ServerMessage {
         val: Option<Vec<u8>>,
     },
     Spawn {
+        id: u32,
         prog: String,
         args: Vec<String>,
         env: Vec<(String, String)>,
+        stdout: bool,
         stderr: bool,
     },
     Xwayland {
@@ -292,6 +392,10 @@ enum ForkerMessage {
         success: bool,
         pid: c::pid_t,
     },
+    Exited {
+        id: u32,
+        status: ExitStatus,
+    },
 }
 
 struct Forker {
@@ -371,11 +475,13 @@ impl Forker {
         match msg {
             ServerMessage::SetEnv { var, val } => self.handle_set_env(&var, val),
             ServerMessage::Spawn {
+                id,
                 prog,
                 args,
                 env,
+                stdout,
                 stderr,
-            } => self.handle_spawn(prog, args, env, stderr, io),
+            } => self.handle_spawn(id, prog, args, env, stdout, stderr, io),
             ServerMessage::Xwayland { id } => self.handle_xwayland(io, id),
         }
     }
@@ -398,44 +504,49 @@ impl Forker {
         ];
         let (prog, args) = xwayland::build_args(&fds);
         let env = vec![("WAYLAND_SOCKET".to_string(), fds[3].raw().to_string())];
-        self.spawn(prog, args, env, stderr, fds, Some(id));
+        self.spawn(id, prog, args, env, None, stderr, fds);
     }
 
     fn handle_spawn(
         self: &Rc<Self>,
+        id: u32,
         prog: String,
         args: Vec<String>,
         env: Vec<(String, String)>,
+        stdout: bool,
         stderr: bool,
         io: &mut IoIn,
     ) {
+        let stdout = match stdout {
+            true => io.pop_fd(),
+            _ => None,
+        };
         let stderr = match stderr {
             true => io.pop_fd(),
             _ => None,
         };
-        self.spawn(prog, args, env, stderr, vec![], None)
+        self.spawn(id, prog, args, env, stdout, stderr, vec![])
     }
 
     fn spawn(
         self: &Rc<Self>,
+        id: u32,
         prog: String,
         args: Vec<String>,
         env: Vec<(String, String)>,
+        stdout: Option<Rc<OwnedFd>>,
         stderr: Option<Rc<OwnedFd>>,
         fds: Vec<OwnedFd>,
-        pidfd_id: Option<u32>,
     ) {
         let (read, mut write) = pipe2(c::O_CLOEXEC).unwrap();
         let res = match fork_with_pidfd(false) {
             Ok(o) => o,
             Err(e) => {
-                if let Some(id) = pidfd_id {
-                    self.outgoing.push(ForkerMessage::PidFd {
-                        id,
-                        success: false,
-                        pid: 0,
-                    });
-                }
+                self.outgoing.push(ForkerMessage::PidFd {
+                    id,
+                    success: false,
+                    pid: 0,
+                });
                 self.outgoing.push(ForkerMessage::Log {
                     level: log::Level::Error as usize,
                     msg: ErrorFmt(e).to_string(),
@@ -445,18 +556,18 @@ impl Forker {
         };
         match res {
             Forked::Parent { pid, pidfd } => {
-                if let Some(id) = pidfd_id {
-                    self.fds.borrow_mut().push(Rc::new(pidfd));
-                    self.outgoing.push(ForkerMessage::PidFd {
-                        id,
-                        success: true,
-                        pid,
-                    });
-                }
+                let pidfd = Rc::new(pidfd);
+                self.fds.borrow_mut().push(pidfd.clone());
+                self.outgoing.push(ForkerMessage::PidFd {
+                    id,
+                    success: true,
+                    pid,
+                });
                 drop(write);
                 let slf = self.clone();
                 let spawn = self.ae.spawn(async move {
                     let read = Rc::new(read);
+                    let mut spawn_failed = false;
                     if let Err(e) = slf.ring.readable(&read).await {
                         log::error!(
                             "Cannot wait for the child fd to become readable: {}",
@@ -466,18 +577,23 @@ impl Forker {
                         let mut s = String::new();
                         let _ = Fd::new(read.raw()).read_to_string(&mut s);
                         if s.len() > 0 {
+                            spawn_failed = true;
                             slf.outgoing.push(ForkerMessage::Log {
                                 level: log::Level::Error as _,
                                 msg: format!("Could not spawn `{}`: {}", prog, s),
                             });
                         }
                     }
+                    slf.wait_for_exit(id, pid, &pidfd, spawn_failed).await;
                     slf.pending_spawns.remove(&pid);
                 });
                 self.pending_spawns.set(pid, spawn);
             }
             Forked::Child { .. } => {
                 let err = (|| {
+                    if let Some(stdout) = stdout {
+                        uapi::dup2(stdout.raw(), 1).unwrap();
+                    }
                     if let Some(stderr) = stderr {
                         uapi::dup2(stderr.raw(), 2).unwrap();
                     }
@@ -515,6 +631,50 @@ impl Forker {
             }
         }
     }
+
+    async fn wait_for_exit(&self, id: u32, pid: c::pid_t, pidfd: &Rc<OwnedFd>, spawn_failed: bool) {
+        let status = self.wait_for_exit2(pid, pidfd).await;
+        let status = match status {
+            _ if spawn_failed => ExitStatus::SpawnFailed,
+            Some(status) if c::WIFSIGNALED(status) => ExitStatus::Signaled(c::WTERMSIG(status)),
+            Some(status) => ExitStatus::Exited(c::WEXITSTATUS(status)),
+            None => ExitStatus::Unknown,
+        };
+        self.outgoing.push(ForkerMessage::Exited { id, status });
+    }
+
+    async fn wait_for_exit2(&self, pid: c::pid_t, pidfd: &Rc<OwnedFd>) -> Option<c::c_int> {
+        let mut flags = 0;
+        if let Err(e) = self.ring.readable(pidfd).await {
+            self.outgoing.push(ForkerMessage::Log {
+                level: log::Level::Error as _,
+                msg: format!(
+                    "Cannot wait for the child pidfd to become readable: {}",
+                    ErrorFmt(e)
+                ),
+            });
+            // Reap the child if it has already exited. Otherwise we have to leave it
+            // alone to not block the forker.
+            flags = c::WNOHANG;
+        }
+        loop {
+            match uapi::waitpid(pid, flags) {
+                Ok((0, _)) => return None,
+                Ok((_, status)) => return Some(status),
+                Err(Errno(c::EINTR)) => {}
+                Err(e) => {
+                    self.outgoing.push(ForkerMessage::Log {
+                        level: log::Level::Error as _,
+                        msg: format!(
+                            "Could not retrieve the exit status of a child: {}",
+                            ErrorFmt(crate::utils::oserror::OsError::from(e))
+                        ),
+                    });
+                    return None;
+                }
+            }
+        }
+    }
 }
 
 #[derive(Debug, Error)]
@@ -546,7 +706,6 @@ fn reset_signals() {
         for sig in 1..=NSIG {
             c::signal(sig, c::SIG_DFL);
         }
-        c::signal(c::SIGCHLD, c::SIG_IGN);
     }
 }
 
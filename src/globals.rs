@@ -3,6 +3,7 @@ use {
         backend::Backend,
         client::Client,
         ifs::{
+            ext_idle_notifier_v1::ExtIdleNotifierV1Global,
             ext_session_lock_manager_v1::ExtSessionLockManagerV1Global,
             ipc::{
                 wl_data_device_manager::WlDataDeviceManagerGlobal,
@@ -14,6 +15,7 @@ use {
             wl_output::WlOutputGlobal,
             wl_registry::WlRegistry,
             wl_seat::{
+                wp_cursor_shape_manager_v1::WpCursorShapeManagerV1Global,
                 zwp_pointer_constraints_v1::ZwpPointerConstraintsV1Global,
                 zwp_relative_pointer_manager_v1::ZwpRelativePointerManagerV1Global, WlSeatGlobal,
             },
@@ -24,6 +26,7 @@ use {
             wp_presentation::WpPresentationGlobal,
             wp_tearing_control_manager_v1::WpTearingControlManagerV1Global,
             wp_viewporter::WpViewporterGlobal,
+            xdg_activation_v1::XdgActivationV1Global,
             xdg_wm_base::XdgWmBaseGlobal,
             zwlr_layer_shell_v1::ZwlrLayerShellV1Global,
             zwlr_screencopy_manager_v1::ZwlrScreencopyManagerV1Global,
@@ -156,6 +159,9 @@ impl Globals {
         add_singleton!(ZwpPointerConstraintsV1Global);
         add_singleton!(XwaylandShellV1Global);
         add_singleton!(WpTearingControlManagerV1Global);
+        add_singleton!(ExtIdleNotifierV1Global);
+        add_singleton!(WpCursorShapeManagerV1Global);
+        add_singleton!(XdgActivationV1Global);
     }
 
     pub fn add_backend_singletons(&self, backend: &Rc<dyn Backend>) {
@@ -1,3 +1,5 @@
+pub mod ext_idle_notification_v1;
+pub mod ext_idle_notifier_v1;
 pub mod ext_session_lock_manager_v1;
 pub mod ext_session_lock_v1;
 pub mod ipc;
@@ -32,6 +34,8 @@ pub mod wp_presentation;
 pub mod wp_presentation_feedback;
 pub mod wp_tearing_control_manager_v1;
 pub mod wp_viewporter;
+pub mod xdg_activation_token_v1;
+pub mod xdg_activation_v1;
 pub mod xdg_positioner;
 pub mod xdg_wm_base;
 pub mod zwlr_layer_shell_v1;
@@ -53,7 +53,9 @@ impl ExtSessionLockManagerV1 {
 
     fn lock(&self, msg: MsgParser<'_, '_>) -> Result<(), ExtSessionLockManagerV1Error> {
         let req: Lock = self.client.parse(self, msg)?;
-        let did_lock = self.client.state.lock.locked.get() == false;
+        let state = &self.client.state;
+        let was_locked = state.lock.locked.get();
+        let did_lock = !was_locked || state.lock.lock.get().is_none();
         let new = Rc::new(ExtSessionLockV1 {
             id: req.id,
             client: self.client.clone(),
@@ -64,10 +66,13 @@ impl ExtSessionLockManagerV1 {
         track!(new.client, new);
         self.client.add_client_obj(&new)?;
         if did_lock {
-            log::info!("Client {} locks the screen", self.client.id);
-            let state = &self.client.state;
-            for seat in state.globals.seats.lock().values() {
-                seat.prepare_for_lock();
+            if was_locked {
+                log::info!("Client {} takes over the screen lock", self.client.id);
+            } else {
+                log::info!("Client {} locks the screen", self.client.id);
+                for seat in state.globals.seats.lock().values() {
+                    seat.prepare_for_lock();
+                }
             }
             state.lock.locked.set(true);
             state.lock.lock.set(Some(new.clone()));
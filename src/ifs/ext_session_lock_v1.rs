@@ -35,11 +35,18 @@ impl ExtSessionLockV1 {
         self.finished.set(true);
     }
 
-    fn destroy(&self, msg: MsgParser<'_, '_>) -> Result<(), ExtSessionLockV1Error> {
-        let _req: Destroy = self.client.parse(self, msg)?;
+    fn detach(&self) {
         if !self.finished.get() {
-            self.client.state.lock.lock.take();
+            let state = &self.client.state;
+            if state.lock.lock.take().is_some() {
+                state.locker_died();
+            }
         }
+    }
+
+    fn destroy(&self, msg: MsgParser<'_, '_>) -> Result<(), ExtSessionLockV1Error> {
+        let _req: Destroy = self.client.parse(self, msg)?;
+        self.detach();
         self.client.remove_obj(self)?;
         Ok(())
     }
@@ -111,9 +118,7 @@ impl Object for ExtSessionLockV1 {
     }
 
     fn break_loops(&self) {
-        if !self.finished.get() {
-            self.client.state.lock.lock.take();
-        }
+        self.detach();
     }
 }
 
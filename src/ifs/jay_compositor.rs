@@ -11,7 +11,7 @@ use {
         },
         leaks::Tracker,
         object::Object,
-        screenshoter::take_screenshot,
+        screenshoter::{take_screenshot, Screenshot},
         utils::{
             buffd::{MsgParser, MsgParserError},
             clonecell::CloneCell,
@@ -133,11 +133,11 @@ impl JayCompositor {
         track!(self.client, ss);
         self.client.add_client_obj(&ss)?;
         match take_screenshot(&self.client.state) {
-            Ok(s) => {
-                let dmabuf = s.bo.dmabuf();
+            Ok(Screenshot::Dmabuf { drm, bo }) => {
+                let dmabuf = bo.dmabuf();
                 let plane = &dmabuf.planes[0];
                 ss.send_dmabuf(
-                    &s.drm,
+                    &drm,
                     &plane.fd,
                     dmabuf.width,
                     dmabuf.height,
@@ -145,6 +145,14 @@ impl JayCompositor {
                     plane.stride,
                 );
             }
+            Ok(Screenshot::Shm {
+                fd,
+                width,
+                height,
+                stride,
+            }) => {
+                ss.send_shm(&fd, width, height, stride);
+            }
             Err(e) => {
                 let msg = ErrorFmt(e).to_string();
                 ss.send_error(&msg);
@@ -24,10 +24,12 @@ impl JayRenderCtx {
     pub fn send_render_ctx(&self, ctx: Option<&Rc<RenderContext>>) {
         let mut fd = None;
         if let Some(ctx) = ctx {
-            match ctx.gbm.drm.dup_render() {
-                Ok(d) => fd = Some(d.fd().clone()),
-                Err(e) => {
-                    log::error!("Could not dup drm fd: {}", ErrorFmt(e));
+            if let Ok(gbm) = ctx.gbm() {
+                match gbm.drm.dup_render() {
+                    Ok(d) => fd = Some(d.fd().clone()),
+                    Err(e) => {
+                        log::error!("Could not dup drm fd: {}", ErrorFmt(e));
+                    }
                 }
             }
         } else {
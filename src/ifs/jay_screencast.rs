@@ -207,7 +207,9 @@ impl JayScreencast {
                 if self.linear.get() {
                     flags |= GBM_BO_USE_LINEAR;
                 }
-                let buffer = ctx.gbm.create_bo(mode.width, mode.height, &format, flags)?;
+                let buffer = ctx
+                    .gbm()?
+                    .create_bo(mode.width, mode.height, &format, flags)?;
                 let fb = ctx.dmabuf_img(buffer.dmabuf())?.to_framebuffer()?;
                 buffers.push(ScreencastBuffer {
                     dmabuf: buffer.dmabuf().clone(),
@@ -36,6 +36,16 @@ impl JayScreenshot {
         });
     }
 
+    pub fn send_shm(&self, fd: &Rc<OwnedFd>, width: i32, height: i32, stride: i32) {
+        self.client.event(Shm {
+            self_id: self.id,
+            fd: fd.clone(),
+            width: width as _,
+            height: height as _,
+            stride: stride as _,
+        });
+    }
+
     pub fn send_error(&self, msg: &str) {
         self.client.event(Error {
             self_id: self.id,
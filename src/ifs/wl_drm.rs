@@ -43,8 +43,13 @@ impl WlDrmGlobal {
         });
         track!(client, obj);
         client.add_client_obj(&obj)?;
-        if let Some(rc) = client.state.render_ctx.get() {
-            obj.send_device(&rc.render_node());
+        if let Some(node) = client
+            .state
+            .render_ctx
+            .get()
+            .and_then(|rc| rc.render_node())
+        {
+            obj.send_device(&node);
             obj.send_capabilities(PRIME);
         }
         Ok(())
@@ -1,9 +1,12 @@
 mod event_handling;
 mod kb_owner;
 mod pointer_owner;
+mod shortcuts;
 pub mod wl_keyboard;
 pub mod wl_pointer;
 pub mod wl_touch;
+pub mod wp_cursor_shape_device_v1;
+pub mod wp_cursor_shape_manager_v1;
 pub mod zwp_pointer_constraints_v1;
 pub mod zwp_relative_pointer_manager_v1;
 pub mod zwp_relative_pointer_v1;
@@ -17,6 +20,7 @@ use {
         fixed::Fixed,
         globals::{Global, GlobalName},
         ifs::{
+            ext_idle_notification_v1::ExtIdleNotificationV1,
             ipc,
             ipc::{
                 wl_data_device::{ClipboardIpc, WlDataDevice},
@@ -30,21 +34,22 @@ use {
             wl_seat::{
                 kb_owner::KbOwnerHolder,
                 pointer_owner::PointerOwnerHolder,
+                shortcuts::Shortcuts,
                 wl_keyboard::{WlKeyboard, WlKeyboardError, REPEAT_INFO_SINCE},
                 wl_pointer::WlPointer,
                 wl_touch::WlTouch,
                 zwp_pointer_constraints_v1::{SeatConstraint, SeatConstraintStatus},
                 zwp_relative_pointer_v1::ZwpRelativePointerV1,
             },
-            wl_surface::WlSurface,
+            wl_surface::{xdg_surface::xdg_popup::XdgPopup, WlSurface},
         },
         leaks::Tracker,
         object::Object,
         rect::Rect,
         state::State,
         tree::{
-            generic_node_visitor, ContainerNode, ContainerSplit, Direction, FloatNode, FoundNode,
-            Node, OutputNode, WorkspaceNode,
+            generic_node_visitor, ContainerLayout, ContainerNode, ContainerSplit, Direction,
+            FloatNode, FoundNode, Node, OutputNode, ToplevelNode, WindowMenuNode, WorkspaceNode,
         },
         utils::{
             asyncevent::AsyncEvent,
@@ -57,13 +62,13 @@ use {
             rc_eq::rc_eq,
         },
         wire::{
-            wl_seat::*, WlDataDeviceId, WlKeyboardId, WlPointerId, WlSeatId,
-            ZwpPrimarySelectionDeviceV1Id, ZwpRelativePointerV1Id,
+            wl_seat::*, ExtIdleNotificationV1Id, WlDataDeviceId, WlKeyboardId, WlPointerId,
+            WlSeatId, ZwpPrimarySelectionDeviceV1Id, ZwpRelativePointerV1Id,
         },
-        xkbcommon::{XkbKeymap, XkbState},
+        xkbcommon::{ModifierState, XkbKeymap, XkbState},
     },
     ahash::{AHashMap, AHashSet},
-    jay_config::keyboard::mods::Modifiers,
+    jay_config::{input::ScrollDirection, keyboard::mods::Modifiers, Axis},
     smallvec::SmallVec,
     std::{
         cell::{Cell, RefCell},
@@ -77,7 +82,7 @@ use {
 };
 
 pub const POINTER: u32 = 1;
-const KEYBOARD: u32 = 2;
+pub const KEYBOARD: u32 = 2;
 #[allow(dead_code)]
 const TOUCH: u32 = 4;
 
@@ -85,6 +90,16 @@ const TOUCH: u32 = 4;
 const MISSING_CAPABILITY: u32 = 0;
 
 pub const BTN_LEFT: u32 = 0x110;
+pub const BTN_RIGHT: u32 = 0x111;
+
+bitflags! {
+    ResizeEdges: u32;
+
+    RESIZE_TOP    = 1 << 0,
+    RESIZE_BOTTOM = 1 << 1,
+    RESIZE_LEFT   = 1 << 2,
+    RESIZE_RIGHT  = 1 << 3,
+}
 
 pub const SEAT_NAME_SINCE: u32 = 2;
 
@@ -135,8 +150,10 @@ pub struct WlSeatGlobal {
         >,
     >,
     repeat_rate: Cell<(i32, i32)>,
+    seat_kb_map: CloneCell<Rc<XkbKeymap>>,
     kb_map: CloneCell<Rc<XkbKeymap>>,
     kb_state: RefCell<XkbState>,
+    remember_kb_group: Cell<bool>,
     cursor: CloneCell<Option<Rc<dyn Cursor>>>,
     tree_changed: Rc<AsyncEvent>,
     selection: CloneCell<Option<Rc<WlDataSource>>>,
@@ -145,8 +162,16 @@ pub struct WlSeatGlobal {
     primary_selection_serial: Cell<u32>,
     pointer_owner: PointerOwnerHolder,
     kb_owner: KbOwnerHolder,
+    popup_grabs: RefCell<Vec<Rc<XdgPopup>>>,
+    window_menu: CloneCell<Option<Rc<WindowMenuNode>>>,
     dropped_dnd: RefCell<Option<DroppedDnd>>,
-    shortcuts: CopyHashMap<(u32, u32), Modifiers>,
+    shortcuts: Shortcuts,
+    last_pressed_key: Cell<Option<u32>>,
+    button_bindings: CopyHashMap<(u32, u32), ()>,
+    scroll_bindings: CopyHashMap<(u32, Axis, ScrollDirection), ()>,
+    consumed_buttons: RefCell<AHashSet<u32>>,
+    scroll_binding_v120: [Cell<i32>; 2],
+    window_drag_mods: Cell<Modifiers>,
     queue_link: Cell<Option<LinkedNode<Rc<Self>>>>,
     tree_changed_handler: Cell<Option<SpawnedFuture<()>>>,
     output: CloneCell<Rc<OutputNode>>,
@@ -155,6 +180,7 @@ pub struct WlSeatGlobal {
     cursor_size: Cell<u32>,
     hardware_cursor: Cell<bool>,
     constraint: CloneCell<Option<Rc<SeatConstraint>>>,
+    idle_notifications: CopyHashMap<(ClientId, ExtIdleNotificationV1Id), Rc<ExtIdleNotificationV1>>,
 }
 
 const CHANGE_CURSOR_MOVED: u32 = 1 << 0;
@@ -189,8 +215,10 @@ impl WlSeatGlobal {
             data_devices: RefCell::new(Default::default()),
             primary_selection_devices: RefCell::new(Default::default()),
             repeat_rate: Cell::new((25, 250)),
+            seat_kb_map: CloneCell::new(state.default_keymap.clone()),
             kb_map: CloneCell::new(state.default_keymap.clone()),
             kb_state: RefCell::new(state.default_keymap.state().unwrap()),
+            remember_kb_group: Cell::new(false),
             cursor: Default::default(),
             tree_changed: Default::default(),
             selection: Default::default(),
@@ -199,8 +227,16 @@ impl WlSeatGlobal {
             primary_selection_serial: Cell::new(0),
             pointer_owner: Default::default(),
             kb_owner: Default::default(),
+            popup_grabs: Default::default(),
+            window_menu: Default::default(),
             dropped_dnd: RefCell::new(None),
             shortcuts: Default::default(),
+            last_pressed_key: Cell::new(None),
+            button_bindings: Default::default(),
+            scroll_bindings: Default::default(),
+            consumed_buttons: Default::default(),
+            scroll_binding_v120: Default::default(),
+            window_drag_mods: Default::default(),
             queue_link: Cell::new(None),
             tree_changed_handler: Cell::new(None),
             output: CloneCell::new(state.dummy_output.get().unwrap()),
@@ -209,6 +245,7 @@ impl WlSeatGlobal {
             cursor_size: Cell::new(DEFAULT_CURSOR_SIZE),
             hardware_cursor: Cell::new(state.globals.seats.len() == 0),
             constraint: Default::default(),
+            idle_notifications: Default::default(),
         });
         state.add_cursor_size(DEFAULT_CURSOR_SIZE);
         let seat = slf.clone();
@@ -345,10 +382,12 @@ impl WlSeatGlobal {
     }
 
     pub fn set_workspace(&self, ws: &Rc<WorkspaceNode>) {
-        let tl = match self.keyboard_node.get().node_toplevel() {
-            Some(tl) => tl,
-            _ => return,
-        };
+        if let Some(tl) = self.keyboard_node.get().node_toplevel() {
+            self.set_tl_workspace(tl, ws);
+        }
+    }
+
+    pub fn set_tl_workspace(&self, tl: Rc<dyn ToplevelNode>, ws: &Rc<WorkspaceNode>) {
         if tl.tl_data().is_fullscreen.get() {
             return;
         }
@@ -392,6 +431,30 @@ impl WlSeatGlobal {
             .set(Some(self.state.seat_queue.add_last(self.clone())));
     }
 
+    pub fn add_idle_notification(&self, notification: &Rc<ExtIdleNotificationV1>) {
+        self.idle_notifications.set(
+            (notification.client.id, notification.id),
+            notification.clone(),
+        );
+    }
+
+    pub fn remove_idle_notification(&self, notification: &ExtIdleNotificationV1) {
+        self.idle_notifications
+            .remove(&(notification.client.id, notification.id));
+    }
+
+    pub fn idle_inhibited_changed(&self) {
+        for notification in self.idle_notifications.lock().values() {
+            notification.inhibited_changed.trigger();
+        }
+    }
+
+    pub fn idle_activity(&self) {
+        for notification in self.idle_notifications.lock().values() {
+            notification.activity.trigger();
+        }
+    }
+
     pub fn disable_pointer_constraint(&self) {
         if let Some(constraint) = self.constraint.get() {
             constraint.deactivate();
@@ -447,15 +510,29 @@ impl WlSeatGlobal {
     }
 
     pub fn set_keymap(&self, keymap: &Rc<XkbKeymap>) {
-        let state = match keymap.state() {
+        self.seat_kb_map.set(keymap.clone());
+        self.set_effective_keymap(keymap);
+    }
+
+    /// Sets the keymap used by the seat and sends it to all keyboards.
+    ///
+    /// Returns `false` if no state could be created for the keymap.
+    fn set_effective_keymap(&self, keymap: &Rc<XkbKeymap>) -> bool {
+        let mut state = match keymap.state() {
             Ok(s) => s,
             Err(e) => {
                 log::error!("Could not create keymap state: {}", ErrorFmt(e));
-                return;
+                return false;
             }
         };
+        let old_mods = self.kb_state.borrow().mods();
+        state.apply_locked(&old_mods);
+        let group = state.mods().group;
         self.kb_map.set(keymap.clone());
         *self.kb_state.borrow_mut() = state;
+        if group != old_mods.group {
+            self.kb_group_changed(group);
+        }
         let bindings = self.bindings.borrow_mut();
         for (id, client) in bindings.iter() {
             for seat in client.values() {
@@ -472,6 +549,71 @@ impl WlSeatGlobal {
                 }
             }
         }
+        true
+    }
+
+    pub fn kb_group(&self) -> u32 {
+        self.kb_state.borrow().mods().group
+    }
+
+    pub fn num_kb_groups(&self) -> u32 {
+        self.kb_map.get().num_groups()
+    }
+
+    pub fn kb_group_name(&self, group: u32) -> Option<String> {
+        self.kb_map.get().group_name(group)
+    }
+
+    pub fn set_kb_group(&self, group: u32) {
+        if let Some(mods) = self.update_kb_group(group) {
+            self.keyboard_node.get().node_on_mods(self, mods);
+        }
+    }
+
+    /// Updates the group without sending the modifiers to the focused node.
+    fn update_kb_group(&self, group: u32) -> Option<ModifierState> {
+        let old_group = self.kb_group();
+        let mods = self.kb_state.borrow_mut().set_group(group)?;
+        self.state.for_each_seat_tester(|t| {
+            t.send_modifiers(self.id, &mods);
+        });
+        if mods.group != old_group {
+            self.kb_group_changed(mods.group);
+        }
+        Some(mods)
+    }
+
+    pub fn next_kb_group(&self) {
+        let num = self.num_kb_groups().max(1);
+        self.set_kb_group((self.kb_group() + 1) % num);
+    }
+
+    pub fn prev_kb_group(&self) {
+        let num = self.num_kb_groups().max(1);
+        self.set_kb_group((self.kb_group() + num - 1) % num);
+    }
+
+    pub fn set_remember_kb_group(&self, remember: bool) {
+        self.remember_kb_group.set(remember);
+    }
+
+    /// Stores the active group in the toplevel of `old` and restores the group stored in
+    /// the toplevel of `new`.
+    fn swap_kb_group(&self, old: &Rc<dyn Node>, new: &Rc<dyn Node>) {
+        if let Some(tl) = old.clone().node_toplevel() {
+            tl.tl_data().kb_groups.insert(self.id, self.kb_group());
+        }
+        if let Some(tl) = new.clone().node_toplevel() {
+            if let Some(group) = tl.tl_data().kb_groups.get(&self.id) {
+                self.update_kb_group(group);
+            }
+        }
+    }
+
+    fn kb_group_changed(&self, group: u32) {
+        if let Some(config) = self.state.config.get() {
+            config.keyboard_group_changed(self.id, group);
+        }
     }
 
     pub fn prepare_for_lock(self: &Rc<Self>) {
@@ -546,6 +688,26 @@ impl WlSeatGlobal {
         }
     }
 
+    pub fn get_hide_titles(&self) -> Option<bool> {
+        self.kb_parent_container().map(|c| c.hides_titles())
+    }
+
+    pub fn set_hide_titles(&self, hide: bool) {
+        if let Some(c) = self.kb_parent_container() {
+            c.set_hide_titles(hide);
+        }
+    }
+
+    pub fn get_layout(&self) -> Option<ContainerLayout> {
+        self.kb_parent_container().map(|c| c.layout.get())
+    }
+
+    pub fn set_layout(&self, layout: ContainerLayout) {
+        if let Some(c) = self.kb_parent_container() {
+            c.set_layout(layout);
+        }
+    }
+
     pub fn set_split(&self, axis: ContainerSplit) {
         if let Some(c) = self.kb_parent_container() {
             c.set_split(axis);
@@ -590,10 +752,12 @@ impl WlSeatGlobal {
     }
 
     pub fn set_floating(self: &Rc<Self>, floating: bool) {
-        let tl = match self.keyboard_node.get().node_toplevel() {
-            Some(tl) => tl,
-            _ => return,
-        };
+        if let Some(tl) = self.keyboard_node.get().node_toplevel() {
+            self.set_tl_floating(tl, floating);
+        }
+    }
+
+    pub fn set_tl_floating(&self, tl: Rc<dyn ToplevelNode>, floating: bool) {
         let data = tl.tl_data();
         if data.is_fullscreen.get() {
             return;
@@ -607,6 +771,7 @@ impl WlSeatGlobal {
         };
         if let Some(cn) = parent.node_into_containing_node() {
             if !floating {
+                data.in_scratchpad.set(false);
                 cn.cnode_remove_child2(tl.tl_as_node(), true);
                 self.state.map_tiled(tl);
             } else if let Some(ws) = data.workspace.get() {
@@ -617,6 +782,36 @@ impl WlSeatGlobal {
         }
     }
 
+    pub fn toggle_sticky(&self) {
+        if let Some(tl) = self.keyboard_node.get().node_toplevel() {
+            if let Some(float) = tl.tl_data().parent.get().and_then(|p| p.node_into_float()) {
+                float.sticky.set(!float.sticky.get());
+            }
+        }
+    }
+
+    pub fn minimize(&self) {
+        if let Some(tl) = self.keyboard_node.get().node_toplevel() {
+            tl.tl_data().minimize(tl.clone());
+        }
+    }
+
+    pub fn restore_last_minimized(&self) {
+        self.get_output()
+            .ensure_workspace()
+            .restore_last_minimized();
+    }
+
+    pub fn move_to_scratchpad(&self) {
+        if let Some(tl) = self.keyboard_node.get().node_toplevel() {
+            self.state.move_to_scratchpad(tl);
+        }
+    }
+
+    pub fn toggle_scratchpad(self: &Rc<Self>, filter: Option<&str>) {
+        self.state.toggle_scratchpad(self, filter);
+    }
+
     pub fn get_rate(&self) -> (i32, i32) {
         self.repeat_rate.get()
     }
@@ -668,6 +863,36 @@ impl WlSeatGlobal {
         }
     }
 
+    pub fn resize_focused(&self, direction: Direction, amount: i32) {
+        let tl = match self.keyboard_node.get().node_toplevel() {
+            Some(tl) => tl,
+            _ => return,
+        };
+        if tl.tl_data().is_fullscreen.get() {
+            return;
+        }
+        if let Some(parent) = tl.tl_data().parent.get() {
+            if let Some(c) = parent.node_into_container() {
+                if !c.resize_child(tl.tl_as_node(), direction, amount) {
+                    let opposite = match direction {
+                        Direction::Left => Direction::Right,
+                        Direction::Down => Direction::Up,
+                        Direction::Up => Direction::Down,
+                        Direction::Right => Direction::Left,
+                        Direction::Unspecified => return,
+                    };
+                    c.resize_child(tl.tl_as_node(), opposite, amount);
+                }
+            }
+        }
+    }
+
+    pub fn equalize(&self) {
+        if let Some(c) = self.kb_parent_container() {
+            c.equalize();
+        }
+    }
+
     fn set_selection_<T: ipc::IpcVtable>(
         self: &Rc<Self>,
         field: &CloneCell<Option<Rc<T::Source>>>,
@@ -710,6 +935,12 @@ impl WlSeatGlobal {
             .start_drag(self, origin, source, icon, serial)
     }
 
+    /// Starts an interactive resize of the toplevel if the pointer is currently grabbed by
+    /// one of its surfaces.
+    pub fn start_resize(self: &Rc<Self>, node: Rc<dyn ToplevelNode>, edges: ResizeEdges) {
+        self.pointer_owner.start_resize(self, node, edges)
+    }
+
     pub fn cancel_dnd(self: &Rc<Self>) {
         self.pointer_owner.cancel_dnd(self);
     }
@@ -769,6 +1000,10 @@ impl WlSeatGlobal {
         }
     }
 
+    pub fn desired_known_cursor(&self) -> Option<KnownCursor> {
+        self.desired_known_cursor.get()
+    }
+
     pub fn set_known_cursor(&self, cursor: KnownCursor) {
         self.desired_known_cursor.set(Some(cursor));
         let cursors = match self.state.cursors.get() {
@@ -778,16 +1013,7 @@ impl WlSeatGlobal {
                 return;
             }
         };
-        let tpl = match cursor {
-            KnownCursor::Default => &cursors.default,
-            KnownCursor::Pointer => &cursors.pointer,
-            KnownCursor::ResizeLeftRight => &cursors.resize_left_right,
-            KnownCursor::ResizeTopBottom => &cursors.resize_top_bottom,
-            KnownCursor::ResizeTopLeft => &cursors.resize_top_left,
-            KnownCursor::ResizeTopRight => &cursors.resize_top_right,
-            KnownCursor::ResizeBottomLeft => &cursors.resize_bottom_left,
-            KnownCursor::ResizeBottomRight => &cursors.resize_bottom_right,
-        };
+        let tpl = cursors.get(cursor);
         self.set_cursor2(Some(tpl.instantiate(self.cursor_size.get())));
     }
 
@@ -848,11 +1074,16 @@ impl WlSeatGlobal {
         self.primary_selection.set(None);
         self.pointer_owner.clear();
         self.kb_owner.clear();
+        self.popup_grabs.borrow_mut().clear();
+        if let Some(menu) = self.window_menu.take() {
+            menu.unlink();
+        }
         *self.dropped_dnd.borrow_mut() = None;
         self.queue_link.set(None);
         self.tree_changed_handler.set(None);
         self.output.set(self.state.dummy_output.get().unwrap());
         self.constraint.take();
+        self.idle_notifications.clear();
     }
 
     pub fn id(&self) -> SeatId {
@@ -909,6 +1140,7 @@ impl Global for WlSeatGlobal {
         self.bindings.borrow_mut().clear();
         self.queue_link.take();
         self.tree_changed_handler.take();
+        self.idle_notifications.clear();
     }
 }
 
@@ -1,6 +1,6 @@
 use {
     crate::{
-        backend::{ConnectorId, InputEvent, KeyState, AXIS_120},
+        backend::{ConnectorId, InputEvent, KeyState, ScrollAxis, AXIS_120},
         client::{Client, ClientId},
         fixed::Fixed,
         ifs::{
@@ -12,6 +12,7 @@ use {
                 },
             },
             wl_seat::{
+                shortcuts::ShortcutMatch,
                 wl_keyboard::{self, WlKeyboard},
                 wl_pointer::{
                     self, PendingScroll, WlPointer, AXIS_DISCRETE_SINCE_VERSION,
@@ -21,23 +22,27 @@ use {
                 },
                 zwp_pointer_constraints_v1::{ConstraintType, SeatConstraintStatus},
                 zwp_relative_pointer_v1::ZwpRelativePointerV1,
-                Dnd, SeatId, WlSeat, WlSeatGlobal, CHANGE_CURSOR_MOVED,
+                Dnd, SeatId, WlSeat, WlSeatGlobal, BTN_LEFT, BTN_RIGHT, CHANGE_CURSOR_MOVED,
             },
             wl_surface::{xdg_surface::xdg_popup::XdgPopup, WlSurface},
         },
         state::DeviceHandlerData,
-        tree::{Direction, FloatNode, Node, ToplevelNode},
-        utils::{bitflags::BitflagsExt, clonecell::CloneCell, smallmap::SmallMap},
+        tree::{Direction, FloatNode, Node, NodeId, ToplevelNode, WindowMenuNode},
+        utils::{bitflags::BitflagsExt, clonecell::CloneCell, rc_eq::rc_eq, smallmap::SmallMap},
         wire::WlDataOfferId,
         xkbcommon::{ModifierState, XKB_KEY_DOWN, XKB_KEY_UP},
     },
-    jay_config::keyboard::{
-        mods::{Modifiers, CAPS, NUM},
-        syms::KeySym,
-        ModifiedKeySym,
+    jay_config::{
+        input::{button::Button, ScrollDirection},
+        keyboard::{
+            mods::{Modifiers, CAPS, NUM},
+            syms::KeySym,
+            BindingMode, KeyBinding,
+        },
+        Axis,
     },
     smallvec::SmallVec,
-    std::rc::Rc,
+    std::{mem, rc::Rc},
 };
 
 #[derive(Default)]
@@ -178,7 +183,7 @@ impl WlSeatGlobal {
                 time_usec,
                 key,
                 state,
-            } => self.key_event(time_usec, key, state),
+            } => self.key_event(dev, time_usec, key, state),
             InputEvent::ConnectorPosition {
                 time_usec,
                 connector,
@@ -202,7 +207,11 @@ impl WlSeatGlobal {
             InputEvent::Axis120 { dist, axis } => self.pointer_owner.axis_120(dist, axis),
             InputEvent::AxisPx { dist, axis } => self.pointer_owner.axis_px(dist, axis),
             InputEvent::AxisStop { axis } => self.pointer_owner.axis_stop(axis),
-            InputEvent::AxisFrame { time_usec } => self.pointer_owner.frame(dev, self, time_usec),
+            InputEvent::AxisFrame { time_usec } => {
+                if !self.scroll_binding_event() {
+                    self.pointer_owner.frame(dev, self, time_usec)
+                }
+            }
         }
     }
 
@@ -314,10 +323,99 @@ impl WlSeatGlobal {
         self.state.for_each_seat_tester(|t| {
             t.send_button(self.id, time_usec, button, state);
         });
+        if self.button_binding_event(button, state) {
+            return;
+        }
         self.pointer_owner.button(self, time_usec, button, state);
     }
 
-    fn key_event(&self, time_usec: u64, key: u32, key_state: KeyState) {
+    pub fn binding_mods(&self) -> Modifiers {
+        Modifiers(self.kb_state.borrow().mods().mods_effective & !(CAPS.0 | NUM.0))
+    }
+
+    /// Returns whether the event was consumed by a button binding.
+    fn button_binding_event(&self, button: u32, state: KeyState) -> bool {
+        if state == KeyState::Released {
+            return self.consumed_buttons.borrow_mut().remove(&button);
+        }
+        if self.state.lock.locked.get() {
+            return false;
+        }
+        let mods = self.binding_mods();
+        if !self.button_bindings.contains(&(mods.0, button)) {
+            return false;
+        }
+        self.consumed_buttons.borrow_mut().insert(button);
+        if let Some(config) = self.state.config.get() {
+            config.invoke_button_binding(self.id, mods, Button(button));
+        }
+        true
+    }
+
+    /// Discards the pending scroll events of axes consumed by scroll bindings. Returns
+    /// whether no scroll events are left to be forwarded.
+    fn scroll_binding_event(&self) -> bool {
+        if self.state.lock.locked.get() || self.scroll_bindings.is_empty() {
+            return false;
+        }
+        let mods = self.binding_mods();
+        let mut consumed = false;
+        for (scroll_axis, axis) in [
+            (ScrollAxis::Horizontal, Axis::Horizontal),
+            (ScrollAxis::Vertical, Axis::Vertical),
+        ] {
+            let delta = match self.pointer_owner.pending_v120(scroll_axis) {
+                Some(d) if d != 0 => d,
+                _ => continue,
+            };
+            let direction = match delta < 0 {
+                true => ScrollDirection::Negative,
+                false => ScrollDirection::Positive,
+            };
+            if !self.scroll_bindings.contains(&(mods.0, axis, direction)) {
+                continue;
+            }
+            consumed = true;
+            self.pointer_owner.discard_pending_axis(scroll_axis);
+            let acc = &self.scroll_binding_v120[scroll_axis as usize];
+            let mut total = acc.get();
+            if total.signum() == -delta.signum() {
+                total = 0;
+            }
+            total += delta;
+            while total.abs() >= AXIS_120 {
+                total -= AXIS_120 * total.signum();
+                if let Some(config) = self.state.config.get() {
+                    config.invoke_scroll_binding(self.id, mods, axis, direction);
+                }
+            }
+            acc.set(total);
+        }
+        if !consumed || self.pointer_owner.has_pending_axes() {
+            return false;
+        }
+        self.pointer_owner.discard_pending_scroll();
+        true
+    }
+
+    /// Switches to the keymap of the device that produced a key event if it differs from
+    /// the keymap that was last sent to clients.
+    fn update_device_keymap(&self, dev: &DeviceHandlerData) {
+        let keymap = match dev.keymap.get() {
+            Some(k) => k,
+            _ => self.seat_kb_map.get(),
+        };
+        if rc_eq(&keymap, &self.kb_map.get()) {
+            return;
+        }
+        if self.set_effective_keymap(&keymap) {
+            let mods = self.kb_state.borrow().mods();
+            self.keyboard_node.get().node_on_mods(self, mods);
+        }
+    }
+
+    fn key_event(&self, dev: &DeviceHandlerData, time_usec: u64, key: u32, key_state: KeyState) {
+        self.update_device_keymap(dev);
         let (state, xkb_dir) = {
             let mut pk = self.pressed_keys.borrow_mut();
             match key_state {
@@ -336,33 +434,63 @@ impl WlSeatGlobal {
             }
         };
         let mut shortcuts = SmallVec::<[_; 1]>::new();
+        let mut consumed = false;
         let new_mods;
+        let old_group;
         {
             let mut kb_state = self.kb_state.borrow_mut();
-            if !self.state.lock.locked.get() && state == wl_keyboard::PRESSED {
+            old_group = kb_state.mods().group;
+            let last_pressed_key = match key_state {
+                KeyState::Pressed => self.last_pressed_key.replace(Some(key)),
+                KeyState::Released => self.last_pressed_key.take(),
+            };
+            if !self.state.lock.locked.get() {
                 let old_mods = kb_state.mods();
+                let mods = Modifiers(old_mods.mods_effective & !(CAPS.0 | NUM.0));
                 let keysyms = kb_state.unmodified_keysyms(key);
                 for &sym in keysyms {
-                    let mods = old_mods.mods_effective & !(CAPS.0 | NUM.0);
-                    if let Some(mods) = self.shortcuts.get(&(mods, sym)) {
-                        shortcuts.push(ModifiedKeySym {
-                            mods,
-                            sym: KeySym(sym),
-                        });
+                    let mod_sym = mods | KeySym(sym);
+                    match key_state {
+                        KeyState::Pressed => match self.shortcuts.press(mod_sym) {
+                            ShortcutMatch::None => continue,
+                            ShortcutMatch::Prefix => consumed = true,
+                            ShortcutMatch::Binding(b) => shortcuts.push(b),
+                        },
+                        KeyState::Released if last_pressed_key == Some(key) => {
+                            match self.shortcuts.release(mod_sym) {
+                                Some(b) => shortcuts.push(b),
+                                _ => continue,
+                            }
+                        }
+                        KeyState::Released => {}
                     }
+                    break;
                 }
             }
             new_mods = kb_state.update(key, xkb_dir);
         }
+        if state == wl_keyboard::PRESSED
+            && !consumed
+            && shortcuts.is_empty()
+            && new_mods.is_none()
+            && self.shortcuts.has_pending_sequence()
+        {
+            // The key does not continue the pending key sequence.
+            self.shortcuts.abort_sequence();
+            consumed = true;
+        }
         self.state.for_each_seat_tester(|t| {
             t.send_key(self.id, time_usec, key, key_state);
         });
         let node = self.keyboard_node.get();
-        if shortcuts.is_empty() {
+        if state == wl_keyboard::RELEASED || (!consumed && shortcuts.is_empty()) {
             node.node_on_key(self, time_usec, key, state);
-        } else if let Some(config) = self.state.config.get() {
-            for shortcut in shortcuts {
-                config.invoke_shortcut(self.id(), &shortcut);
+        }
+        if !shortcuts.is_empty() {
+            if let Some(config) = self.state.config.get() {
+                for shortcut in &shortcuts {
+                    config.invoke_shortcut(self.id(), shortcut);
+                }
             }
         }
         if let Some(mods) = new_mods {
@@ -370,6 +498,9 @@ impl WlSeatGlobal {
                 t.send_modifiers(self.id, &mods);
             });
             node.node_on_mods(self, mods);
+            if mods.group != old_group {
+                self.kb_group_changed(mods.group);
+            }
         }
     }
 }
@@ -406,6 +537,119 @@ impl WlSeatGlobal {
         self.kb_owner.set_kb_node(self, node);
     }
 
+    pub fn has_popup_grabs(&self) -> bool {
+        !self.popup_grabs.borrow().is_empty()
+    }
+
+    pub fn top_popup_grab(&self) -> Option<Rc<XdgPopup>> {
+        self.popup_grabs.borrow().last().cloned()
+    }
+
+    /// Returns whether the node belongs to the client owning the active popup grab.
+    pub fn popup_grab_contains(&self, node: &dyn Node) -> bool {
+        let top = match self.top_popup_grab() {
+            Some(top) => top,
+            _ => return true,
+        };
+        match node.node_client() {
+            Some(client) => client.id == top.xdg.surface.client.id,
+            _ => false,
+        }
+    }
+
+    pub fn add_popup_grab(self: &Rc<Self>, popup: &Rc<XdgPopup>) {
+        if let Some(top) = self.top_popup_grab() {
+            if top.xdg.surface.client.id != popup.xdg.surface.client.id {
+                self.dismiss_popups();
+            }
+        }
+        self.popup_grabs.borrow_mut().push(popup.clone());
+        self.popup_grabs_changed();
+        self.focus_node(popup.xdg.surface.clone());
+    }
+
+    pub fn remove_popup_grab(self: &Rc<Self>, popup: &XdgPopup) {
+        let top = {
+            let mut grabs = self.popup_grabs.borrow_mut();
+            let id = popup.node_id();
+            match grabs.iter().position(|p| p.node_id() == id) {
+                Some(pos) => grabs.truncate(pos),
+                _ => return,
+            }
+            grabs.last().cloned()
+        };
+        self.popup_grabs_changed();
+        match top {
+            Some(top) => self.focus_node(top.xdg.surface.clone()),
+            _ => {
+                if self.keyboard_node.get().node_id() == self.state.root.node_id() {
+                    self.output
+                        .get()
+                        .node_do_focus(self, Direction::Unspecified);
+                }
+            }
+        }
+    }
+
+    pub fn dismiss_popups(self: &Rc<Self>) {
+        let popups = mem::take(&mut *self.popup_grabs.borrow_mut());
+        for popup in popups.iter().rev() {
+            popup.dismiss();
+        }
+        self.popup_grabs_changed();
+    }
+
+    fn popup_grabs_changed(self: &Rc<Self>) {
+        self.pointer_owner.popup_grabs_changed(self);
+        self.kb_owner.popup_grabs_changed(self);
+    }
+
+    pub fn window_menu(&self) -> Option<Rc<WindowMenuNode>> {
+        self.window_menu.get()
+    }
+
+    /// Shows the window menu of the toplevel at the given absolute position and gives it
+    /// the keyboard focus.
+    pub fn show_window_menu(self: &Rc<Self>, tl: Rc<dyn ToplevelNode>, x: i32, y: i32) {
+        self.close_window_menu();
+        let menu = WindowMenuNode::install(&self.state, self, tl, x, y);
+        self.window_menu.set(Some(menu.clone()));
+        self.focus_node(menu);
+    }
+
+    /// Closes the window menu and returns the keyboard focus to its toplevel.
+    pub fn close_window_menu(self: &Rc<Self>) {
+        let menu = match self.window_menu.take() {
+            Some(m) => m,
+            _ => return,
+        };
+        if self.keyboard_node.get().node_id() == menu.node_id() && menu.toplevel.node_visible() {
+            self.focus_toplevel(menu.toplevel.clone());
+        }
+        menu.destroy();
+    }
+
+    /// Called when a toplevel is destroyed. Destroys the window menu if it belongs to
+    /// this toplevel.
+    pub fn toplevel_destroyed(&self, tl: NodeId) {
+        let menu = match self.window_menu.get() {
+            Some(m) if m.toplevel.node_id() == tl => m,
+            _ => return,
+        };
+        self.window_menu.take();
+        menu.destroy();
+    }
+
+    /// Called when the window menu loses the keyboard focus.
+    pub fn remove_window_menu(&self, menu: &WindowMenuNode) {
+        if let Some(m) = self.window_menu.get() {
+            if m.node_id() == menu.node_id() {
+                self.window_menu.take();
+            }
+        }
+        menu.unlink();
+    }
+
     fn offer_selection<T: ipc::IpcVtable>(
         &self,
         field: &CloneCell<Option<Rc<T::Source>>>,
@@ -543,14 +787,54 @@ impl WlSeatGlobal {
 
     pub fn clear_shortcuts(&self) {
         self.shortcuts.clear();
+        self.button_bindings.clear();
+        self.scroll_bindings.clear();
+        self.window_drag_mods.take();
+    }
+
+    pub fn set_window_drag_modifier(&self, mods: Modifiers) {
+        self.window_drag_mods.set(mods);
+    }
+
+    /// Returns whether a press of `button` starts an interactive move or resize.
+    pub fn is_window_drag_button(&self, button: u32) -> bool {
+        let mods = self.window_drag_mods.get();
+        mods.0 != 0
+            && matches!(button, BTN_LEFT | BTN_RIGHT)
+            && !self.state.lock.locked.get()
+            && self.binding_mods() == mods
+    }
+
+    pub fn add_button_binding(&self, mods: Modifiers, button: Button) {
+        self.button_bindings.set((mods.0, button.0), ());
     }
 
-    pub fn add_shortcut(&self, mods: Modifiers, keysym: KeySym) {
-        self.shortcuts.set((mods.0, keysym.0), mods);
+    pub fn remove_button_binding(&self, mods: Modifiers, button: Button) {
+        self.button_bindings.remove(&(mods.0, button.0));
     }
 
-    pub fn remove_shortcut(&self, mods: Modifiers, keysym: KeySym) {
-        self.shortcuts.remove(&(mods.0, keysym.0));
+    pub fn add_scroll_binding(&self, mods: Modifiers, axis: Axis, direction: ScrollDirection) {
+        self.scroll_bindings.set((mods.0, axis, direction), ());
+    }
+
+    pub fn remove_scroll_binding(&self, mods: Modifiers, axis: Axis, direction: ScrollDirection) {
+        self.scroll_bindings.remove(&(mods.0, axis, direction));
+    }
+
+    pub fn add_shortcut(&self, binding: KeyBinding) {
+        self.shortcuts.add(binding);
+    }
+
+    pub fn remove_shortcut(&self, binding: &KeyBinding) {
+        self.shortcuts.remove(binding);
+    }
+
+    pub fn set_binding_mode(&self, mode: BindingMode) {
+        self.shortcuts.set_mode(mode);
+    }
+
+    pub fn binding_mode(&self) -> BindingMode {
+        self.shortcuts.mode()
     }
 
     pub fn trigger_tree_changed(&self) {
@@ -663,6 +947,9 @@ impl WlSeatGlobal {
 // Enter callbacks
 impl WlSeatGlobal {
     pub fn enter_toplevel(self: &Rc<Self>, n: Rc<dyn ToplevelNode>) {
+        if self.has_popup_grabs() {
+            return;
+        }
         if n.tl_accepts_keyboard_focus() && self.changes.get().contains(CHANGE_CURSOR_MOVED) {
             self.focus_toplevel(n);
         }
@@ -33,6 +33,20 @@ impl KbOwnerHolder {
         self.owner.get().set_kb_node(seat, node);
     }
 
+    pub fn popup_grabs_changed(&self, seat: &Rc<WlSeatGlobal>) {
+        if self.owner.get().is_base() {
+            self.set_base_owner(seat);
+        }
+    }
+
+    fn set_base_owner(&self, seat: &Rc<WlSeatGlobal>) {
+        if seat.has_popup_grabs() {
+            self.owner.set(Rc::new(PopupGrabKbOwner));
+        } else {
+            self.owner.set(self.default.clone());
+        }
+    }
+
     pub fn clear(&self) {
         self.owner.set(self.default.clone());
     }
@@ -42,10 +56,17 @@ struct DefaultKbOwner;
 
 struct GrabKbOwner;
 
+struct PopupGrabKbOwner;
+
 trait KbOwner {
     fn grab(&self, seat: &Rc<WlSeatGlobal>, node: Rc<dyn Node>) -> bool;
     fn ungrab(&self, seat: &Rc<WlSeatGlobal>);
     fn set_kb_node(&self, seat: &Rc<WlSeatGlobal>, node: Rc<dyn Node>);
+
+    /// Returns whether this owner is one of the owners used when no explicit grab is active.
+    fn is_base(&self) -> bool {
+        false
+    }
 }
 
 impl KbOwner for DefaultKbOwner {
@@ -72,6 +93,9 @@ impl KbOwner for DefaultKbOwner {
         if old.node_seat_state().unfocus(seat) {
             old.node_active_changed(false);
         }
+        if seat.remember_kb_group.get() {
+            seat.swap_kb_group(&old, &node);
+        }
 
         if node.node_seat_state().focus(seat) {
             node.node_active_changed(true);
@@ -80,6 +104,10 @@ impl KbOwner for DefaultKbOwner {
         node.clone().node_on_focus(seat);
         seat.keyboard_node.set(node.clone());
     }
+
+    fn is_base(&self) -> bool {
+        true
+    }
 }
 
 impl KbOwner for GrabKbOwner {
@@ -88,10 +116,32 @@ impl KbOwner for GrabKbOwner {
     }
 
     fn ungrab(&self, seat: &Rc<WlSeatGlobal>) {
-        seat.kb_owner.owner.set(seat.kb_owner.default.clone());
+        seat.kb_owner.set_base_owner(seat);
     }
 
     fn set_kb_node(&self, _seat: &Rc<WlSeatGlobal>, _node: Rc<dyn Node>) {
         // nothing
     }
 }
+
+impl KbOwner for PopupGrabKbOwner {
+    fn grab(&self, seat: &Rc<WlSeatGlobal>, node: Rc<dyn Node>) -> bool {
+        seat.dismiss_popups();
+        seat.kb_owner.default.grab(seat, node)
+    }
+
+    fn ungrab(&self, _seat: &Rc<WlSeatGlobal>) {
+        // nothing
+    }
+
+    fn set_kb_node(&self, seat: &Rc<WlSeatGlobal>, node: Rc<dyn Node>) {
+        if !seat.popup_grab_contains(&*node) {
+            seat.dismiss_popups();
+        }
+        seat.kb_owner.default.set_kb_node(seat, node);
+    }
+
+    fn is_base(&self) -> bool {
+        true
+    }
+}
@@ -1,16 +1,22 @@
 use {
     crate::{
         backend::{AxisSource, KeyState, ScrollAxis, AXIS_120},
+        client::ClientId,
+        cursor::KnownCursor,
         fixed::Fixed,
         ifs::{
             ipc,
             ipc::{wl_data_device::ClipboardIpc, wl_data_source::WlDataSource},
-            wl_seat::{wl_pointer::PendingScroll, Dnd, DroppedDnd, WlSeatError, WlSeatGlobal},
+            wl_seat::{
+                wl_pointer::PendingScroll, Dnd, DroppedDnd, ResizeEdges, WlSeatError, WlSeatGlobal,
+                BTN_RIGHT, RESIZE_BOTTOM, RESIZE_LEFT, RESIZE_RIGHT, RESIZE_TOP,
+            },
             wl_surface::WlSurface,
         },
+        rect::Rect,
         state::DeviceHandlerData,
-        tree::{FoundNode, Node},
-        utils::{clonecell::CloneCell, smallmap::SmallMap},
+        tree::{ContainerSplit, Direction, FoundNode, Node, ToplevelNode},
+        utils::{bitflags::BitflagsExt, clonecell::CloneCell, smallmap::SmallMap},
     },
     std::{cell::Cell, rc::Rc},
 };
@@ -52,6 +58,28 @@ impl PointerOwnerHolder {
         self.pending_scroll.stop[axis as usize].set(true);
     }
 
+    pub fn pending_v120(&self, axis: ScrollAxis) -> Option<i32> {
+        self.pending_scroll.v120[axis as usize].get()
+    }
+
+    pub fn discard_pending_scroll(&self) {
+        self.pending_scroll.take();
+    }
+
+    pub fn discard_pending_axis(&self, axis: ScrollAxis) {
+        let axis = axis as usize;
+        self.pending_scroll.v120[axis].take();
+        self.pending_scroll.px[axis].take();
+        self.pending_scroll.stop[axis].take();
+    }
+
+    pub fn has_pending_axes(&self) -> bool {
+        let ps = &self.pending_scroll;
+        (0..2).any(|axis| {
+            ps.v120[axis].get().is_some() || ps.px[axis].get().is_some() || ps.stop[axis].get()
+        })
+    }
+
     pub fn frame(&self, dev: &DeviceHandlerData, seat: &Rc<WlSeatGlobal>, time_usec: u64) {
         self.pending_scroll.time_usec.set(time_usec);
         let pending = self.pending_scroll.take();
@@ -111,6 +139,15 @@ impl PointerOwnerHolder {
         self.owner.get().cancel_dnd(seat)
     }
 
+    pub fn start_resize(
+        &self,
+        seat: &Rc<WlSeatGlobal>,
+        node: Rc<dyn ToplevelNode>,
+        edges: ResizeEdges,
+    ) {
+        self.owner.get().start_resize(seat, node, edges)
+    }
+
     pub fn revert_to_default(&self, seat: &Rc<WlSeatGlobal>) {
         self.owner.get().revert_to_default(seat)
     }
@@ -127,6 +164,20 @@ impl PointerOwnerHolder {
         self.owner.get().remove_dnd_icon()
     }
 
+    pub fn popup_grabs_changed(&self, seat: &Rc<WlSeatGlobal>) {
+        if self.owner.get().is_base() {
+            self.set_base_owner(seat);
+        }
+    }
+
+    fn set_base_owner(&self, seat: &Rc<WlSeatGlobal>) {
+        if seat.has_popup_grabs() {
+            self.owner.set(Rc::new(PopupGrabPointerOwner));
+        } else {
+            self.owner.set(self.default.clone());
+        }
+    }
+
     pub fn clear(&self) {
         self.owner.set(self.default.clone());
     }
@@ -149,16 +200,45 @@ trait PointerOwner {
     fn dnd_target_removed(&self, seat: &Rc<WlSeatGlobal>);
     fn dnd_icon(&self) -> Option<Rc<WlSurface>>;
     fn remove_dnd_icon(&self);
+
+    fn start_resize(
+        &self,
+        seat: &Rc<WlSeatGlobal>,
+        node: Rc<dyn ToplevelNode>,
+        edges: ResizeEdges,
+    ) {
+        let _ = seat;
+        let _ = node;
+        let _ = edges;
+    }
+
+    /// Returns whether this owner is one of the owners used when no button is pressed.
+    fn is_base(&self) -> bool {
+        false
+    }
 }
 
 struct DefaultPointerOwner;
 
+struct PopupGrabPointerOwner;
+
 struct GrabPointerOwner {
     buttons: SmallMap<u32, (), 1>,
     node: Rc<dyn Node>,
     serial: u32,
 }
 
+struct WindowOpPointerOwner {
+    button: u32,
+    node: Rc<dyn ToplevelNode>,
+    edges: ResizeEdges,
+    start_x: i32,
+    start_y: i32,
+    last_x: Cell<i32>,
+    last_y: Cell<i32>,
+    float_position: Option<Rect>,
+}
+
 struct DndPointerOwner {
     button: u32,
     dnd: Dnd,
@@ -168,30 +248,10 @@ struct DndPointerOwner {
     pos_y: Cell<Fixed>,
 }
 
-impl PointerOwner for DefaultPointerOwner {
-    fn button(&self, seat: &Rc<WlSeatGlobal>, time_usec: u64, button: u32, state: KeyState) {
-        if state != KeyState::Pressed {
-            return;
-        }
-        let pn = match seat.pointer_node() {
-            Some(n) => n,
-            _ => return,
-        };
-        let serial = seat.state.next_serial(pn.node_client().as_deref());
-        seat.pointer_owner.owner.set(Rc::new(GrabPointerOwner {
-            buttons: SmallMap::new_with(button, ()),
-            node: pn.clone(),
-            serial,
-        }));
-        pn.node_seat_state().add_pointer_grab(seat);
-        pn.node_on_button(seat, time_usec, button, state, serial);
-    }
-
-    fn axis_node(&self, seat: &Rc<WlSeatGlobal>) -> Option<Rc<dyn Node>> {
-        seat.pointer_node()
-    }
-
-    fn apply_changes(&self, seat: &Rc<WlSeatGlobal>) {
+impl DefaultPointerOwner {
+    /// Updates the pointer stack. If a client is given, the stack ends before the first
+    /// surface belonging to another client.
+    fn apply_changes2(&self, seat: &Rc<WlSeatGlobal>, client: Option<ClientId>) {
         let (x, y) = seat.pos.get();
         let mut found_tree = seat.found_tree.borrow_mut();
         let mut stack = seat.pointer_stack.borrow_mut();
@@ -205,6 +265,14 @@ impl PointerOwner for DefaultPointerOwner {
         seat.state
             .root
             .node_find_tree_at(x_int, y_int, &mut found_tree);
+        if let Some(client) = client {
+            let foreign = found_tree
+                .iter()
+                .position(|f| matches!(f.node.node_client_id(), Some(c) if c != client));
+            if let Some(pos) = foreign {
+                found_tree.truncate(pos);
+            }
+        }
         let mut divergence = found_tree.len().min(stack.len());
         for (i, (found, stack)) in found_tree.iter().zip(stack.iter()).enumerate() {
             if found.node.node_id() != stack.node_id() {
@@ -254,6 +322,45 @@ impl PointerOwner for DefaultPointerOwner {
         }
         found_tree.clear();
     }
+}
+
+impl PointerOwner for DefaultPointerOwner {
+    fn button(&self, seat: &Rc<WlSeatGlobal>, time_usec: u64, button: u32, state: KeyState) {
+        if state != KeyState::Pressed {
+            return;
+        }
+        if seat.is_window_drag_button(button) {
+            if let Some(owner) = WindowOpPointerOwner::new(seat, button) {
+                owner.start(seat);
+                return;
+            }
+        }
+        let pn = match seat.pointer_node() {
+            Some(n) => n,
+            _ => return,
+        };
+        if let Some(menu) = seat.window_menu() {
+            if menu.node_id() != pn.node_id() {
+                seat.close_window_menu();
+            }
+        }
+        let serial = seat.state.next_serial(pn.node_client().as_deref());
+        seat.pointer_owner.owner.set(Rc::new(GrabPointerOwner {
+            buttons: SmallMap::new_with(button, ()),
+            node: pn.clone(),
+            serial,
+        }));
+        pn.node_seat_state().add_pointer_grab(seat);
+        pn.node_on_button(seat, time_usec, button, state, serial);
+    }
+
+    fn axis_node(&self, seat: &Rc<WlSeatGlobal>) -> Option<Rc<dyn Node>> {
+        seat.pointer_node()
+    }
+
+    fn apply_changes(&self, seat: &Rc<WlSeatGlobal>) {
+        self.apply_changes2(seat, None)
+    }
 
     fn start_drag(
         &self,
@@ -288,6 +395,75 @@ impl PointerOwner for DefaultPointerOwner {
     fn remove_dnd_icon(&self) {
         // nothing
     }
+
+    fn is_base(&self) -> bool {
+        true
+    }
+}
+
+impl PointerOwner for PopupGrabPointerOwner {
+    fn button(&self, seat: &Rc<WlSeatGlobal>, time_usec: u64, button: u32, state: KeyState) {
+        if state == KeyState::Pressed {
+            let inside = match seat.pointer_node() {
+                Some(pn) => seat.popup_grab_contains(&*pn),
+                _ => false,
+            };
+            if !inside {
+                seat.dismiss_popups();
+                return;
+            }
+        }
+        DefaultPointerOwner.button(seat, time_usec, button, state)
+    }
+
+    fn axis_node(&self, seat: &Rc<WlSeatGlobal>) -> Option<Rc<dyn Node>> {
+        let pn = seat.pointer_node()?;
+        if seat.popup_grab_contains(&*pn) {
+            Some(pn)
+        } else {
+            None
+        }
+    }
+
+    fn apply_changes(&self, seat: &Rc<WlSeatGlobal>) {
+        let client = seat.top_popup_grab().map(|p| p.xdg.surface.client.id);
+        DefaultPointerOwner.apply_changes2(seat, client)
+    }
+
+    fn start_drag(
+        &self,
+        seat: &Rc<WlSeatGlobal>,
+        origin: &Rc<WlSurface>,
+        source: Option<Rc<WlDataSource>>,
+        icon: Option<Rc<WlSurface>>,
+        serial: u32,
+    ) -> Result<(), WlSeatError> {
+        DefaultPointerOwner.start_drag(seat, origin, source, icon, serial)
+    }
+
+    fn cancel_dnd(&self, seat: &Rc<WlSeatGlobal>) {
+        DefaultPointerOwner.cancel_dnd(seat)
+    }
+
+    fn revert_to_default(&self, seat: &Rc<WlSeatGlobal>) {
+        DefaultPointerOwner.revert_to_default(seat)
+    }
+
+    fn dnd_target_removed(&self, seat: &Rc<WlSeatGlobal>) {
+        DefaultPointerOwner.dnd_target_removed(seat)
+    }
+
+    fn dnd_icon(&self) -> Option<Rc<WlSurface>> {
+        DefaultPointerOwner.dnd_icon()
+    }
+
+    fn remove_dnd_icon(&self) {
+        DefaultPointerOwner.remove_dnd_icon()
+    }
+
+    fn is_base(&self) -> bool {
+        true
+    }
 }
 
 impl PointerOwner for GrabPointerOwner {
@@ -299,9 +475,7 @@ impl PointerOwner for GrabPointerOwner {
                     self.node.node_seat_state().remove_pointer_grab(seat);
                     // log::info!("button");
                     seat.tree_changed.trigger();
-                    seat.pointer_owner
-                        .owner
-                        .set(seat.pointer_owner.default.clone());
+                    seat.pointer_owner.set_base_owner(seat);
                 }
             }
             KeyState::Pressed => {
@@ -392,9 +566,30 @@ impl PointerOwner for GrabPointerOwner {
 
     fn revert_to_default(&self, seat: &Rc<WlSeatGlobal>) {
         self.node.node_seat_state().remove_pointer_grab(seat);
-        seat.pointer_owner
-            .owner
-            .set(seat.pointer_owner.default.clone());
+        seat.pointer_owner.set_base_owner(seat);
+    }
+
+    fn start_resize(
+        &self,
+        seat: &Rc<WlSeatGlobal>,
+        node: Rc<dyn ToplevelNode>,
+        edges: ResizeEdges,
+    ) {
+        let button = match self.buttons.iter().next() {
+            Some((b, _)) => b,
+            None => return,
+        };
+        if self.buttons.len() != 1 || !edges.is_some() {
+            return;
+        }
+        match self.node.clone().node_toplevel() {
+            Some(tl) if tl.node_id() == node.node_id() => {}
+            _ => return,
+        }
+        if let Some(owner) = WindowOpPointerOwner::for_node(seat, button, node, edges) {
+            self.node.node_seat_state().remove_pointer_grab(seat);
+            owner.start(seat);
+        }
     }
 
     fn dnd_target_removed(&self, seat: &Rc<WlSeatGlobal>) {
@@ -410,6 +605,259 @@ impl PointerOwner for GrabPointerOwner {
     }
 }
 
+impl WindowOpPointerOwner {
+    fn new(seat: &Rc<WlSeatGlobal>, button: u32) -> Option<Self> {
+        let node = seat
+            .pointer_stack
+            .borrow()
+            .iter()
+            .rev()
+            .find_map(|n| n.clone().node_toplevel())?;
+        let mut edges = ResizeEdges::none();
+        if button == BTN_RIGHT {
+            let (x, y) = seat.pos.get();
+            let (x, y) = (x.round_down(), y.round_down());
+            let pos = node.node_absolute_position();
+            edges |= match x < pos.x1() + pos.width() / 2 {
+                true => RESIZE_LEFT,
+                false => RESIZE_RIGHT,
+            };
+            edges |= match y < pos.y1() + pos.height() / 2 {
+                true => RESIZE_TOP,
+                false => RESIZE_BOTTOM,
+            };
+        }
+        Self::for_node(seat, button, node, edges)
+    }
+
+    fn for_node(
+        seat: &Rc<WlSeatGlobal>,
+        button: u32,
+        node: Rc<dyn ToplevelNode>,
+        edges: ResizeEdges,
+    ) -> Option<Self> {
+        let data = node.tl_data();
+        if data.is_fullscreen.get() {
+            return None;
+        }
+        let float_position = match data.parent.get()?.node_into_float() {
+            Some(f) => Some(f.position.get()),
+            _ => None,
+        };
+        let (x, y) = seat.pos.get();
+        let (x, y) = (x.round_down(), y.round_down());
+        Some(Self {
+            button,
+            edges,
+            node,
+            start_x: x,
+            start_y: y,
+            last_x: Cell::new(x),
+            last_y: Cell::new(y),
+            float_position,
+        })
+    }
+
+    fn start(self, seat: &Rc<WlSeatGlobal>) {
+        {
+            let mut stack = seat.pointer_stack.borrow_mut();
+            for node in stack.drain(1..).rev() {
+                node.node_on_leave(seat);
+                node.node_seat_state().leave(seat);
+            }
+        }
+        let left = self.edges.contains(RESIZE_LEFT);
+        let right = self.edges.contains(RESIZE_RIGHT);
+        let top = self.edges.contains(RESIZE_TOP);
+        let bottom = self.edges.contains(RESIZE_BOTTOM);
+        let cursor = match (left, right, top, bottom) {
+            (true, _, true, _) => KnownCursor::ResizeTopLeft,
+            (_, true, true, _) => KnownCursor::ResizeTopRight,
+            (true, _, _, true) => KnownCursor::ResizeBottomLeft,
+            (_, true, _, true) => KnownCursor::ResizeBottomRight,
+            (true, _, _, _) | (_, true, _, _) => KnownCursor::ResizeLeftRight,
+            (_, _, true, _) | (_, _, _, true) => KnownCursor::ResizeTopBottom,
+            _ => KnownCursor::Pointer,
+        };
+        seat.set_known_cursor(cursor);
+        seat.pointer_owner.owner.set(Rc::new(self));
+    }
+
+    fn finish(&self, seat: &Rc<WlSeatGlobal>) {
+        seat.set_known_cursor(KnownCursor::Default);
+        seat.pointer_owner.set_base_owner(seat);
+        seat.tree_changed.trigger();
+    }
+
+    fn update_float(&self, start: Rect, x: i32, y: i32) {
+        let float = match self.node.tl_data().parent.get() {
+            Some(p) => match p.node_into_float() {
+                Some(f) => f,
+                _ => return,
+            },
+            _ => return,
+        };
+        let (dx, dy) = (x - self.start_x, y - self.start_y);
+        if !self.edges.is_some() {
+            float.set_position(start.move_(dx, dy));
+            return;
+        }
+        let sizes = &float.state.theme.sizes;
+        let bw = sizes.border_width.get();
+        let th = sizes.title_height.get();
+        let (mut x1, mut y1, mut x2, mut y2) = (start.x1(), start.y1(), start.x2(), start.y2());
+        if self.edges.contains(RESIZE_LEFT) {
+            x1 = (x1 + dx).min(x2 - 2 * bw);
+        } else if self.edges.contains(RESIZE_RIGHT) {
+            x2 = (x2 + dx).max(x1 + 2 * bw);
+        }
+        if self.edges.contains(RESIZE_TOP) {
+            y1 = (y1 + dy).min(y2 - 2 * bw - th - 1);
+        } else if self.edges.contains(RESIZE_BOTTOM) {
+            y2 = (y2 + dy).max(y1 + 2 * bw + th + 1);
+        }
+        float.set_position(Rect::new(x1, y1, x2, y2).unwrap());
+    }
+
+    fn update_tiled(&self, x: i32, y: i32) {
+        let dx = x - self.last_x.replace(x);
+        let dy = y - self.last_y.replace(y);
+        let parent = match self.node.tl_data().parent.get() {
+            Some(p) => match p.node_into_container() {
+                Some(c) => c,
+                _ => return,
+            },
+            _ => return,
+        };
+        let node = self.node.tl_as_node();
+        if dx != 0 {
+            if self.edges.contains(RESIZE_LEFT) {
+                parent.resize_child(node, Direction::Left, -dx);
+            } else if self.edges.contains(RESIZE_RIGHT) {
+                parent.resize_child(node, Direction::Right, dx);
+            }
+        }
+        if dy != 0 {
+            if self.edges.contains(RESIZE_TOP) {
+                parent.resize_child(node, Direction::Up, -dy);
+            } else if self.edges.contains(RESIZE_BOTTOM) {
+                parent.resize_child(node, Direction::Down, dy);
+            }
+        }
+    }
+
+    fn drop_tiled(&self, seat: &Rc<WlSeatGlobal>) {
+        let (x, y) = seat.pos.get();
+        let (x, y) = (x.round_down(), y.round_down());
+        let target = {
+            let mut found_tree = seat.found_tree.borrow_mut();
+            found_tree.push(FoundNode {
+                node: seat.state.root.clone(),
+                x,
+                y,
+            });
+            seat.state.root.node_find_tree_at(x, y, &mut found_tree);
+            let target = found_tree
+                .iter()
+                .rev()
+                .find_map(|n| n.node.clone().node_toplevel());
+            found_tree.clear();
+            match target {
+                Some(t) => t,
+                _ => return,
+            }
+        };
+        if target.node_id() == self.node.node_id()
+            || target.node_is_container()
+            || target.tl_data().is_fullscreen.get()
+            || self.node.tl_data().is_fullscreen.get()
+        {
+            return;
+        }
+        let container = match target.tl_data().parent.get() {
+            Some(p) => match p.node_into_container() {
+                Some(c) => c,
+                _ => return,
+            },
+            _ => return,
+        };
+        let old_parent = match self.node.tl_data().parent.get() {
+            Some(p) => p,
+            _ => return,
+        };
+        let pos = target.node_absolute_position();
+        let before = match container.split.get() {
+            ContainerSplit::Horizontal => x < pos.x1() + pos.width() / 2,
+            ContainerSplit::Vertical => y < pos.y1() + pos.height() / 2,
+        };
+        old_parent.cnode_remove_child2(self.node.tl_as_node(), true);
+        match before {
+            true => container.add_child_before(target.tl_as_node(), self.node.clone()),
+            false => container.add_child_after(target.tl_as_node(), self.node.clone()),
+        }
+    }
+}
+
+impl PointerOwner for WindowOpPointerOwner {
+    fn button(&self, seat: &Rc<WlSeatGlobal>, _time_usec: u64, button: u32, state: KeyState) {
+        if button != self.button || state != KeyState::Released {
+            return;
+        }
+        if !self.edges.is_some() && self.float_position.is_none() {
+            self.drop_tiled(seat);
+        }
+        self.finish(seat);
+    }
+
+    fn axis_node(&self, _seat: &Rc<WlSeatGlobal>) -> Option<Rc<dyn Node>> {
+        None
+    }
+
+    fn apply_changes(&self, seat: &Rc<WlSeatGlobal>) {
+        let (x, y) = seat.pos.get();
+        let (x, y) = (x.round_down(), y.round_down());
+        match self.float_position {
+            Some(start) => self.update_float(start, x, y),
+            _ if self.edges.is_some() => self.update_tiled(x, y),
+            _ => {}
+        }
+    }
+
+    fn start_drag(
+        &self,
+        _seat: &Rc<WlSeatGlobal>,
+        _origin: &Rc<WlSurface>,
+        source: Option<Rc<WlDataSource>>,
+        _icon: Option<Rc<WlSurface>>,
+        _serial: u32,
+    ) -> Result<(), WlSeatError> {
+        if let Some(src) = source {
+            src.send_cancelled();
+        }
+        Ok(())
+    }
+
+    fn cancel_dnd(&self, seat: &Rc<WlSeatGlobal>) {
+        seat.dropped_dnd.borrow_mut().take();
+    }
+
+    fn revert_to_default(&self, seat: &Rc<WlSeatGlobal>) {
+        self.finish(seat);
+    }
+
+    fn dnd_target_removed(&self, seat: &Rc<WlSeatGlobal>) {
+        self.cancel_dnd(seat);
+    }
+
+    fn dnd_icon(&self) -> Option<Rc<WlSurface>> {
+        None
+    }
+
+    fn remove_dnd_icon(&self) {
+        // nothing
+    }
+}
+
 impl PointerOwner for DndPointerOwner {
     fn button(&self, seat: &Rc<WlSeatGlobal>, _time_usec: u64, button: u32, state: KeyState) {
         if button != self.button || state != KeyState::Released {
@@ -436,9 +884,7 @@ impl PointerOwner for DndPointerOwner {
         if let Some(icon) = self.icon.get() {
             icon.dnd_icons.remove(&seat.id());
         }
-        seat.pointer_owner
-            .owner
-            .set(seat.pointer_owner.default.clone());
+        seat.pointer_owner.set_base_owner(seat);
         // log::info!("button2");
         seat.tree_changed.trigger();
     }
@@ -509,9 +955,7 @@ impl PointerOwner for DndPointerOwner {
         if let Some(icon) = self.icon.get() {
             icon.dnd_icons.remove(&seat.id());
         }
-        seat.pointer_owner
-            .owner
-            .set(seat.pointer_owner.default.clone());
+        seat.pointer_owner.set_base_owner(seat);
         // log::info!("cancel_dnd");
         seat.tree_changed.trigger();
     }
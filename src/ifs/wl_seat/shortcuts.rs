@@ -0,0 +1,127 @@
+use {
+    ahash::{AHashMap, AHashSet},
+    jay_config::keyboard::{BindingMode, KeyBinding, KeyTrigger, ModifiedKeySym},
+    std::{
+        cell::{Cell, RefCell},
+        collections::hash_map::Entry,
+    },
+};
+
+#[derive(Default)]
+pub struct Shortcuts {
+    mode: Cell<BindingMode>,
+    bindings: RefCell<AHashSet<KeyBinding>>,
+    prefixes: RefCell<AHashMap<(BindingMode, Vec<ModifiedKeySym>), usize>>,
+    pending: RefCell<Vec<ModifiedKeySym>>,
+}
+
+pub enum ShortcutMatch {
+    None,
+    Prefix,
+    Binding(KeyBinding),
+}
+
+impl Shortcuts {
+    pub fn clear(&self) {
+        self.mode.set(BindingMode::DEFAULT);
+        self.bindings.borrow_mut().clear();
+        self.prefixes.borrow_mut().clear();
+        self.pending.borrow_mut().clear();
+    }
+
+    pub fn add(&self, binding: KeyBinding) {
+        if !self.bindings.borrow_mut().insert(binding.clone()) {
+            return;
+        }
+        let mut prefixes = self.prefixes.borrow_mut();
+        for len in 1..=binding.prefix.len() {
+            *prefixes
+                .entry((binding.mode, binding.prefix[..len].to_vec()))
+                .or_default() += 1;
+        }
+    }
+
+    pub fn remove(&self, binding: &KeyBinding) {
+        if !self.bindings.borrow_mut().remove(binding) {
+            return;
+        }
+        let mut prefixes = self.prefixes.borrow_mut();
+        for len in 1..=binding.prefix.len() {
+            if let Entry::Occupied(mut o) =
+                prefixes.entry((binding.mode, binding.prefix[..len].to_vec()))
+            {
+                *o.get_mut() -= 1;
+                if *o.get() == 0 {
+                    o.remove();
+                }
+            }
+        }
+    }
+
+    pub fn mode(&self) -> BindingMode {
+        self.mode.get()
+    }
+
+    pub fn set_mode(&self, mode: BindingMode) {
+        self.mode.set(mode);
+        self.pending.borrow_mut().clear();
+    }
+
+    pub fn has_pending_sequence(&self) -> bool {
+        self.pending.borrow().len() > 0
+    }
+
+    pub fn abort_sequence(&self) {
+        self.pending.borrow_mut().clear();
+    }
+
+    /// Evaluates a key press.
+    ///
+    /// If the key continues a key sequence, it is appended to the pending sequence. If
+    /// the key completes a binding, the pending sequence is reset. Otherwise the pending
+    /// sequence is left untouched.
+    pub fn press(&self, key: ModifiedKeySym) -> ShortcutMatch {
+        let mode = self.mode.get();
+        let mut pending = self.pending.borrow_mut();
+        pending.push(key);
+        if self
+            .prefixes
+            .borrow()
+            .contains_key(&(mode, pending.clone()))
+        {
+            return ShortcutMatch::Prefix;
+        }
+        pending.pop();
+        match self.find(mode, KeyTrigger::Press, &mut pending, key) {
+            Some(b) => ShortcutMatch::Binding(b),
+            _ => ShortcutMatch::None,
+        }
+    }
+
+    /// Evaluates the release of the last pressed key.
+    pub fn release(&self, key: ModifiedKeySym) -> Option<KeyBinding> {
+        let mut pending = self.pending.borrow_mut();
+        self.find(self.mode.get(), KeyTrigger::Release, &mut pending, key)
+    }
+
+    fn find(
+        &self,
+        mode: BindingMode,
+        trigger: KeyTrigger,
+        pending: &mut Vec<ModifiedKeySym>,
+        key: ModifiedKeySym,
+    ) -> Option<KeyBinding> {
+        let binding = KeyBinding {
+            mode,
+            trigger,
+            prefix: pending.clone(),
+            key,
+        };
+        if self.bindings.borrow().contains(&binding) {
+            pending.clear();
+            Some(binding)
+        } else {
+            None
+        }
+    }
+}
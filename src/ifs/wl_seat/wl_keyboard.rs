@@ -22,7 +22,7 @@ const NO_KEYMAP: u32 = 0;
 pub(super) const XKB_V1: u32 = 1;
 
 pub(super) const RELEASED: u32 = 0;
-pub(super) const PRESSED: u32 = 1;
+pub const PRESSED: u32 = 1;
 
 pub struct WlKeyboard {
     id: WlKeyboardId,
@@ -0,0 +1,156 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        cursor::KnownCursor,
+        ifs::wl_seat::WlSeat,
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{wp_cursor_shape_device_v1::*, WpCursorShapeDeviceV1Id},
+    },
+    std::rc::Rc,
+    thiserror::Error,
+};
+
+const INVALID_SHAPE: u32 = 1;
+
+const SHAPE_DEFAULT: u32 = 1;
+const SHAPE_CONTEXT_MENU: u32 = 2;
+const SHAPE_HELP: u32 = 3;
+const SHAPE_POINTER: u32 = 4;
+const SHAPE_PROGRESS: u32 = 5;
+const SHAPE_WAIT: u32 = 6;
+const SHAPE_CELL: u32 = 7;
+const SHAPE_CROSSHAIR: u32 = 8;
+const SHAPE_TEXT: u32 = 9;
+const SHAPE_VERTICAL_TEXT: u32 = 10;
+const SHAPE_ALIAS: u32 = 11;
+const SHAPE_COPY: u32 = 12;
+const SHAPE_MOVE: u32 = 13;
+const SHAPE_NO_DROP: u32 = 14;
+const SHAPE_NOT_ALLOWED: u32 = 15;
+const SHAPE_GRAB: u32 = 16;
+const SHAPE_GRABBING: u32 = 17;
+const SHAPE_E_RESIZE: u32 = 18;
+const SHAPE_N_RESIZE: u32 = 19;
+const SHAPE_NE_RESIZE: u32 = 20;
+const SHAPE_NW_RESIZE: u32 = 21;
+const SHAPE_S_RESIZE: u32 = 22;
+const SHAPE_SE_RESIZE: u32 = 23;
+const SHAPE_SW_RESIZE: u32 = 24;
+const SHAPE_W_RESIZE: u32 = 25;
+const SHAPE_EW_RESIZE: u32 = 26;
+const SHAPE_NS_RESIZE: u32 = 27;
+const SHAPE_NESW_RESIZE: u32 = 28;
+const SHAPE_NWSE_RESIZE: u32 = 29;
+const SHAPE_COL_RESIZE: u32 = 30;
+const SHAPE_ROW_RESIZE: u32 = 31;
+const SHAPE_ALL_SCROLL: u32 = 32;
+const SHAPE_ZOOM_IN: u32 = 33;
+const SHAPE_ZOOM_OUT: u32 = 34;
+
+pub struct WpCursorShapeDeviceV1 {
+    pub id: WpCursorShapeDeviceV1Id,
+    pub client: Rc<Client>,
+    pub seat: Rc<WlSeat>,
+    pub tracker: Tracker<Self>,
+}
+
+impl WpCursorShapeDeviceV1 {
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), WpCursorShapeDeviceV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+
+    fn set_shape(&self, parser: MsgParser<'_, '_>) -> Result<(), WpCursorShapeDeviceV1Error> {
+        let req: SetShape = self.client.parse(self, parser)?;
+        let cursor = match req.shape {
+            SHAPE_DEFAULT => KnownCursor::Default,
+            SHAPE_CONTEXT_MENU => KnownCursor::ContextMenu,
+            SHAPE_HELP => KnownCursor::Help,
+            SHAPE_POINTER => KnownCursor::Pointer,
+            SHAPE_PROGRESS => KnownCursor::Progress,
+            SHAPE_WAIT => KnownCursor::Wait,
+            SHAPE_CELL => KnownCursor::Cell,
+            SHAPE_CROSSHAIR => KnownCursor::Crosshair,
+            SHAPE_TEXT => KnownCursor::Text,
+            SHAPE_VERTICAL_TEXT => KnownCursor::VerticalText,
+            SHAPE_ALIAS => KnownCursor::Alias,
+            SHAPE_COPY => KnownCursor::Copy,
+            SHAPE_MOVE => KnownCursor::Move,
+            SHAPE_NO_DROP => KnownCursor::NoDrop,
+            SHAPE_NOT_ALLOWED => KnownCursor::NotAllowed,
+            SHAPE_GRAB => KnownCursor::Grab,
+            SHAPE_GRABBING => KnownCursor::Grabbing,
+            SHAPE_E_RESIZE => KnownCursor::ResizeRight,
+            SHAPE_N_RESIZE => KnownCursor::ResizeTop,
+            SHAPE_NE_RESIZE => KnownCursor::ResizeTopRight,
+            SHAPE_NW_RESIZE => KnownCursor::ResizeTopLeft,
+            SHAPE_S_RESIZE => KnownCursor::ResizeBottom,
+            SHAPE_SE_RESIZE => KnownCursor::ResizeBottomRight,
+            SHAPE_SW_RESIZE => KnownCursor::ResizeBottomLeft,
+            SHAPE_W_RESIZE => KnownCursor::ResizeLeft,
+            SHAPE_EW_RESIZE => KnownCursor::ResizeLeftRight,
+            SHAPE_NS_RESIZE => KnownCursor::ResizeTopBottom,
+            SHAPE_NESW_RESIZE => KnownCursor::ResizeTopRightBottomLeft,
+            SHAPE_NWSE_RESIZE => KnownCursor::ResizeTopLeftBottomRight,
+            SHAPE_COL_RESIZE => KnownCursor::ColResize,
+            SHAPE_ROW_RESIZE => KnownCursor::RowResize,
+            SHAPE_ALL_SCROLL => KnownCursor::AllScroll,
+            SHAPE_ZOOM_IN => KnownCursor::ZoomIn,
+            SHAPE_ZOOM_OUT => KnownCursor::ZoomOut,
+            _ => {
+                self.client.protocol_error(
+                    self,
+                    INVALID_SHAPE,
+                    &format!("Unknown cursor shape {}", req.shape),
+                );
+                return Err(WpCursorShapeDeviceV1Error::UnknownShape(req.shape));
+            }
+        };
+        if !self.client.valid_serial(req.serial) {
+            log::warn!("Client tried to set_shape with an invalid serial");
+            return Ok(());
+        }
+        let pointer_node = match self.seat.global.pointer_node() {
+            Some(n) => n,
+            _ => return Ok(()),
+        };
+        if pointer_node.node_client_id() != Some(self.client.id) {
+            return Ok(());
+        }
+        if req.serial != self.client.last_enter_serial.get() {
+            return Ok(());
+        }
+        self.seat.global.set_known_cursor(cursor);
+        Ok(())
+    }
+}
+
+object_base! {
+    WpCursorShapeDeviceV1;
+
+    DESTROY => destroy,
+    SET_SHAPE => set_shape,
+}
+
+impl Object for WpCursorShapeDeviceV1 {
+    fn num_requests(&self) -> u32 {
+        SET_SHAPE + 1
+    }
+}
+
+simple_add_obj!(WpCursorShapeDeviceV1);
+
+#[derive(Debug, Error)]
+pub enum WpCursorShapeDeviceV1Error {
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("Parsing failed")]
+    MsgParserError(Box<MsgParserError>),
+    #[error("Unknown cursor shape {0}")]
+    UnknownShape(u32),
+}
+efrom!(WpCursorShapeDeviceV1Error, ClientError);
+efrom!(WpCursorShapeDeviceV1Error, MsgParserError);
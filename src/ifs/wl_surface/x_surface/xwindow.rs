@@ -270,16 +270,27 @@ impl Xwindow {
                     Some(self.data.state.root.stacked.add_last(self.clone()));
                 self.data.state.tree_changed();
             }
-            Change::Map if self.data.info.wants_floating.get() => {
-                let ws = self.data.state.float_map_ws();
-                let ext = self.data.info.pending_extents.get();
-                self.data
-                    .state
-                    .map_floating(self.clone(), ext.width(), ext.height(), &ws);
-                self.data.title_changed();
-            }
             Change::Map => {
-                self.data.state.map_tiled(self.clone());
+                let info = &self.data.info;
+                let state = &self.data.state;
+                let ext = info.pending_extents.get();
+                let intent = state.placements.take(
+                    info.pid.get().map(|p| p as _),
+                    info.startup_id.borrow().as_deref().map(|s| s.as_slice()),
+                );
+                if let Some(intent) = intent {
+                    state.map_placed(
+                        self.clone(),
+                        &intent,
+                        info.wants_floating.get(),
+                        Some((ext.width(), ext.height())),
+                    );
+                } else if info.wants_floating.get() {
+                    let ws = state.float_map_ws();
+                    state.map_floating(self.clone(), ext.width(), ext.height(), &ws);
+                } else {
+                    state.map_tiled(self.clone());
+                }
                 self.data.title_changed();
             }
         }
@@ -413,6 +424,22 @@ impl ToplevelNode for Xwindow {
             .push(XWaylandEvent::Close(self.data.clone()));
     }
 
+    fn tl_set_minimized(&self, minimized: bool) {
+        self.data
+            .state
+            .xwayland
+            .queue
+            .push(XWaylandEvent::SetMinimized(self.data.clone(), minimized));
+    }
+
+    fn tl_set_maximized(&self, maximized: bool) {
+        self.data
+            .state
+            .xwayland
+            .queue
+            .push(XWaylandEvent::SetMaximized(self.data.clone(), maximized));
+    }
+
     fn tl_set_visible(&self, visible: bool) {
         self.x.surface.set_visible(visible);
         self.seat_state.set_visible(self, visible);
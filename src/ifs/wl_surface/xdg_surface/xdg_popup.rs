@@ -5,7 +5,7 @@ use {
         fixed::Fixed,
         ifs::{
             wl_seat::{NodeSeatState, WlSeatGlobal},
-            wl_surface::xdg_surface::{XdgSurface, XdgSurfaceError, XdgSurfaceExt},
+            wl_surface::xdg_surface::{XdgSurface, XdgSurfaceError, XdgSurfaceExt, XdgSurfaceRole},
             xdg_positioner::{XdgPositioned, XdgPositioner, CA},
         },
         leaks::Tracker,
@@ -28,7 +28,6 @@ use {
     thiserror::Error,
 };
 
-#[allow(dead_code)]
 const INVALID_GRAB: u32 = 1;
 
 tree_id!(PopupId);
@@ -44,6 +43,7 @@ pub struct XdgPopup {
     pos: RefCell<XdgPositioned>,
     pub tracker: Tracker<Self>,
     seat_state: NodeSeatState,
+    grab_seat: CloneCell<Option<Rc<WlSeatGlobal>>>,
 }
 
 impl Debug for XdgPopup {
@@ -74,6 +74,7 @@ impl XdgPopup {
             pos: RefCell::new(pos),
             tracker: Default::default(),
             seat_state: Default::default(),
+            grab_seat: Default::default(),
         })
     }
 
@@ -217,10 +218,45 @@ impl XdgPopup {
     }
 
     fn grab(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgPopupError> {
-        let _req: Grab = self.xdg.surface.client.parse(self, parser)?;
+        let req: Grab = self.xdg.surface.client.parse(self, parser)?;
+        let seat = self.xdg.surface.client.lookup(req.seat)?;
+        if self.workspace_link.borrow().is_some() {
+            self.xdg.surface.client.protocol_error(
+                self,
+                INVALID_GRAB,
+                &format!("xdg_popup {} is already mapped", self.id),
+            );
+            return Err(XdgPopupError::AlreadyMapped);
+        }
+        if !self.xdg.surface.client.valid_serial(req.serial) {
+            log::warn!("Client tried to grab a popup with an invalid serial");
+            self.send_popup_done();
+            return Ok(());
+        }
+        self.grab_seat.set(Some(seat.global.clone()));
         Ok(())
     }
 
+    fn start_grab(self: &Rc<Self>, seat: &Rc<WlSeatGlobal>) {
+        if let Some(parent) = self.parent.get() {
+            if parent.role.get() == XdgSurfaceRole::XdgPopup {
+                let top = seat.top_popup_grab();
+                if top.map(|t| t.xdg.id) != Some(parent.id) {
+                    self.grab_seat.set(None);
+                    self.send_popup_done();
+                    return;
+                }
+            }
+        }
+        seat.add_popup_grab(self);
+    }
+
+    /// Ends the grab of this popup and tells the client to destroy it.
+    pub fn dismiss(&self) {
+        self.grab_seat.set(None);
+        self.send_popup_done();
+    }
+
     fn reposition(self: &Rc<Self>, parser: MsgParser<'_, '_>) -> Result<(), XdgPopupError> {
         let req: Reposition = self.xdg.surface.client.parse(&**self, parser)?;
         *self.pos.borrow_mut() = self.xdg.surface.client.lookup(req.positioner)?.value();
@@ -249,6 +285,9 @@ impl XdgPopup {
         let _v = self.workspace_link.borrow_mut().take();
         self.xdg.destroy_node();
         self.seat_state.destroy_node(self);
+        if let Some(seat) = self.grab_seat.take() {
+            seat.remove_popup_grab(self);
+        }
     }
 }
 
@@ -272,6 +311,7 @@ impl Object for XdgPopup {
     fn break_loops(&self) {
         self.destroy_node();
         self.parent.set(None);
+        self.grab_seat.set(None);
         *self.display_link.borrow_mut() = None;
         *self.workspace_link.borrow_mut() = None;
     }
@@ -372,6 +412,11 @@ impl XdgSurfaceExt for XdgPopup {
                         .map(|p| p.surface.visible.get())
                         .unwrap_or(false),
                 );
+                if let Some(seat) = self.grab_seat.get() {
+                    drop(wl);
+                    drop(dl);
+                    self.start_grab(&seat);
+                }
             }
         } else {
             if wl.take().is_some() {
@@ -393,6 +438,8 @@ impl XdgSurfaceExt for XdgPopup {
 pub enum XdgPopupError {
     #[error("The `xdg_positioner` is incomplete")]
     Incomplete,
+    #[error("Tried to grab a popup that is already mapped")]
+    AlreadyMapped,
     #[error("Parsing failed")]
     MsgParserError(#[source] Box<MsgParserError>),
     #[error(transparent)]
@@ -6,7 +6,10 @@ use {
         cursor::KnownCursor,
         fixed::Fixed,
         ifs::{
-            wl_seat::{NodeSeatState, SeatId, WlSeatGlobal},
+            wl_seat::{
+                NodeSeatState, SeatId, WlSeatGlobal, RESIZE_BOTTOM, RESIZE_LEFT, RESIZE_RIGHT,
+                RESIZE_TOP,
+            },
             wl_surface::xdg_surface::{XdgSurface, XdgSurfaceError, XdgSurfaceExt},
         },
         leaks::Tracker,
@@ -26,6 +29,7 @@ use {
     },
     ahash::{AHashMap, AHashSet},
     num_derive::FromPrimitive,
+    num_traits::FromPrimitive,
     std::{
         cell::{Cell, RefCell},
         fmt::{Debug, Formatter},
@@ -49,7 +53,6 @@ pub enum ResizeEdge {
     BottomRight = 10,
 }
 
-#[allow(dead_code)]
 const STATE_MAXIMIZED: u32 = 1;
 #[allow(dead_code)]
 const STATE_FULLSCREEN: u32 = 2;
@@ -61,7 +64,6 @@ const STATE_TILED_RIGHT: u32 = 6;
 const STATE_TILED_TOP: u32 = 7;
 const STATE_TILED_BOTTOM: u32 = 8;
 
-#[allow(dead_code)]
 const CAP_WINDOW_MENU: u32 = 1;
 #[allow(dead_code)]
 const CAP_MAXIMIZE: u32 = 2;
@@ -177,7 +179,7 @@ impl XdgToplevel {
     pub fn send_wm_capabilities(&self) {
         self.xdg.surface.client.event(WmCapabilities {
             self_id: self.id,
-            capabilities: &[CAP_FULLSCREEN],
+            capabilities: &[CAP_WINDOW_MENU, CAP_FULLSCREEN],
         })
     }
 
@@ -232,8 +234,18 @@ impl XdgToplevel {
         Ok(())
     }
 
-    fn show_window_menu(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgToplevelError> {
-        let _req: ShowWindowMenu = self.xdg.surface.client.parse(self, parser)?;
+    fn show_window_menu(
+        self: &Rc<Self>,
+        parser: MsgParser<'_, '_>,
+    ) -> Result<(), XdgToplevelError> {
+        let req: ShowWindowMenu = self.xdg.surface.client.parse(&**self, parser)?;
+        let seat = self.xdg.surface.client.lookup(req.seat)?;
+        if !self.xdg.surface.visible.get() {
+            return Ok(());
+        }
+        let ext = self.xdg.absolute_desired_extents.get();
+        seat.global
+            .show_window_menu(self.clone(), ext.x1() + req.x, ext.y1() + req.y);
         Ok(())
     }
 
@@ -248,8 +260,25 @@ impl XdgToplevel {
         Ok(())
     }
 
-    fn resize(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgToplevelError> {
-        let _req: Resize = self.xdg.surface.client.parse(self, parser)?;
+    fn resize(self: &Rc<Self>, parser: MsgParser<'_, '_>) -> Result<(), XdgToplevelError> {
+        let req: Resize = self.xdg.surface.client.parse(&**self, parser)?;
+        let seat = self.xdg.surface.client.lookup(req.seat)?;
+        let edges = match ResizeEdge::from_u32(req.edges) {
+            Some(e) => e,
+            _ => return Err(XdgToplevelError::UnknownResizeEdge(req.edges)),
+        };
+        let edges = match edges {
+            ResizeEdge::None => return Ok(()),
+            ResizeEdge::Top => RESIZE_TOP,
+            ResizeEdge::Bottom => RESIZE_BOTTOM,
+            ResizeEdge::Left => RESIZE_LEFT,
+            ResizeEdge::TopLeft => RESIZE_TOP | RESIZE_LEFT,
+            ResizeEdge::BottomLeft => RESIZE_BOTTOM | RESIZE_LEFT,
+            ResizeEdge::Right => RESIZE_RIGHT,
+            ResizeEdge::TopRight => RESIZE_TOP | RESIZE_RIGHT,
+            ResizeEdge::BottomRight => RESIZE_BOTTOM | RESIZE_RIGHT,
+        };
+        seat.global.start_resize(self.clone(), edges);
         Ok(())
     }
 
@@ -291,11 +320,13 @@ impl XdgToplevel {
 
     fn set_maximized(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgToplevelError> {
         let _req: SetMaximized = self.xdg.surface.client.parse(self, parser)?;
+        self.toplevel_data.set_maximized(self, true);
         Ok(())
     }
 
     fn unset_maximized(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgToplevelError> {
         let _req: UnsetMaximized = self.xdg.surface.client.parse(self, parser)?;
+        self.toplevel_data.set_maximized(self, false);
         Ok(())
     }
 
@@ -336,8 +367,9 @@ impl XdgToplevel {
         Ok(())
     }
 
-    fn set_minimized(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgToplevelError> {
-        let _req: SetMinimized = self.xdg.surface.client.parse(self, parser)?;
+    fn set_minimized(self: &Rc<Self>, parser: MsgParser<'_, '_>) -> Result<(), XdgToplevelError> {
+        let _req: SetMinimized = self.xdg.surface.client.parse(self.deref(), parser)?;
+        self.toplevel_data.minimize(self.clone());
         Ok(())
     }
 
@@ -355,6 +387,15 @@ impl XdgToplevel {
     }
 
     fn map_tiled(self: &Rc<Self>) {
+        let pid = self.xdg.surface.client.pid_info.pid;
+        let intent = match self.toplevel_data.placement.take() {
+            Some(intent) => Some(intent),
+            _ => self.state.placements.take(Some(pid), None),
+        };
+        if let Some(intent) = intent {
+            self.state.map_placed(self.clone(), &intent, false, None);
+            return;
+        }
         self.state.map_tiled(self.clone());
     }
 }
@@ -473,6 +514,19 @@ impl ToplevelNode for XdgToplevel {
         Some(self.xdg.surface.clone())
     }
 
+    fn tl_set_maximized(&self, maximized: bool) {
+        let changed = {
+            let mut states = self.states.borrow_mut();
+            match maximized {
+                true => states.insert(STATE_MAXIMIZED),
+                false => states.remove(&STATE_MAXIMIZED),
+            }
+        };
+        if changed {
+            self.send_current_configure();
+        }
+    }
+
     fn tl_set_workspace_ext(self: Rc<Self>, ws: &Rc<WorkspaceNode>) {
         self.xdg.set_workspace(ws);
     }
@@ -590,6 +644,8 @@ pub enum XdgToplevelError {
     ClientError(Box<ClientError>),
     #[error("width/height must be non-negative")]
     NonNegative,
+    #[error("Unknown resize edge {0}")]
+    UnknownResizeEdge(u32),
 }
 efrom!(XdgToplevelError, MsgParserError);
 efrom!(XdgToplevelError, ClientError);
@@ -0,0 +1,95 @@
+use {
+    crate::{
+        client::{Client, ClientError},
+        leaks::Tracker,
+        object::Object,
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{xdg_activation_token_v1::*, XdgActivationTokenV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+    thiserror::Error,
+};
+
+pub struct XdgActivationTokenV1 {
+    pub id: XdgActivationTokenV1Id,
+    pub client: Rc<Client>,
+    pub committed: Cell<bool>,
+    pub tracker: Tracker<Self>,
+}
+
+impl XdgActivationTokenV1 {
+    fn send_done(&self, token: &str) {
+        self.client.event(Done {
+            self_id: self.id,
+            token,
+        })
+    }
+
+    fn check_uncommitted(&self) -> Result<(), XdgActivationTokenV1Error> {
+        if self.committed.get() {
+            return Err(XdgActivationTokenV1Error::AlreadyUsed);
+        }
+        Ok(())
+    }
+
+    fn set_serial(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgActivationTokenV1Error> {
+        let _req: SetSerial = self.client.parse(self, parser)?;
+        self.check_uncommitted()
+    }
+
+    fn set_app_id(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgActivationTokenV1Error> {
+        let _req: SetAppId = self.client.parse(self, parser)?;
+        self.check_uncommitted()
+    }
+
+    fn set_surface(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgActivationTokenV1Error> {
+        let req: SetSurface = self.client.parse(self, parser)?;
+        self.client.lookup(req.surface)?;
+        self.check_uncommitted()
+    }
+
+    fn commit(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgActivationTokenV1Error> {
+        let _req: Commit = self.client.parse(self, parser)?;
+        self.check_uncommitted()?;
+        self.committed.set(true);
+        let token = self.client.state.placements.next_token();
+        self.send_done(&token);
+        Ok(())
+    }
+
+    fn destroy(&self, parser: MsgParser<'_, '_>) -> Result<(), XdgActivationTokenV1Error> {
+        let _req: Destroy = self.client.parse(self, parser)?;
+        self.client.remove_obj(self)?;
+        Ok(())
+    }
+}
+
+object_base! {
+    XdgActivationTokenV1;
+
+    SET_SERIAL => set_serial,
+    SET_APP_ID => set_app_id,
+    SET_SURFACE => set_surface,
+    COMMIT => commit,
+    DESTROY => destroy,
+}
+
+impl Object for XdgActivationTokenV1 {
+    fn num_requests(&self) -> u32 {
+        DESTROY + 1
+    }
+}
+
+simple_add_obj!(XdgActivationTokenV1);
+
+#[derive(Debug, Error)]
+pub enum XdgActivationTokenV1Error {
+    #[error("Parsing failed")]
+    MsgParserError(#[source] Box<MsgParserError>),
+    #[error(transparent)]
+    ClientError(Box<ClientError>),
+    #[error("The token has already been committed")]
+    AlreadyUsed,
+}
+efrom!(XdgActivationTokenV1Error, MsgParserError);
+efrom!(XdgActivationTokenV1Error, ClientError);
@@ -1,6 +1,7 @@
 use {
     crate::{
         async_engine::Phase,
+        forker::ForkerProxy,
         it::{
             test_backend::TestBackend,
             test_config::{with_test_config, TestConfig},
@@ -53,14 +54,29 @@ pub fn run_tests() {
         ),
         failed: Default::default(),
     });
+    let (forker_tests, tests): (Vec<_>, Vec<_>) =
+        tests::tests().into_iter().partition(|t| t.uses_forker());
+    // The forkers must be created while this process is still single-threaded.
+    let forkers: Vec<_> = forker_tests
+        .iter()
+        .map(|_| match ForkerProxy::create() {
+            Ok(f) => Rc::new(f),
+            Err(e) => fatal!("Could not create a forker process: {}", ErrorFmt(e)),
+        })
+        .collect();
+    for (test, forker) in forker_tests.into_iter().zip(forkers) {
+        with_test_config(|cfg| {
+            run_test(&it_run, test, cfg, Some(forker));
+        })
+    }
     if SINGLE_THREAD {
-        for test in tests::tests() {
+        for test in tests {
             with_test_config(|cfg| {
-                run_test(&it_run, test, cfg);
+                run_test(&it_run, test, cfg, None);
             })
         }
     } else {
-        let queue = Arc::new(Mutex::new(VecDeque::from_iter(tests::tests())));
+        let queue = Arc::new(Mutex::new(VecDeque::from_iter(tests)));
         let mut threads = vec![];
         let num_cpus = match num_cpus() {
             Ok(n) => n,
@@ -76,7 +92,7 @@ pub fn run_tests() {
                     _ => break,
                 };
                 with_test_config(|cfg| {
-                    run_test(&it_run, test, cfg);
+                    run_test(&it_run, test, cfg, None);
                 })
             }));
         }
@@ -104,7 +120,12 @@ struct ItRun {
     failed: Mutex<AHashMap<&'static str, Vec<String>>>,
 }
 
-fn run_test(it_run: &ItRun, test: &'static dyn TestCase, cfg: Rc<TestConfig>) {
+fn run_test(
+    it_run: &ItRun,
+    test: &'static dyn TestCase,
+    cfg: Rc<TestConfig>,
+    forker: Option<Rc<ForkerProxy>>,
+) {
     log::info!("Running {}", test.name());
     let dir = format!("{}/{}", it_run.path, test.name());
     std::fs::create_dir_all(&dir).unwrap();
@@ -113,47 +134,50 @@ fn run_test(it_run: &ItRun, test: &'static dyn TestCase, cfg: Rc<TestConfig>) {
     test_logger::set_file(log_file);
     let errors = Rc::new(Cell::new(Vec::new()));
     let errors2 = errors.clone();
-    let res = crate::compositor::start_compositor_for_test(Box::new(move |state| {
-        let state = state.clone();
-        let server_addr = {
-            let mut addr: c::sockaddr_un = uapi::pod_zeroed();
-            addr.sun_family = c::AF_UNIX as _;
-            let acceptor = state.acceptor.get().unwrap();
-            let path = acceptor.secure_path();
-            let sun_path = uapi::as_bytes_mut(&mut addr.sun_path[..]);
-            sun_path[..path.len()].copy_from_slice(path.as_bytes());
-            sun_path[path.len()] = 0;
-            addr
-        };
-        let backend: Rc<TestBackend> = state.backend.get().into_any().downcast().unwrap();
-        let testrun = Rc::new(TestRun {
-            state: state.clone(),
-            backend,
-            errors: Default::default(),
-            server_addr,
-            out_dir: dir.clone(),
-            in_dir: format!("{}/{}", env!("CARGO_MANIFEST_DIR"), test.dir()),
-            cfg: cfg.clone(),
-        });
-        let errors = errors2.clone();
-        Box::new(async move {
-            let future: Pin<_> = test.run(testrun.clone()).into();
-            let future = state.eng.spawn2(Phase::Present, future);
-            let timeout = state.wheel.timeout(5000);
-            match future::select(future, timeout).await {
-                Either::Left((Ok(..), _)) => {}
-                Either::Left((Err(e), _)) => {
-                    testrun.errors.push(e.to_string());
+    let res = crate::compositor::start_compositor_for_test(
+        forker,
+        Box::new(move |state| {
+            let state = state.clone();
+            let server_addr = {
+                let mut addr: c::sockaddr_un = uapi::pod_zeroed();
+                addr.sun_family = c::AF_UNIX as _;
+                let acceptor = state.acceptor.get().unwrap();
+                let path = acceptor.secure_path();
+                let sun_path = uapi::as_bytes_mut(&mut addr.sun_path[..]);
+                sun_path[..path.len()].copy_from_slice(path.as_bytes());
+                sun_path[path.len()] = 0;
+                addr
+            };
+            let backend: Rc<TestBackend> = state.backend.get().into_any().downcast().unwrap();
+            let testrun = Rc::new(TestRun {
+                state: state.clone(),
+                backend,
+                errors: Default::default(),
+                server_addr,
+                out_dir: dir.clone(),
+                in_dir: format!("{}/{}", env!("CARGO_MANIFEST_DIR"), test.dir()),
+                cfg: cfg.clone(),
+            });
+            let errors = errors2.clone();
+            Box::new(async move {
+                let future: Pin<_> = test.run(testrun.clone()).into();
+                let future = state.eng.spawn2(Phase::Present, future);
+                let timeout = state.wheel.timeout(5000);
+                match future::select(future, timeout).await {
+                    Either::Left((Ok(..), _)) => {}
+                    Either::Left((Err(e), _)) => {
+                        testrun.errors.push(e.to_string());
+                    }
+                    Either::Right(..) => {
+                        testrun.errors.push("Test timed out".to_string());
+                    }
                 }
-                Either::Right(..) => {
-                    testrun.errors.push("Test timed out".to_string());
-                }
-            }
-            errors.set(testrun.errors.take());
-            state.ring.stop();
-            pending().await
-        })
-    }));
+                errors.set(testrun.errors.take());
+                state.ring.stop();
+                pending().await
+            })
+        }),
+    );
     let mut errors = errors.take();
     if let Err(e) = res {
         errors.push(format!("The compositor failed: {}", ErrorFmt(e)));
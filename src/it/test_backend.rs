@@ -14,7 +14,8 @@ use {
         state::State,
         time::now_usec,
         utils::{
-            clonecell::CloneCell, copyhashmap::CopyHashMap, oserror::OsError, syncqueue::SyncQueue,
+            clonecell::CloneCell, copyhashmap::CopyHashMap, errorfmt::ErrorFmt, numcell::NumCell,
+            oserror::OsError, syncqueue::SyncQueue,
         },
         video::drm::{ConnectorType, Drm},
     },
@@ -34,8 +35,14 @@ pub enum TestBackendError {
     OpenDrmNode(String, #[source] OsError),
     #[error("Could not create a render context")]
     RenderContext(#[source] RenderError),
+    #[error("{} is set", SOFTWARE_RENDERER_VAR)]
+    SoftwareRendererRequested,
 }
 
+/// If this environment variable is set, the tests use the software renderer even if a
+/// drm node is available.
+const SOFTWARE_RENDERER_VAR: &str = "JAY_IT_SOFTWARE_RENDERER";
+
 pub struct TestBackend {
     pub state: Rc<State>,
     pub test_future: TestFuture,
@@ -43,6 +50,9 @@ pub struct TestBackend {
     pub default_mouse: Rc<TestBackendMouse>,
     pub default_kb: Rc<TestBackendKb>,
     pub render_context_installed: Cell<bool>,
+    pub software_renderer: Cell<bool>,
+    pub virtual_outputs: CopyHashMap<ConnectorId, Rc<TestConnector>>,
+    pub next_virtual_idx: NumCell<u32>,
 }
 
 impl TestBackend {
@@ -74,20 +84,7 @@ impl TestBackend {
             accel_profile: Cell::new(InputDeviceAccelProfile::Flat),
             left_handed: Cell::new(false),
         });
-        let default_kb = Rc::new(TestBackendKb {
-            common: TestInputDeviceCommon {
-                id: state.input_device_ids.next(),
-                removed: Cell::new(false),
-                events: Default::default(),
-                on_change: Default::default(),
-                capabilities: {
-                    let chm = CopyHashMap::new();
-                    chm.set(InputDeviceCapability::Keyboard, ());
-                    chm
-                },
-                name: Rc::new("default-keyboard".to_string()),
-            },
-        });
+        let default_kb = create_keyboard(state, "default-keyboard");
         Self {
             state: state.clone(),
             test_future: future,
@@ -95,6 +92,9 @@ impl TestBackend {
             default_mouse,
             default_kb,
             render_context_installed: Cell::new(false),
+            software_renderer: Cell::new(false),
+            virtual_outputs: Default::default(),
+            next_virtual_idx: NumCell::new(1),
         }
     }
 
@@ -102,39 +102,78 @@ impl TestBackend {
         if self.render_context_installed.get() {
             return Ok(());
         }
-        self.create_render_context()?;
+        let res = match std::env::var_os(SOFTWARE_RENDERER_VAR) {
+            Some(_) => Err(TestBackendError::SoftwareRendererRequested),
+            _ => self.create_render_context(),
+        };
+        if let Err(e) = res {
+            log::info!("Using the software renderer: {}", ErrorFmt(e));
+            self.state
+                .set_render_ctx(Some(&Rc::new(RenderContext::software())));
+            self.software_renderer.set(true);
+        }
         self.render_context_installed.set(true);
         Ok(())
     }
 
     pub fn install_default(&self) -> TestResult {
         self.install_render_context()?;
+        self.connect(&self.default_connector);
+        self.state
+            .backend_events
+            .push(BackendEvent::NewInputDevice(self.default_kb.clone()));
         self.state
             .backend_events
-            .push(BackendEvent::NewConnector(self.default_connector.clone()));
+            .push(BackendEvent::NewInputDevice(self.default_mouse.clone()));
+        Ok(())
+    }
+
+    pub fn add_keyboard(&self, name: &str) -> Rc<TestBackendKb> {
+        let kb = create_keyboard(&self.state, name);
+        self.state
+            .backend_events
+            .push(BackendEvent::NewInputDevice(kb.clone()));
+        kb
+    }
+
+    pub fn add_connector(&self, idx: u32) -> Rc<TestConnector> {
+        let connector = Rc::new(TestConnector {
+            id: self.state.connector_ids.next(),
+            kernel_id: ConnectorKernelId {
+                ty: ConnectorType::VGA,
+                idx,
+            },
+            events: Default::default(),
+            on_change: Default::default(),
+        });
+        self.connect(&connector);
+        connector
+    }
+
+    fn connect(&self, connector: &Rc<TestConnector>) {
         let mode = Mode {
             width: 800,
             height: 600,
             refresh_rate_millihz: 60_000,
         };
-        self.default_connector
+        self.connect_with_mode(connector, mode);
+    }
+
+    fn connect_with_mode(&self, connector: &Rc<TestConnector>, mode: Mode) {
+        self.state
+            .backend_events
+            .push(BackendEvent::NewConnector(connector.clone()));
+        connector
             .events
             .push(ConnectorEvent::Connected(MonitorInfo {
                 modes: vec![mode],
                 manufacturer: "jay".to_string(),
                 product: "TestConnector".to_string(),
-                serial_number: self.default_connector.id.to_string(),
+                serial_number: connector.id.to_string(),
                 initial_mode: mode,
                 width_mm: 80,
                 height_mm: 60,
             }));
-        self.state
-            .backend_events
-            .push(BackendEvent::NewInputDevice(self.default_kb.clone()));
-        self.state
-            .backend_events
-            .push(BackendEvent::NewInputDevice(self.default_mouse.clone()));
-        Ok(())
     }
 
     fn create_render_context(&self) -> Result<(), TestBackendError> {
@@ -213,6 +252,35 @@ impl Backend for TestBackend {
     fn supports_presentation_feedback(&self) -> bool {
         true
     }
+
+    fn create_virtual_output(&self, mode: Mode) -> Option<ConnectorId> {
+        let connector = Rc::new(TestConnector {
+            id: self.state.connector_ids.next(),
+            kernel_id: ConnectorKernelId {
+                ty: ConnectorType::VIRTUAL,
+                idx: self.next_virtual_idx.fetch_add(1),
+            },
+            events: Default::default(),
+            on_change: Default::default(),
+        });
+        self.connect_with_mode(&connector, mode);
+        self.virtual_outputs.set(connector.id, connector.clone());
+        Some(connector.id)
+    }
+
+    fn remove_virtual_output(&self, id: ConnectorId) -> bool {
+        match self.virtual_outputs.remove(&id) {
+            Some(connector) => {
+                connector.events.push(ConnectorEvent::Disconnected);
+                connector.events.push(ConnectorEvent::Removed);
+                if let Some(on_change) = connector.on_change.get() {
+                    on_change();
+                }
+                true
+            }
+            _ => false,
+        }
+    }
 }
 
 pub struct TestConnector {
@@ -222,6 +290,15 @@ pub struct TestConnector {
     pub on_change: CloneCell<Option<Rc<dyn Fn()>>>,
 }
 
+impl TestConnector {
+    pub fn disconnect(&self) {
+        self.events.push(ConnectorEvent::Disconnected);
+        if let Some(on_change) = self.on_change.get() {
+            on_change();
+        }
+    }
+}
+
 impl Connector for TestConnector {
     fn id(&self) -> ConnectorId {
         self.id
@@ -320,6 +397,23 @@ impl TestBackendMouse {
         });
     }
 
+    pub fn scroll2(&self, dx: i32, dy: i32) {
+        self.common.event(InputEvent::AxisSource {
+            source: AxisSource::Wheel,
+        });
+        self.common.event(InputEvent::Axis120 {
+            dist: dx * 120,
+            axis: ScrollAxis::Horizontal,
+        });
+        self.common.event(InputEvent::Axis120 {
+            dist: dy * 120,
+            axis: ScrollAxis::Vertical,
+        });
+        self.common.event(InputEvent::AxisFrame {
+            time_usec: now_usec(),
+        });
+    }
+
     pub fn scroll_px(&self, dy: i32) {
         self.common.event(InputEvent::AxisSource {
             source: AxisSource::Finger,
@@ -338,6 +432,23 @@ pub struct TestBackendKb {
     pub common: TestInputDeviceCommon,
 }
 
+fn create_keyboard(state: &Rc<State>, name: &str) -> Rc<TestBackendKb> {
+    Rc::new(TestBackendKb {
+        common: TestInputDeviceCommon {
+            id: state.input_device_ids.next(),
+            removed: Cell::new(false),
+            events: Default::default(),
+            on_change: Default::default(),
+            capabilities: {
+                let chm = CopyHashMap::new();
+                chm.set(InputDeviceCapability::Keyboard, ());
+                chm
+            },
+            name: Rc::new(name.to_string()),
+        },
+    })
+}
+
 pub struct PressedKey {
     pub kb: Rc<TestBackendKb>,
     pub key: u32,
@@ -1,6 +1,5 @@
 use {
     crate::{
-        cli::screenshot::buf_to_qoi,
         client::Client,
         format::ARGB8888,
         globals::GlobalBase,
@@ -84,9 +83,7 @@ impl TestClient {
     }
 
     pub async fn take_screenshot(&self) -> Result<Vec<u8>, TestError> {
-        let dmabuf = self.jc.take_screenshot().await?;
-        let qoi = buf_to_qoi(&dmabuf);
-        Ok(qoi)
+        self.jc.take_screenshot().await
     }
 
     #[allow(dead_code)]
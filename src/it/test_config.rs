@@ -1,6 +1,6 @@
 use {
     crate::{
-        backend::InputDeviceId,
+        backend::{ConnectorId, InputDeviceId},
         ifs::wl_seat::SeatId,
         it::test_error::{TestError, TestResult},
         utils::{copyhashmap::CopyHashMap, stack::Stack},
@@ -9,14 +9,18 @@ use {
     jay_config::{
         _private::{
             bincode_ops,
-            ipc::{ClientMessage, Response, ServerMessage},
+            ipc::{ClientMessage, Placement, Response, ServerMessage},
             ConfigEntry, VERSION,
         },
-        input::{InputDevice, Seat},
-        keyboard::{Keymap, ModifiedKeySym},
-        Axis, Direction,
+        exec::{ExitStatus, Process, Stdio},
+        idle::IdleStage,
+        input::{button::Button, InputDevice, ScrollDirection, Seat},
+        keyboard::{mods::Modifiers, BindingMode, KeyBinding, Keymap, ModifiedKeySym},
+        theme::sized::Resizable,
+        video::Connector,
+        Axis, ContainerLayout, Direction, Workspace,
     },
-    std::{cell::Cell, ops::Deref, ptr, rc::Rc},
+    std::{cell::Cell, ops::Deref, ptr, rc::Rc, time::Duration},
 };
 
 pub static TEST_CONFIG_ENTRY: ConfigEntry = ConfigEntry {
@@ -39,6 +43,13 @@ where
             responses: Default::default(),
             invoked_shortcuts: Default::default(),
             graphics_initialized: Cell::new(false),
+            locker_died: Cell::new(false),
+            entered_idle_stages: Default::default(),
+            resumed_idle_stages: Default::default(),
+            keyboard_groups: Default::default(),
+            invoked_button_bindings: Default::default(),
+            invoked_scroll_bindings: Default::default(),
+            exited_processes: Default::default(),
         });
         let old = CONFIG;
         CONFIG = tc.deref();
@@ -90,8 +101,14 @@ unsafe extern "C" fn handle_msg(data: *const u8, msg: *const u8, size: usize) {
             tc.responses.push(response);
         }
         ServerMessage::InvokeShortcut { seat, mods, sym } => {
+            tc.invoked_shortcuts.set(
+                (SeatId::from_raw(seat.0 as _), ModifiedKeySym { mods, sym }),
+                (),
+            );
+        }
+        ServerMessage::InvokeKeyBinding { seat, binding } => {
             tc.invoked_shortcuts
-                .set((SeatId::from_raw(seat.0 as _), mods | sym), ());
+                .set((SeatId::from_raw(seat.0 as _), binding.key), ());
         }
         ServerMessage::NewInputDevice { .. } => {}
         ServerMessage::DelInputDevice { .. } => {}
@@ -100,12 +117,40 @@ unsafe extern "C" fn handle_msg(data: *const u8, msg: *const u8, size: usize) {
         ServerMessage::NewConnector { .. } => {}
         ServerMessage::DelConnector { .. } => {}
         ServerMessage::TimerExpired { .. } => {}
+        ServerMessage::ProcessExited { process, status } => {
+            tc.exited_processes.set(process, status);
+        }
         ServerMessage::GraphicsInitialized => tc.graphics_initialized.set(true),
         ServerMessage::Clear => tc.clear(),
         ServerMessage::NewDrmDev { .. } => {}
         ServerMessage::DelDrmDev { .. } => {}
         ServerMessage::Idle => {}
         ServerMessage::DevicesEnumerated => {}
+        ServerMessage::LockerDied => tc.locker_died.set(true),
+        ServerMessage::IdleStageEntered { stage } => {
+            tc.entered_idle_stages.set(stage, ());
+        }
+        ServerMessage::IdleStageResumed { stage } => {
+            tc.resumed_idle_stages.set(stage, ());
+        }
+        ServerMessage::KeyboardGroupChanged { seat, group } => {
+            tc.keyboard_groups.set(SeatId::from_raw(seat.0 as _), group);
+        }
+        ServerMessage::InvokeButtonBinding { seat, mods, button } => {
+            let key = (SeatId::from_raw(seat.0 as _), mods, button);
+            let n = tc.invoked_button_bindings.get(&key).unwrap_or(0);
+            tc.invoked_button_bindings.set(key, n + 1);
+        }
+        ServerMessage::InvokeScrollBinding {
+            seat,
+            mods,
+            axis,
+            direction,
+        } => {
+            let key = (SeatId::from_raw(seat.0 as _), mods, axis, direction);
+            let n = tc.invoked_scroll_bindings.get(&key).unwrap_or(0);
+            tc.invoked_scroll_bindings.set(key, n + 1);
+        }
     }
 }
 
@@ -121,6 +166,13 @@ pub struct TestConfig {
     responses: Stack<Response>,
     pub invoked_shortcuts: CopyHashMap<(SeatId, ModifiedKeySym), ()>,
     pub graphics_initialized: Cell<bool>,
+    pub locker_died: Cell<bool>,
+    pub entered_idle_stages: CopyHashMap<IdleStage, ()>,
+    pub resumed_idle_stages: CopyHashMap<IdleStage, ()>,
+    pub keyboard_groups: CopyHashMap<SeatId, u32>,
+    pub invoked_button_bindings: CopyHashMap<(SeatId, Modifiers, Button), u32>,
+    pub invoked_scroll_bindings: CopyHashMap<(SeatId, Modifiers, Axis, ScrollDirection), u32>,
+    pub exited_processes: CopyHashMap<Process, ExitStatus>,
 }
 
 macro_rules! get_response {
@@ -170,9 +222,14 @@ impl TestConfig {
         Ok(SeatId::from_raw(seat.0 as _))
     }
 
-    pub fn show_workspace(&self, seat: SeatId, name: &str) -> Result<(), TestError> {
+    pub fn get_workspace(&self, name: &str) -> Result<Workspace, TestError> {
         let reply = self.send_with_reply(ClientMessage::GetWorkspace { name })?;
         get_response!(reply, GetWorkspace { workspace });
+        Ok(workspace)
+    }
+
+    pub fn show_workspace(&self, seat: SeatId, name: &str) -> Result<(), TestError> {
+        let workspace = self.get_workspace(name)?;
         self.send(ClientMessage::ShowWorkspace {
             seat: Seat(seat.raw() as _),
             workspace,
@@ -195,6 +252,71 @@ impl TestConfig {
         })
     }
 
+    pub fn spawn(
+        &self,
+        prog: &str,
+        args: &[&str],
+        stdout: Stdio,
+        stderr: Stdio,
+        placement: Placement,
+    ) -> Result<Process, TestError> {
+        let reply = self.send_with_reply(ClientMessage::Run2 {
+            prog,
+            args: args.iter().map(|a| a.to_string()).collect(),
+            env: vec![],
+            stdout,
+            stderr,
+            placement,
+        })?;
+        get_response!(reply, Run { process });
+        Ok(process)
+    }
+
+    pub fn get_process(&self, process: Process) -> Result<(Option<i32>, bool), TestError> {
+        let reply = self.send_with_reply(ClientMessage::GetProcess { process })?;
+        get_response!(reply, GetProcess { pid, alive });
+        Ok((pid, alive))
+    }
+
+    pub fn signal_process(&self, process: Process, signal: i32) -> TestResult {
+        self.send(ClientMessage::ProcessSignal { process, signal })
+    }
+
+    pub fn set_device_keymap(&self, device: InputDeviceId, keymap: Keymap) -> TestResult {
+        self.send(ClientMessage::DeviceSetKeymap {
+            device: InputDevice(device.raw() as _),
+            keymap,
+        })
+    }
+
+    pub fn get_keyboard_group(&self, seat: SeatId) -> Result<(u32, u32), TestError> {
+        let reply = self.send_with_reply(ClientMessage::SeatGetKeyboardGroup {
+            seat: Seat(seat.raw() as _),
+        })?;
+        get_response!(reply, GetKeyboardGroup { group, num_groups });
+        Ok((group, num_groups))
+    }
+
+    pub fn set_keyboard_group(&self, seat: SeatId, group: u32) -> TestResult {
+        self.send(ClientMessage::SeatSetKeyboardGroup {
+            seat: Seat(seat.raw() as _),
+            group,
+        })
+    }
+
+    pub fn next_keyboard_group(&self, seat: SeatId) -> TestResult {
+        self.send(ClientMessage::SeatNextKeyboardGroup {
+            seat: Seat(seat.raw() as _),
+        })
+    }
+
+    pub fn set_remember_keyboard_group(&self, seat: SeatId, remember: bool) -> TestResult {
+        self.send(ClientMessage::SeatSetRememberKeyboardGroup {
+            seat: Seat(seat.raw() as _),
+            remember,
+        })
+    }
+
     pub fn create_split(&self, seat: SeatId, axis: Axis) -> TestResult {
         self.send(ClientMessage::CreateSplit {
             seat: Seat(seat.raw() as _),
@@ -202,6 +324,20 @@ impl TestConfig {
         })
     }
 
+    pub fn resize(&self, seat: SeatId, direction: Direction, amount: i32) -> TestResult {
+        self.send(ClientMessage::Resize {
+            seat: Seat(seat.raw() as _),
+            direction,
+            amount,
+        })
+    }
+
+    pub fn equalize(&self, seat: SeatId) -> TestResult {
+        self.send(ClientMessage::Equalize {
+            seat: Seat(seat.raw() as _),
+        })
+    }
+
     pub fn set_mono(&self, seat: SeatId, mono: bool) -> TestResult {
         self.send(ClientMessage::SetMono {
             seat: Seat(seat.raw() as _),
@@ -209,6 +345,91 @@ impl TestConfig {
         })
     }
 
+    pub fn set_hide_titles(&self, hide: bool) -> TestResult {
+        self.send(ClientMessage::SetHideTitles { hide })
+    }
+
+    pub fn set_size(&self, sized: Resizable, size: i32) -> TestResult {
+        self.send(ClientMessage::SetSize { sized, size })
+    }
+
+    pub fn create_virtual_output(
+        &self,
+        width: i32,
+        height: i32,
+        refresh_millihz: u32,
+    ) -> Result<ConnectorId, TestError> {
+        let reply = self.send_with_reply(ClientMessage::CreateVirtualOutput {
+            width,
+            height,
+            refresh_millihz,
+        })?;
+        get_response!(reply, CreateVirtualOutput { connector });
+        Ok(ConnectorId::from_raw(connector.0 as _))
+    }
+
+    pub fn remove_virtual_output(&self, connector: ConnectorId) -> TestResult {
+        self.send(ClientMessage::RemoveVirtualOutput {
+            connector: Connector(connector.raw() as _),
+        })
+    }
+
+    pub fn set_smart_gaps(&self, enabled: bool) -> TestResult {
+        self.send(ClientMessage::SetSmartGaps { enabled })
+    }
+
+    pub fn seat_set_hide_titles(&self, seat: SeatId, hide: bool) -> TestResult {
+        self.send(ClientMessage::SeatSetHideTitles {
+            seat: Seat(seat.raw() as _),
+            hide,
+        })
+    }
+
+    pub fn set_layout(&self, seat: SeatId, layout: ContainerLayout) -> TestResult {
+        self.send(ClientMessage::SeatSetLayout {
+            seat: Seat(seat.raw() as _),
+            layout,
+        })
+    }
+
+    pub fn set_floating(&self, seat: SeatId, floating: bool) -> TestResult {
+        self.send(ClientMessage::SetFloating {
+            seat: Seat(seat.raw() as _),
+            floating,
+        })
+    }
+
+    pub fn toggle_sticky(&self, seat: SeatId) -> TestResult {
+        self.send(ClientMessage::SeatToggleSticky {
+            seat: Seat(seat.raw() as _),
+        })
+    }
+
+    pub fn minimize(&self, seat: SeatId) -> TestResult {
+        self.send(ClientMessage::SeatMinimize {
+            seat: Seat(seat.raw() as _),
+        })
+    }
+
+    pub fn restore_last_minimized(&self, seat: SeatId) -> TestResult {
+        self.send(ClientMessage::SeatRestoreLastMinimized {
+            seat: Seat(seat.raw() as _),
+        })
+    }
+
+    pub fn move_to_scratchpad(&self, seat: SeatId) -> TestResult {
+        self.send(ClientMessage::SeatMoveToScratchpad {
+            seat: Seat(seat.raw() as _),
+        })
+    }
+
+    pub fn toggle_scratchpad(&self, seat: SeatId, filter: Option<&str>) -> TestResult {
+        self.send(ClientMessage::SeatToggleScratchpad {
+            seat: Seat(seat.raw() as _),
+            filter,
+        })
+    }
+
     pub fn add_shortcut<T: Into<ModifiedKeySym>>(
         &self,
         seat: SeatId,
@@ -222,6 +443,50 @@ impl TestConfig {
         })
     }
 
+    pub fn add_binding(&self, seat: SeatId, binding: KeyBinding) -> TestResult {
+        self.send(ClientMessage::AddKeyBinding {
+            seat: Seat(seat.raw() as _),
+            binding,
+        })
+    }
+
+    pub fn add_button_binding(&self, seat: SeatId, mods: Modifiers, button: Button) -> TestResult {
+        self.send(ClientMessage::AddButtonBinding {
+            seat: Seat(seat.raw() as _),
+            mods,
+            button,
+        })
+    }
+
+    pub fn add_scroll_binding(
+        &self,
+        seat: SeatId,
+        mods: Modifiers,
+        axis: Axis,
+        direction: ScrollDirection,
+    ) -> TestResult {
+        self.send(ClientMessage::AddScrollBinding {
+            seat: Seat(seat.raw() as _),
+            mods,
+            axis,
+            direction,
+        })
+    }
+
+    pub fn set_window_drag_modifier(&self, seat: SeatId, mods: Modifiers) -> TestResult {
+        self.send(ClientMessage::SeatSetWindowDragModifier {
+            seat: Seat(seat.raw() as _),
+            mods,
+        })
+    }
+
+    pub fn set_binding_mode(&self, seat: SeatId, mode: BindingMode) -> TestResult {
+        self.send(ClientMessage::SeatSetBindingMode {
+            seat: Seat(seat.raw() as _),
+            mode,
+        })
+    }
+
     pub fn set_input_device_seat(&self, id: InputDeviceId, seat: SeatId) -> Result<(), TestError> {
         self.send(ClientMessage::SetSeat {
             device: InputDevice(id.raw() as _),
@@ -243,6 +508,20 @@ impl TestConfig {
         })
     }
 
+    pub fn set_lock_fallback_text(&self, text: &str) -> TestResult {
+        self.send(ClientMessage::SetLockFallbackText { text })
+    }
+
+    pub fn get_idle_stage(&self, name: &str) -> Result<IdleStage, TestError> {
+        let reply = self.send_with_reply(ClientMessage::GetIdleStage { name })?;
+        get_response!(reply, GetIdleStage { stage });
+        Ok(stage)
+    }
+
+    pub fn set_idle_stage_timeout(&self, stage: IdleStage, timeout: Duration) -> TestResult {
+        self.send(ClientMessage::SetIdleStageTimeout { stage, timeout })
+    }
+
     fn clear(&self) {
         unsafe {
             if let Some(srv) = self.srv.take() {
@@ -1,6 +1,14 @@
+pub mod test_activation;
+pub mod test_activation_token;
 pub mod test_callback;
 pub mod test_compositor;
+pub mod test_cursor_shape_device;
+pub mod test_cursor_shape_manager;
 pub mod test_display;
+pub mod test_idle_inhibit_manager;
+pub mod test_idle_inhibitor;
+pub mod test_idle_notification;
+pub mod test_idle_notifier;
 pub mod test_jay_compositor;
 pub mod test_keyboard;
 pub mod test_pointer;
@@ -8,6 +16,8 @@ pub mod test_region;
 pub mod test_registry;
 pub mod test_screenshot;
 pub mod test_seat;
+pub mod test_session_lock;
+pub mod test_session_lock_manager;
 pub mod test_shm;
 pub mod test_shm_buffer;
 pub mod test_shm_pool;
@@ -15,5 +25,7 @@ pub mod test_subcompositor;
 pub mod test_subsurface;
 pub mod test_surface;
 pub mod test_xdg_base;
+pub mod test_xdg_popup;
+pub mod test_xdg_positioner;
 pub mod test_xdg_surface;
 pub mod test_xdg_toplevel;
@@ -0,0 +1,63 @@
+use {
+    crate::{
+        it::{
+            test_error::TestError,
+            test_ifs::{test_activation_token::TestActivationToken, test_surface::TestSurface},
+            test_object::TestObject,
+            test_transport::TestTransport,
+        },
+        wire::{xdg_activation_v1::*, XdgActivationV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+};
+
+pub struct TestActivation {
+    pub id: XdgActivationV1Id,
+    pub tran: Rc<TestTransport>,
+    pub destroyed: Cell<bool>,
+}
+
+impl TestActivation {
+    pub fn destroy(&self) -> Result<(), TestError> {
+        if !self.destroyed.replace(true) {
+            self.tran.send(Destroy { self_id: self.id })?;
+        }
+        Ok(())
+    }
+
+    pub fn get_activation_token(&self) -> Result<Rc<TestActivationToken>, TestError> {
+        let token = Rc::new(TestActivationToken {
+            id: self.tran.id(),
+            tran: self.tran.clone(),
+            destroyed: Cell::new(false),
+            token: Default::default(),
+        });
+        self.tran.send(GetActivationToken {
+            self_id: self.id,
+            id: token.id,
+        })?;
+        self.tran.add_obj(token.clone())?;
+        Ok(token)
+    }
+
+    pub fn activate(&self, token: &str, surface: &TestSurface) -> Result<(), TestError> {
+        self.tran.send(Activate {
+            self_id: self.id,
+            token,
+            surface: surface.id,
+        })?;
+        Ok(())
+    }
+}
+
+test_object! {
+    TestActivation, XdgActivationV1;
+}
+
+impl TestObject for TestActivation {}
+
+impl Drop for TestActivation {
+    fn drop(&mut self) {
+        let _ = self.destroy();
+    }
+}
@@ -0,0 +1,59 @@
+use {
+    crate::{
+        it::{
+            test_error::TestError,
+            test_ifs::{
+                test_cursor_shape_device::TestCursorShapeDevice, test_pointer::TestPointer,
+            },
+            test_object::TestObject,
+            test_transport::TestTransport,
+        },
+        wire::{wp_cursor_shape_manager_v1::*, WpCursorShapeManagerV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+};
+
+pub struct TestCursorShapeManager {
+    pub id: WpCursorShapeManagerV1Id,
+    pub tran: Rc<TestTransport>,
+    pub destroyed: Cell<bool>,
+}
+
+impl TestCursorShapeManager {
+    pub fn destroy(&self) -> Result<(), TestError> {
+        if !self.destroyed.replace(true) {
+            self.tran.send(Destroy { self_id: self.id })?;
+        }
+        Ok(())
+    }
+
+    pub fn get_pointer(
+        &self,
+        pointer: &TestPointer,
+    ) -> Result<Rc<TestCursorShapeDevice>, TestError> {
+        let device = Rc::new(TestCursorShapeDevice {
+            id: self.tran.id(),
+            tran: self.tran.clone(),
+            destroyed: Cell::new(false),
+        });
+        self.tran.send(GetPointer {
+            self_id: self.id,
+            cursor_shape_device: device.id,
+            pointer: pointer.id,
+        })?;
+        self.tran.add_obj(device.clone())?;
+        Ok(device)
+    }
+}
+
+test_object! {
+    TestCursorShapeManager, WpCursorShapeManagerV1;
+}
+
+impl TestObject for TestCursorShapeManager {}
+
+impl Drop for TestCursorShapeManager {
+    fn drop(&mut self) {
+        let _ = self.destroy();
+    }
+}
@@ -0,0 +1,57 @@
+use {
+    crate::{
+        it::{
+            test_error::TestError,
+            test_ifs::{test_idle_inhibitor::TestIdleInhibitor, test_surface::TestSurface},
+            test_object::TestObject,
+            test_transport::TestTransport,
+        },
+        wire::{zwp_idle_inhibit_manager_v1::*, ZwpIdleInhibitManagerV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+};
+
+pub struct TestIdleInhibitManager {
+    pub id: ZwpIdleInhibitManagerV1Id,
+    pub tran: Rc<TestTransport>,
+    pub destroyed: Cell<bool>,
+}
+
+impl TestIdleInhibitManager {
+    pub fn destroy(&self) -> Result<(), TestError> {
+        if !self.destroyed.replace(true) {
+            self.tran.send(Destroy { self_id: self.id })?;
+        }
+        Ok(())
+    }
+
+    pub fn create_inhibitor(
+        &self,
+        surface: &TestSurface,
+    ) -> Result<Rc<TestIdleInhibitor>, TestError> {
+        let inhibitor = Rc::new(TestIdleInhibitor {
+            id: self.tran.id(),
+            tran: self.tran.clone(),
+            destroyed: Cell::new(false),
+        });
+        self.tran.send(CreateInhibitor {
+            self_id: self.id,
+            id: inhibitor.id,
+            surface: surface.id,
+        })?;
+        self.tran.add_obj(inhibitor.clone())?;
+        Ok(inhibitor)
+    }
+}
+
+test_object! {
+    TestIdleInhibitManager, ZwpIdleInhibitManagerV1;
+}
+
+impl TestObject for TestIdleInhibitManager {}
+
+impl Drop for TestIdleInhibitManager {
+    fn drop(&mut self) {
+        let _ = self.destroy();
+    }
+}
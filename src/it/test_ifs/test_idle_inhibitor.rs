@@ -0,0 +1,34 @@
+use {
+    crate::{
+        it::{test_error::TestError, test_object::TestObject, test_transport::TestTransport},
+        wire::{zwp_idle_inhibitor_v1::*, ZwpIdleInhibitorV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+};
+
+pub struct TestIdleInhibitor {
+    pub id: ZwpIdleInhibitorV1Id,
+    pub tran: Rc<TestTransport>,
+    pub destroyed: Cell<bool>,
+}
+
+impl TestIdleInhibitor {
+    pub fn destroy(&self) -> Result<(), TestError> {
+        if !self.destroyed.replace(true) {
+            self.tran.send(Destroy { self_id: self.id })?;
+        }
+        Ok(())
+    }
+}
+
+test_object! {
+    TestIdleInhibitor, ZwpIdleInhibitorV1;
+}
+
+impl TestObject for TestIdleInhibitor {}
+
+impl Drop for TestIdleInhibitor {
+    fn drop(&mut self) {
+        let _ = self.destroy();
+    }
+}
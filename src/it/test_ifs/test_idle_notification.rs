@@ -0,0 +1,55 @@
+use {
+    crate::{
+        it::{
+            test_error::TestError, test_object::TestObject, test_transport::TestTransport,
+            testrun::ParseFull,
+        },
+        utils::buffd::MsgParser,
+        wire::{ext_idle_notification_v1::*, ExtIdleNotificationV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+};
+
+pub struct TestIdleNotification {
+    pub id: ExtIdleNotificationV1Id,
+    pub tran: Rc<TestTransport>,
+    pub destroyed: Cell<bool>,
+    pub idled: Cell<bool>,
+    pub resumed: Cell<bool>,
+}
+
+impl TestIdleNotification {
+    pub fn destroy(&self) -> Result<(), TestError> {
+        if !self.destroyed.replace(true) {
+            self.tran.send(Destroy { self_id: self.id })?;
+        }
+        Ok(())
+    }
+
+    fn handle_idled(&self, parser: MsgParser<'_, '_>) -> Result<(), TestError> {
+        let _ev = Idled::parse_full(parser)?;
+        self.idled.set(true);
+        Ok(())
+    }
+
+    fn handle_resumed(&self, parser: MsgParser<'_, '_>) -> Result<(), TestError> {
+        let _ev = Resumed::parse_full(parser)?;
+        self.resumed.set(true);
+        Ok(())
+    }
+}
+
+test_object! {
+    TestIdleNotification, ExtIdleNotificationV1;
+
+    IDLED => handle_idled,
+    RESUMED => handle_resumed,
+}
+
+impl TestObject for TestIdleNotification {}
+
+impl Drop for TestIdleNotification {
+    fn drop(&mut self) {
+        let _ = self.destroy();
+    }
+}
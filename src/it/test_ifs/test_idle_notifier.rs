@@ -0,0 +1,59 @@
+use {
+    crate::{
+        it::{
+            test_error::TestError, test_ifs::test_idle_notification::TestIdleNotification,
+            test_object::TestObject, test_transport::TestTransport,
+        },
+        wire::{ext_idle_notifier_v1::*, ExtIdleNotifierV1Id, WlSeatId},
+    },
+    std::{cell::Cell, rc::Rc},
+};
+
+pub struct TestIdleNotifier {
+    pub id: ExtIdleNotifierV1Id,
+    pub tran: Rc<TestTransport>,
+    pub destroyed: Cell<bool>,
+}
+
+impl TestIdleNotifier {
+    pub fn destroy(&self) -> Result<(), TestError> {
+        if !self.destroyed.replace(true) {
+            self.tran.send(Destroy { self_id: self.id })?;
+        }
+        Ok(())
+    }
+
+    pub fn get_idle_notification(
+        &self,
+        timeout: u32,
+        seat: WlSeatId,
+    ) -> Result<Rc<TestIdleNotification>, TestError> {
+        let notification = Rc::new(TestIdleNotification {
+            id: self.tran.id(),
+            tran: self.tran.clone(),
+            destroyed: Cell::new(false),
+            idled: Cell::new(false),
+            resumed: Cell::new(false),
+        });
+        self.tran.send(GetIdleNotification {
+            self_id: self.id,
+            id: notification.id,
+            timeout,
+            seat,
+        })?;
+        self.tran.add_obj(notification.clone())?;
+        Ok(notification)
+    }
+}
+
+test_object! {
+    TestIdleNotifier, ExtIdleNotifierV1;
+}
+
+impl TestObject for TestIdleNotifier {}
+
+impl Drop for TestIdleNotifier {
+    fn drop(&mut self) {
+        let _ = self.destroy();
+    }
+}
@@ -8,7 +8,6 @@ use {
         utils::buffd::MsgParser,
         wire::{
             jay_compositor::{self, *},
-            jay_screenshot::Dmabuf,
             JayCompositorId,
         },
     },
@@ -33,7 +32,8 @@ impl TestJayCompositor {
         }
     }
 
-    pub async fn take_screenshot(&self) -> Result<Dmabuf, TestError> {
+    /// Takes a screenshot and returns it in QOI format.
+    pub async fn take_screenshot(&self) -> Result<Vec<u8>, TestError> {
         let js = Rc::new(TestJayScreenshot {
             id: self.tran.id(),
             result: Cell::new(None),
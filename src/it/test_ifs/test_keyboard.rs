@@ -24,6 +24,7 @@ pub struct TestKeyboard {
     pub destroyed: Once,
     pub enter: TEEH<TestEnterEvent>,
     pub leave: TEEH<Leave>,
+    pub modifiers: TEEH<Modifiers>,
 }
 
 impl TestKeyboard {
@@ -61,7 +62,8 @@ impl TestKeyboard {
     }
 
     fn handle_modifiers(&self, parser: MsgParser<'_, '_>) -> TestResult {
-        let _ev = Modifiers::parse_full(parser)?;
+        let ev = Modifiers::parse_full(parser)?;
+        self.modifiers.push(ev);
         Ok(())
     }
 
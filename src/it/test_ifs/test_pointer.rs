@@ -19,6 +19,7 @@ pub struct TestPointer {
     pub leave: TEEH<Leave>,
     pub enter: TEEH<Enter>,
     pub motion: TEEH<Motion>,
+    pub axis: TEEH<Axis>,
 }
 
 impl TestPointer {
@@ -53,7 +54,8 @@ impl TestPointer {
     }
 
     fn handle_axis(&self, parser: MsgParser<'_, '_>) -> TestResult {
-        let _ev = Axis::parse_full(parser)?;
+        let ev = Axis::parse_full(parser)?;
+        self.axis.push(ev);
         Ok(())
     }
 
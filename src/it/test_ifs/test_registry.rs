@@ -5,9 +5,12 @@ use {
         it::{
             test_error::TestError,
             test_ifs::{
-                test_compositor::TestCompositor, test_jay_compositor::TestJayCompositor,
-                test_shm::TestShm, test_subcompositor::TestSubcompositor,
-                test_xdg_base::TestXdgWmBase,
+                test_activation::TestActivation, test_compositor::TestCompositor,
+                test_cursor_shape_manager::TestCursorShapeManager,
+                test_idle_inhibit_manager::TestIdleInhibitManager,
+                test_idle_notifier::TestIdleNotifier, test_jay_compositor::TestJayCompositor,
+                test_session_lock_manager::TestSessionLockManager, test_shm::TestShm,
+                test_subcompositor::TestSubcompositor, test_xdg_base::TestXdgWmBase,
             },
             test_object::TestObject,
             test_transport::TestTransport,
@@ -31,6 +34,11 @@ pub struct TestRegistrySingletons {
     pub wl_subcompositor: u32,
     pub wl_shm: u32,
     pub xdg_wm_base: u32,
+    pub ext_session_lock_manager_v1: u32,
+    pub ext_idle_notifier_v1: u32,
+    pub wp_cursor_shape_manager_v1: u32,
+    pub xdg_activation_v1: u32,
+    pub zwp_idle_inhibit_manager_v1: u32,
 }
 
 pub struct TestRegistry {
@@ -43,6 +51,11 @@ pub struct TestRegistry {
     pub subcompositor: CloneCell<Option<Rc<TestSubcompositor>>>,
     pub shm: CloneCell<Option<Rc<TestShm>>>,
     pub xdg: CloneCell<Option<Rc<TestXdgWmBase>>>,
+    pub session_lock_manager: CloneCell<Option<Rc<TestSessionLockManager>>>,
+    pub idle_notifier: CloneCell<Option<Rc<TestIdleNotifier>>>,
+    pub cursor_shape_manager: CloneCell<Option<Rc<TestCursorShapeManager>>>,
+    pub activation: CloneCell<Option<Rc<TestActivation>>>,
+    pub idle_inhibit_manager: CloneCell<Option<Rc<TestIdleInhibitManager>>>,
     pub seats: CopyHashMap<GlobalName, Rc<WlSeatGlobal>>,
 }
 
@@ -90,6 +103,11 @@ impl TestRegistry {
             wl_subcompositor,
             wl_shm,
             xdg_wm_base,
+            ext_session_lock_manager_v1,
+            ext_idle_notifier_v1,
+            wp_cursor_shape_manager_v1,
+            xdg_activation_v1,
+            zwp_idle_inhibit_manager_v1,
         };
         self.singletons.set(Some(singletons.clone()));
         Ok(singletons)
@@ -165,6 +183,76 @@ impl TestRegistry {
         Ok(jc)
     }
 
+    pub async fn get_session_lock_manager(&self) -> Result<Rc<TestSessionLockManager>, TestError> {
+        singleton!(self.session_lock_manager);
+        let singletons = self.get_singletons().await?;
+        singleton!(self.session_lock_manager);
+        let jc = Rc::new(TestSessionLockManager {
+            id: self.tran.id(),
+            tran: self.tran.clone(),
+            destroyed: Cell::new(false),
+        });
+        self.bind(&jc, singletons.ext_session_lock_manager_v1, 1)?;
+        self.session_lock_manager.set(Some(jc.clone()));
+        Ok(jc)
+    }
+
+    pub async fn get_idle_notifier(&self) -> Result<Rc<TestIdleNotifier>, TestError> {
+        singleton!(self.idle_notifier);
+        let singletons = self.get_singletons().await?;
+        singleton!(self.idle_notifier);
+        let jc = Rc::new(TestIdleNotifier {
+            id: self.tran.id(),
+            tran: self.tran.clone(),
+            destroyed: Cell::new(false),
+        });
+        self.bind(&jc, singletons.ext_idle_notifier_v1, 1)?;
+        self.idle_notifier.set(Some(jc.clone()));
+        Ok(jc)
+    }
+
+    pub async fn get_cursor_shape_manager(&self) -> Result<Rc<TestCursorShapeManager>, TestError> {
+        singleton!(self.cursor_shape_manager);
+        let singletons = self.get_singletons().await?;
+        singleton!(self.cursor_shape_manager);
+        let jc = Rc::new(TestCursorShapeManager {
+            id: self.tran.id(),
+            tran: self.tran.clone(),
+            destroyed: Cell::new(false),
+        });
+        self.bind(&jc, singletons.wp_cursor_shape_manager_v1, 1)?;
+        self.cursor_shape_manager.set(Some(jc.clone()));
+        Ok(jc)
+    }
+
+    pub async fn get_activation(&self) -> Result<Rc<TestActivation>, TestError> {
+        singleton!(self.activation);
+        let singletons = self.get_singletons().await?;
+        singleton!(self.activation);
+        let jc = Rc::new(TestActivation {
+            id: self.tran.id(),
+            tran: self.tran.clone(),
+            destroyed: Cell::new(false),
+        });
+        self.bind(&jc, singletons.xdg_activation_v1, 1)?;
+        self.activation.set(Some(jc.clone()));
+        Ok(jc)
+    }
+
+    pub async fn get_idle_inhibit_manager(&self) -> Result<Rc<TestIdleInhibitManager>, TestError> {
+        singleton!(self.idle_inhibit_manager);
+        let singletons = self.get_singletons().await?;
+        singleton!(self.idle_inhibit_manager);
+        let jc = Rc::new(TestIdleInhibitManager {
+            id: self.tran.id(),
+            tran: self.tran.clone(),
+            destroyed: Cell::new(false),
+        });
+        self.bind(&jc, singletons.zwp_idle_inhibit_manager_v1, 1)?;
+        self.idle_inhibit_manager.set(Some(jc.clone()));
+        Ok(jc)
+    }
+
     pub fn bind<O: TestObject>(
         &self,
         obj: &Rc<O>,
@@ -1,5 +1,6 @@
 use {
     crate::{
+        cli::screenshot::{buf_to_qoi, shm_to_qoi},
         it::{test_error::TestError, test_object::TestObject, testrun::ParseFull},
         utils::buffd::MsgParser,
         wire::{jay_screenshot::*, JayScreenshotId},
@@ -9,13 +10,19 @@ use {
 
 pub struct TestJayScreenshot {
     pub id: JayScreenshotId,
-    pub result: Cell<Option<Result<Dmabuf, String>>>,
+    pub result: Cell<Option<Result<Vec<u8>, String>>>,
 }
 
 impl TestJayScreenshot {
     fn handle_dmabuf(&self, parser: MsgParser<'_, '_>) -> Result<(), TestError> {
         let ev = Dmabuf::parse_full(parser)?;
-        self.result.set(Some(Ok(ev)));
+        self.result.set(Some(Ok(buf_to_qoi(&ev))));
+        Ok(())
+    }
+
+    fn handle_shm(&self, parser: MsgParser<'_, '_>) -> Result<(), TestError> {
+        let ev = Shm::parse_full(parser)?;
+        self.result.set(Some(Ok(shm_to_qoi(&ev))));
         Ok(())
     }
 
@@ -30,6 +37,7 @@ test_object! {
     TestJayScreenshot, JayScreenshot;
 
     DMABUF => handle_dmabuf,
+    SHM => handle_shm,
     ERROR => handle_error,
 }
 
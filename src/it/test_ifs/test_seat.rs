@@ -44,6 +44,7 @@ impl TestSeat {
             destroyed: Default::default(),
             enter: Default::default(),
             leave: Default::default(),
+            modifiers: Default::default(),
         });
         self.tran.add_obj(kb.clone())?;
         self.tran.sync().await;
@@ -66,6 +67,7 @@ impl TestSeat {
             leave: Rc::new(Default::default()),
             enter: Rc::new(Default::default()),
             motion: Rc::new(Default::default()),
+            axis: Rc::new(Default::default()),
         });
         self.tran.add_obj(pointer.clone())?;
         self.tran.sync().await;
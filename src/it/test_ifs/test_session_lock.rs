@@ -0,0 +1,62 @@
+use {
+    crate::{
+        it::{
+            test_error::TestError, test_object::TestObject, test_transport::TestTransport,
+            testrun::ParseFull,
+        },
+        utils::buffd::MsgParser,
+        wire::{ext_session_lock_v1::*, ExtSessionLockV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+};
+
+pub struct TestSessionLock {
+    pub id: ExtSessionLockV1Id,
+    pub tran: Rc<TestTransport>,
+    pub destroyed: Cell<bool>,
+    pub locked: Cell<bool>,
+    pub finished: Cell<bool>,
+}
+
+impl TestSessionLock {
+    pub fn destroy(&self) -> Result<(), TestError> {
+        if !self.destroyed.replace(true) {
+            self.tran.send(Destroy { self_id: self.id })?;
+        }
+        Ok(())
+    }
+
+    pub fn unlock_and_destroy(&self) -> Result<(), TestError> {
+        if !self.destroyed.replace(true) {
+            self.tran.send(UnlockAndDestroy { self_id: self.id })?;
+        }
+        Ok(())
+    }
+
+    fn handle_locked(&self, parser: MsgParser<'_, '_>) -> Result<(), TestError> {
+        let _ev = Locked::parse_full(parser)?;
+        self.locked.set(true);
+        Ok(())
+    }
+
+    fn handle_finished(&self, parser: MsgParser<'_, '_>) -> Result<(), TestError> {
+        let _ev = Finished::parse_full(parser)?;
+        self.finished.set(true);
+        Ok(())
+    }
+}
+
+test_object! {
+    TestSessionLock, ExtSessionLockV1;
+
+    LOCKED => handle_locked,
+    FINISHED => handle_finished,
+}
+
+impl TestObject for TestSessionLock {}
+
+impl Drop for TestSessionLock {
+    fn drop(&mut self) {
+        let _ = self.destroy();
+    }
+}
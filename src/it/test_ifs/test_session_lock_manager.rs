@@ -0,0 +1,54 @@
+use {
+    crate::{
+        it::{
+            test_error::TestError, test_ifs::test_session_lock::TestSessionLock,
+            test_object::TestObject, test_transport::TestTransport,
+        },
+        wire::{ext_session_lock_manager_v1::*, ExtSessionLockManagerV1Id},
+    },
+    std::{cell::Cell, rc::Rc},
+};
+
+pub struct TestSessionLockManager {
+    pub id: ExtSessionLockManagerV1Id,
+    pub tran: Rc<TestTransport>,
+    pub destroyed: Cell<bool>,
+}
+
+impl TestSessionLockManager {
+    pub fn destroy(&self) -> Result<(), TestError> {
+        if !self.destroyed.replace(true) {
+            self.tran.send(Destroy { self_id: self.id })?;
+        }
+        Ok(())
+    }
+
+    pub async fn lock(&self) -> Result<Rc<TestSessionLock>, TestError> {
+        let lock = Rc::new(TestSessionLock {
+            id: self.tran.id(),
+            tran: self.tran.clone(),
+            destroyed: Cell::new(false),
+            locked: Cell::new(false),
+            finished: Cell::new(false),
+        });
+        self.tran.send(Lock {
+            self_id: self.id,
+            id: lock.id,
+        })?;
+        self.tran.add_obj(lock.clone())?;
+        self.tran.sync().await;
+        Ok(lock)
+    }
+}
+
+test_object! {
+    TestSessionLockManager, ExtSessionLockManagerV1;
+}
+
+impl TestObject for TestSessionLockManager {}
+
+impl Drop for TestSessionLockManager {
+    fn drop(&mut self) {
+        let _ = self.destroy();
+    }
+}
@@ -0,0 +1,70 @@
+use {
+    crate::{
+        ifs::wl_surface::xdg_surface::xdg_popup::XdgPopup,
+        it::{
+            test_error::TestError, test_object::TestObject, test_transport::TestTransport,
+            testrun::ParseFull,
+        },
+        utils::buffd::MsgParser,
+        wire::{xdg_popup::*, WlSeatId, XdgPopupId},
+    },
+    std::{cell::Cell, rc::Rc},
+};
+
+pub struct TestXdgPopup {
+    pub id: XdgPopupId,
+    pub tran: Rc<TestTransport>,
+    pub server: Rc<XdgPopup>,
+    pub destroyed: Cell<bool>,
+    pub done: Cell<bool>,
+}
+
+impl TestXdgPopup {
+    pub fn destroy(&self) -> Result<(), TestError> {
+        if !self.destroyed.replace(true) {
+            self.tran.send(Destroy { self_id: self.id })?;
+        }
+        Ok(())
+    }
+
+    pub fn grab(&self, seat: WlSeatId, serial: u32) -> Result<(), TestError> {
+        self.tran.send(Grab {
+            self_id: self.id,
+            seat,
+            serial,
+        })?;
+        Ok(())
+    }
+
+    fn handle_configure(&self, parser: MsgParser<'_, '_>) -> Result<(), TestError> {
+        let _ev = Configure::parse_full(parser)?;
+        Ok(())
+    }
+
+    fn handle_popup_done(&self, parser: MsgParser<'_, '_>) -> Result<(), TestError> {
+        let _ev = PopupDone::parse_full(parser)?;
+        self.done.set(true);
+        Ok(())
+    }
+
+    fn handle_repositioned(&self, parser: MsgParser<'_, '_>) -> Result<(), TestError> {
+        let _ev = Repositioned::parse_full(parser)?;
+        Ok(())
+    }
+}
+
+impl Drop for TestXdgPopup {
+    fn drop(&mut self) {
+        let _ = self.destroy();
+    }
+}
+
+test_object! {
+    TestXdgPopup, XdgPopup;
+
+    CONFIGURE => handle_configure,
+    POPUP_DONE => handle_popup_done,
+    REPOSITIONED => handle_repositioned,
+}
+
+impl TestObject for TestXdgPopup {}
@@ -2,8 +2,14 @@ use {
     crate::{
         ifs::wl_surface::xdg_surface::XdgSurface,
         it::{
-            test_error::TestError, test_ifs::test_xdg_toplevel::TestXdgToplevel,
-            test_object::TestObject, test_transport::TestTransport, testrun::ParseFull,
+            test_error::TestError,
+            test_ifs::{
+                test_xdg_popup::TestXdgPopup, test_xdg_positioner::TestXdgPositioner,
+                test_xdg_toplevel::TestXdgToplevel,
+            },
+            test_object::TestObject,
+            test_transport::TestTransport,
+            testrun::ParseFull,
         },
         utils::buffd::MsgParser,
         wire::{xdg_surface::*, XdgSurfaceId},
@@ -50,6 +56,31 @@ impl TestXdgSurface {
         Ok(tl)
     }
 
+    pub async fn create_popup(
+        &self,
+        parent: &TestXdgSurface,
+        positioner: &TestXdgPositioner,
+    ) -> Result<Rc<TestXdgPopup>, TestError> {
+        let id = self.tran.id();
+        self.tran.send(GetPopup {
+            self_id: self.id,
+            id,
+            parent: parent.id,
+            positioner: positioner.id,
+        })?;
+        self.tran.sync().await;
+        let server = self.tran.get_server_obj(id)?;
+        let popup = Rc::new(TestXdgPopup {
+            id,
+            tran: self.tran.clone(),
+            server,
+            destroyed: Cell::new(false),
+            done: Cell::new(false),
+        });
+        self.tran.add_obj(popup.clone())?;
+        Ok(popup)
+    }
+
     pub fn ack_configure(&self, serial: u32) -> Result<(), TestError> {
         self.tran.send(AckConfigure {
             self_id: self.id,
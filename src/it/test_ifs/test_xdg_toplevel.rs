@@ -9,7 +9,7 @@ use {
         },
         tree::{ContainerNode, ToplevelNode},
         utils::buffd::MsgParser,
-        wire::{xdg_toplevel::*, XdgToplevelId},
+        wire::{xdg_toplevel::*, WlSeatId, XdgToplevelId},
     },
     ahash::AHashSet,
     std::{
@@ -39,6 +39,48 @@ impl TestXdgToplevel {
         Ok(())
     }
 
+    pub fn set_minimized(&self) -> Result<(), TestError> {
+        self.tran.send(SetMinimized { self_id: self.id })?;
+        Ok(())
+    }
+
+    pub fn set_maximized(&self) -> Result<(), TestError> {
+        self.tran.send(SetMaximized { self_id: self.id })?;
+        Ok(())
+    }
+
+    pub fn unset_maximized(&self) -> Result<(), TestError> {
+        self.tran.send(UnsetMaximized { self_id: self.id })?;
+        Ok(())
+    }
+
+    pub fn resize(&self, seat: WlSeatId, serial: u32, edges: u32) -> Result<(), TestError> {
+        self.tran.send(Resize {
+            self_id: self.id,
+            seat,
+            serial,
+            edges,
+        })?;
+        Ok(())
+    }
+
+    pub fn show_window_menu(
+        &self,
+        seat: WlSeatId,
+        serial: u32,
+        x: i32,
+        y: i32,
+    ) -> Result<(), TestError> {
+        self.tran.send(ShowWindowMenu {
+            self_id: self.id,
+            seat,
+            serial,
+            x,
+            y,
+        })?;
+        Ok(())
+    }
+
     pub fn container_parent(&self) -> TestResult<Rc<ContainerNode>> {
         let parent = match self.server.tl_data().parent.get() {
             Some(p) => p,
@@ -57,6 +57,11 @@ impl TestTransport {
             subcompositor: Default::default(),
             shm: Default::default(),
             xdg: Default::default(),
+            session_lock_manager: Default::default(),
+            idle_notifier: Default::default(),
+            cursor_shape_manager: Default::default(),
+            activation: Default::default(),
+            idle_inhibit_manager: Default::default(),
             seats: Default::default(),
         });
         self.send(wl_display::GetRegistry {
@@ -15,6 +15,7 @@ use {
         tree::OutputNode,
         utils::{bitfield::Bitfield, buffd::MsgParser, oserror::OsErrorExt, stack::Stack},
     },
+    jay_config::exec::{ExitStatus, Process},
     std::{
         cell::{Cell, RefCell},
         rc::Rc,
@@ -123,6 +124,16 @@ impl TestRun {
     pub async fn sync(&self) {
         self.state.eng.yield_now().await;
     }
+
+    pub async fn wait_for_exit(&self, process: Process) -> Result<ExitStatus, TestError> {
+        for _ in 0..200 {
+            if let Some(status) = self.cfg.exited_processes.get(&process) {
+                return Ok(status);
+            }
+            self.state.wheel.timeout(10).await?;
+        }
+        bail!("Process {:?} did not exit", process);
+    }
 }
 
 pub trait ParseFull<'a>: Sized {
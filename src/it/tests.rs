@@ -5,6 +5,9 @@ use {
 
 macro_rules! testcase {
     () => {
+        testcase!(uses_forker: false);
+    };
+    (uses_forker: $uses_forker:expr) => {
         pub struct Test;
 
         impl crate::it::tests::TestCase for Test {
@@ -22,6 +25,10 @@ macro_rules! testcase {
             ) -> Box<dyn std::future::Future<Output = crate::it::test_error::TestResult>> {
                 Box::new(test(testrun))
             }
+
+            fn uses_forker(&self) -> bool {
+                $uses_forker
+            }
         }
     };
 }
@@ -44,11 +51,39 @@ mod t0015_scroll_partial;
 mod t0016_scroll_ws;
 mod t0017_remove_unused_ws;
 mod t0018_click_to_active_ws;
+mod t0019_locker_died;
+mod t0020_lock_output_hotplug;
+mod t0021_idle_notification;
+mod t0022_idle_stages;
+mod t0023_keyboard_groups;
+mod t0024_device_keymap;
+mod t0025_key_bindings;
+mod t0026_pointer_bindings;
+mod t0027_window_drag;
+mod t0028_container_resize;
+mod t0029_hide_titles;
+mod t0030_scratchpad;
+mod t0031_minimize;
+mod t0032_maximize;
+mod t0033_layouts;
+mod t0034_sticky;
+mod t0035_popup_grab;
+mod t0036_client_resize;
+mod t0037_window_menu;
+mod t0038_cursor_shape;
+mod t0039_keyboard_switch_locks;
+mod t0040_process;
+mod t0041_gaps;
+mod t0042_placement;
+mod t0043_virtual_output;
 
 pub trait TestCase: Sync {
     fn name(&self) -> &'static str;
     fn dir(&self) -> &'static str;
     fn run(&self, testrun: Rc<TestRun>) -> Box<dyn Future<Output = Result<(), TestError>>>;
+    /// Whether the test spawns processes. Such tests run on the main thread before all
+    /// other tests.
+    fn uses_forker(&self) -> bool;
 }
 
 pub fn tests() -> Vec<&'static dyn TestCase> {
@@ -80,5 +115,30 @@ pub fn tests() -> Vec<&'static dyn TestCase> {
         t0016_scroll_ws,
         t0017_remove_unused_ws,
         t0018_click_to_active_ws,
+        t0019_locker_died,
+        t0020_lock_output_hotplug,
+        t0021_idle_notification,
+        t0022_idle_stages,
+        t0023_keyboard_groups,
+        t0024_device_keymap,
+        t0025_key_bindings,
+        t0026_pointer_bindings,
+        t0027_window_drag,
+        t0028_container_resize,
+        t0029_hide_titles,
+        t0030_scratchpad,
+        t0031_minimize,
+        t0032_maximize,
+        t0033_layouts,
+        t0034_sticky,
+        t0035_popup_grab,
+        t0036_client_resize,
+        t0037_window_menu,
+        t0038_cursor_shape,
+        t0039_keyboard_switch_locks,
+        t0040_process,
+        t0041_gaps,
+        t0042_placement,
+        t0043_virtual_output,
     }
 }
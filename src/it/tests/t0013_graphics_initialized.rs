@@ -14,5 +14,11 @@ async fn test(run: Rc<TestRun>) -> TestResult {
 
     tassert!(run.cfg.graphics_initialized.get());
 
+    let ctx = match run.state.render_ctx.get() {
+        Some(ctx) => ctx,
+        _ => bail!("No render context was installed"),
+    };
+    tassert_eq!(ctx.is_software(), run.backend.software_renderer.get());
+
     Ok(())
 }
@@ -0,0 +1,44 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test that the session stays locked when the locker dies and that another locker can take over
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    run.cfg.set_lock_fallback_text("The session is locked")?;
+    let text = run.state.lock.fallback_text.get();
+    tassert_eq!(text.as_str(), "The session is locked");
+
+    let locker = run.create_client().await?;
+    let manager = locker.registry.get_session_lock_manager().await?;
+    let lock = manager.lock().await?;
+    tassert!(lock.locked.get());
+    tassert!(run.state.lock.locked.get());
+
+    locker.tran.kill();
+    run.state.clients.kill(locker.server.id);
+    run.sync().await;
+
+    tassert!(run.state.lock.locked.get());
+    tassert!(run.state.lock.lock.get().is_none());
+    tassert!(run.cfg.locker_died.get());
+    tassert!(ds.output.render_data.borrow_mut().lock_fallback.is_some());
+
+    let client = run.create_client().await?;
+    let manager = client.registry.get_session_lock_manager().await?;
+    let lock = manager.lock().await?;
+    tassert!(lock.locked.get());
+    tassert!(run.state.lock.lock.get().is_some());
+
+    let lock2 = manager.lock().await?;
+    tassert!(lock2.finished.get());
+
+    lock.unlock_and_destroy()?;
+    client.sync().await;
+    tassert!(!run.state.lock.locked.get());
+
+    Ok(())
+}
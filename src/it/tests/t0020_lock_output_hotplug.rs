@@ -0,0 +1,51 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test that outputs connected or disconnected while the session is locked stay locked
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+
+    let client = run.create_client().await?;
+    let manager = client.registry.get_session_lock_manager().await?;
+    let lock = manager.lock().await?;
+    tassert!(lock.locked.get());
+
+    let connector = run.backend.add_connector(2);
+    client.sync().await;
+
+    let output = match run.state.outputs.get(&connector.id) {
+        Some(o) => o.node.clone(),
+        _ => bail!("Hotplugged output was not created"),
+    };
+    tassert!(run.state.lock.locked.get());
+    tassert!(output.lock_surface.get().is_none());
+
+    client.tran.kill();
+    run.state.clients.kill(client.server.id);
+    run.sync().await;
+
+    tassert!(run.state.lock.locked.get());
+    tassert!(ds.output.render_data.borrow_mut().lock_fallback.is_some());
+    tassert!(output.render_data.borrow_mut().lock_fallback.is_some());
+
+    connector.disconnect();
+    run.sync().await;
+
+    tassert!(run.state.outputs.get(&connector.id).is_none());
+    tassert!(run.state.lock.locked.get());
+
+    let connector = run.backend.add_connector(3);
+    run.sync().await;
+
+    let output = match run.state.outputs.get(&connector.id) {
+        Some(o) => o.node.clone(),
+        _ => bail!("Hotplugged output was not created"),
+    };
+    tassert!(output.render_data.borrow_mut().lock_fallback.is_some());
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test that idle notifications are sent after the timeout and resumed on input
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+
+    let client = run.create_client().await?;
+    let seat = client.get_default_seat().await?;
+    let notifier = client.registry.get_idle_notifier().await?;
+    let notification = notifier.get_idle_notification(60_000, seat.seat.id)?;
+    client.sync().await;
+    tassert!(!notification.idled.get());
+
+    run.state.wheel.fast_forward(30_000);
+    client.sync().await;
+    tassert!(!notification.idled.get());
+
+    run.state.wheel.fast_forward(60_000);
+    client.sync().await;
+    tassert!(notification.idled.get());
+    tassert!(!notification.resumed.get());
+
+    ds.kb.press(1);
+    client.sync().await;
+    tassert!(notification.resumed.get());
+
+    // Inhibitors prevent the notifications, even with a timeout of 0.
+    let window = client.create_window().await?;
+    window.map2().await?;
+    let inhibitor = client
+        .registry
+        .get_idle_inhibit_manager()
+        .await?
+        .create_inhibitor(&window.surface)?;
+    client.sync().await;
+    tassert_eq!(run.state.idle.inhibitors.len(), 1);
+    notification.idled.set(false);
+    let immediate = notifier.get_idle_notification(0, seat.seat.id)?;
+    run.state.wheel.fast_forward(60_000);
+    client.sync().await;
+    tassert!(!notification.idled.get());
+    tassert!(!immediate.idled.get());
+
+    // The timeout starts over once the inhibitor is gone.
+    inhibitor.destroy()?;
+    client.sync().await;
+    tassert!(immediate.idled.get());
+    tassert!(!notification.idled.get());
+
+    run.state.wheel.fast_forward(60_000);
+    client.sync().await;
+    tassert!(notification.idled.get());
+
+    Ok(())
+}
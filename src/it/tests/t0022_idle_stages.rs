@@ -0,0 +1,33 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    std::{rc::Rc, time::Duration},
+};
+
+testcase!();
+
+/// Test that idle stages are entered after their timeout and resumed on input
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+
+    let dim = run.cfg.get_idle_stage("dim")?;
+    let lock = run.cfg.get_idle_stage("lock")?;
+    tassert_eq!(run.cfg.get_idle_stage("dim")?, dim);
+    tassert!(dim != lock);
+    run.cfg
+        .set_idle_stage_timeout(dim, Duration::from_millis(10))?;
+    run.cfg
+        .set_idle_stage_timeout(lock, Duration::from_secs(60))?;
+    run.sync().await;
+
+    run.state.wheel.timeout(50).await?;
+    run.sync().await;
+    tassert!(run.cfg.entered_idle_stages.contains(&dim));
+    tassert!(!run.cfg.entered_idle_stages.contains(&lock));
+
+    ds.kb.press(1);
+    run.sync().await;
+    tassert!(run.cfg.resumed_idle_stages.contains(&dim));
+    tassert!(!run.cfg.resumed_idle_stages.contains(&lock));
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    jay_config::{
+        keyboard::{
+            mods::{Modifiers, SHIFT},
+            syms::{SYM_F13, SYM_F14},
+        },
+        Direction,
+    },
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test switching keyboard groups and remembering the group per window
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+
+    let keymap = r#"
+xkb_keymap {
+    xkb_keycodes {
+          <1> = 9; # ESC
+          <2> = 10; # 1
+    };
+    xkb_types {
+    };
+    xkb_compatibility {
+        interpret Shift_L { action = SetMods(modifiers = Shift); };
+    };
+    xkb_symbols {
+        key <1> { symbols[Group1] = [ F13 ], symbols[Group2] = [ F14 ] };
+        key <2> { [ Shift_L ] };
+        modifier_map Shift { <2> };
+    };
+};
+    "#;
+
+    let keymap = run.cfg.parse_keymap(keymap)?;
+    run.cfg.set_keymap(seat, keymap)?;
+    run.cfg.add_shortcut(seat, SYM_F13)?;
+    run.cfg.add_shortcut(seat, SYM_F14)?;
+    run.sync().await;
+
+    tassert_eq!(run.cfg.get_keyboard_group(seat)?, (0, 2));
+
+    run.cfg.set_keyboard_group(seat, 1)?;
+    tassert_eq!(run.cfg.keyboard_groups.get(&seat), Some(1));
+    tassert_eq!(run.cfg.get_keyboard_group(seat)?, (1, 2));
+
+    ds.kb.press(1);
+    run.sync().await;
+    tassert!(run.cfg.invoked_shortcuts.contains(&(seat, SYM_F14.into())));
+    tassert!(!run.cfg.invoked_shortcuts.contains(&(seat, SYM_F13.into())));
+
+    run.cfg.next_keyboard_group(seat)?;
+    tassert_eq!(run.cfg.keyboard_groups.get(&seat), Some(0));
+
+    run.cfg.set_remember_keyboard_group(seat, true)?;
+
+    let client = run.create_client().await?;
+    let window1 = client.create_window().await?;
+    window1.map().await?;
+    let window2 = client.create_window().await?;
+    window2.map().await?;
+
+    run.cfg.set_keyboard_group(seat, 1)?;
+    tassert_eq!(run.cfg.get_keyboard_group(seat)?.0, 1);
+
+    run.cfg.focus(seat, Direction::Left)?;
+    client.sync().await;
+    tassert_eq!(run.cfg.get_keyboard_group(seat)?.0, 0);
+    tassert_eq!(run.cfg.keyboard_groups.get(&seat), Some(0));
+
+    run.cfg.focus(seat, Direction::Right)?;
+    client.sync().await;
+    tassert_eq!(run.cfg.get_keyboard_group(seat)?.0, 1);
+    tassert_eq!(run.cfg.keyboard_groups.get(&seat), Some(1));
+
+    // Switching the group does not affect held modifiers.
+    let shift = ds.kb.press(2);
+    run.sync().await;
+    tassert_eq!(ds.seat.binding_mods(), SHIFT);
+    run.cfg.set_keyboard_group(seat, 0)?;
+    tassert_eq!(ds.seat.binding_mods(), SHIFT);
+    drop(shift);
+    run.sync().await;
+    tassert_eq!(ds.seat.binding_mods(), Modifiers(0));
+    tassert_eq!(run.cfg.get_keyboard_group(seat)?.0, 0);
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    jay_config::keyboard::{
+        syms::{SYM_F13, SYM_F14},
+        Keymap,
+    },
+    std::rc::Rc,
+};
+
+testcase!();
+
+const KEYMAP: &str = r#"
+xkb_keymap {
+    xkb_keycodes {
+          <1> = 9; # ESC
+    };
+    xkb_types {
+    };
+    xkb_compatibility {
+    };
+    xkb_symbols {
+        key <1> { [ $SYM ] };
+    };
+};
+"#;
+
+/// Test that device keymaps override the seat keymap
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+
+    let seat_keymap = run.cfg.parse_keymap(&KEYMAP.replace("$SYM", "F13"))?;
+    let device_keymap = run.cfg.parse_keymap(&KEYMAP.replace("$SYM", "F14"))?;
+    run.cfg.set_keymap(seat, seat_keymap)?;
+    run.cfg.add_shortcut(seat, SYM_F13)?;
+    run.cfg.add_shortcut(seat, SYM_F14)?;
+    run.sync().await;
+
+    run.cfg.set_device_keymap(ds.kb.common.id, device_keymap)?;
+    ds.kb.press(1);
+    run.sync().await;
+    tassert!(run.cfg.invoked_shortcuts.contains(&(seat, SYM_F14.into())));
+    tassert!(!run.cfg.invoked_shortcuts.contains(&(seat, SYM_F13.into())));
+
+    run.cfg
+        .set_device_keymap(ds.kb.common.id, Keymap::INVALID)?;
+    ds.kb.press(1);
+    run.sync().await;
+    tassert!(run.cfg.invoked_shortcuts.contains(&(seat, SYM_F13.into())));
+
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    jay_config::keyboard::{
+        syms::{KeySym, SYM_F13, SYM_F14, SYM_F15, SYM_F16},
+        BindingMode, KeyBinding,
+    },
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test binding modes, release bindings, and key sequences
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+
+    let keymap = r#"
+xkb_keymap {
+    xkb_keycodes {
+          <1> = 9;
+          <2> = 10;
+          <3> = 11;
+          <4> = 12;
+    };
+    xkb_types {
+    };
+    xkb_compatibility {
+    };
+    xkb_symbols {
+        key <1> { [ F13 ] };
+        key <2> { [ F14 ] };
+        key <3> { [ F15 ] };
+        key <4> { [ F16 ] };
+    };
+};
+    "#;
+    let keymap = run.cfg.parse_keymap(keymap)?;
+    run.cfg.set_keymap(seat, keymap)?;
+
+    let is_invoked = |sym: KeySym| run.cfg.invoked_shortcuts.contains(&(seat, sym.into()));
+
+    // Binding modes
+    let mode = BindingMode(1);
+    run.cfg.add_shortcut(seat, SYM_F13)?;
+    run.cfg
+        .add_binding(seat, KeyBinding::from(SYM_F14).in_mode(mode))?;
+    ds.kb.press(2);
+    run.sync().await;
+    tassert!(!is_invoked(SYM_F14));
+    run.cfg.set_binding_mode(seat, mode)?;
+    ds.kb.press(2);
+    run.sync().await;
+    tassert!(is_invoked(SYM_F14));
+    ds.kb.press(1);
+    run.sync().await;
+    tassert!(!is_invoked(SYM_F13));
+    run.cfg.set_binding_mode(seat, BindingMode::DEFAULT)?;
+    ds.kb.press(1);
+    run.sync().await;
+    tassert!(is_invoked(SYM_F13));
+    run.cfg.invoked_shortcuts.clear();
+
+    // Release bindings
+    run.cfg
+        .add_binding(seat, KeyBinding::from(SYM_F15).on_release())?;
+    {
+        let _key = ds.kb.press(3);
+        run.sync().await;
+        tassert!(!is_invoked(SYM_F15));
+    }
+    run.sync().await;
+    tassert!(is_invoked(SYM_F15));
+    run.cfg.invoked_shortcuts.clear();
+    {
+        let _key = ds.kb.press(3);
+        ds.kb.press(4);
+    }
+    run.sync().await;
+    tassert!(!is_invoked(SYM_F15));
+
+    // Key sequences
+    run.cfg
+        .add_binding(seat, KeyBinding::from(SYM_F16).after(&[SYM_F13.into()]))?;
+    ds.kb.press(4);
+    run.sync().await;
+    tassert!(!is_invoked(SYM_F16));
+    ds.kb.press(1);
+    run.sync().await;
+    tassert!(!is_invoked(SYM_F13));
+    ds.kb.press(4);
+    run.sync().await;
+    tassert!(is_invoked(SYM_F16));
+    run.cfg.invoked_shortcuts.clear();
+    ds.kb.press(1);
+    run.sync().await;
+    ds.kb.press(2);
+    run.sync().await;
+    ds.kb.press(4);
+    run.sync().await;
+    tassert!(!is_invoked(SYM_F16));
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use {
+    crate::{
+        ifs::wl_seat::wl_pointer::HORIZONTAL_SCROLL,
+        it::{test_error::TestResult, testrun::TestRun},
+    },
+    jay_config::{
+        input::{
+            button::{BTN_LEFT, BTN_MIDDLE},
+            ScrollDirection,
+        },
+        keyboard::mods::Modifiers,
+        Axis,
+    },
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test button and scroll bindings
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+    let mods = Modifiers(0);
+
+    run.cfg.add_button_binding(seat, mods, BTN_MIDDLE)?;
+    run.cfg
+        .add_scroll_binding(seat, mods, Axis::Vertical, ScrollDirection::Positive)?;
+
+    ds.mouse.click(BTN_LEFT.0);
+    ds.mouse.click(BTN_MIDDLE.0);
+    run.sync().await;
+    tassert_eq!(
+        run.cfg
+            .invoked_button_bindings
+            .get(&(seat, mods, BTN_MIDDLE)),
+        Some(1)
+    );
+    tassert!(!run
+        .cfg
+        .invoked_button_bindings
+        .contains(&(seat, mods, BTN_LEFT)));
+
+    let down = (seat, mods, Axis::Vertical, ScrollDirection::Positive);
+    let up = (seat, mods, Axis::Vertical, ScrollDirection::Negative);
+    ds.mouse.scroll(2);
+    ds.mouse.scroll(-1);
+    ds.mouse.scroll_px(10);
+    run.sync().await;
+    tassert_eq!(run.cfg.invoked_scroll_bindings.get(&down), Some(2));
+    tassert!(!run.cfg.invoked_scroll_bindings.contains(&up));
+
+    // Only the axis that matched a binding is consumed.
+    let client = run.create_client().await?;
+    let dss = client.get_default_seat().await?;
+    let win = client.create_window().await?;
+    win.map2().await?;
+    ds.mouse.abs(&ds.connector, 100.0, 100.0);
+    client.sync().await;
+    let axes = dss.pointer.axis.expect()?;
+    ds.mouse.scroll2(1, 1);
+    client.sync().await;
+    tassert_eq!(run.cfg.invoked_scroll_bindings.get(&down), Some(3));
+    let axis = axes.next()?;
+    tassert_eq!(axis.axis, HORIZONTAL_SCROLL);
+    axes.none()?;
+
+    Ok(())
+}
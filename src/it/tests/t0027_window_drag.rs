@@ -0,0 +1,37 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    jay_config::{input::button::BTN_RIGHT, keyboard::mods::LOGO},
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test resizing tiled windows with modifier+drag
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    run.cfg.set_window_drag_modifier(ds.seat.id(), LOGO)?;
+
+    let client = run.create_client().await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+    let w2 = client.create_window().await?;
+    w2.map2().await?;
+
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get(), w2.tl.width.get());
+
+    // Start in the right half of w1 so that its right edge is moved.
+    let x = (w1.tl.width.get() * 3 / 4) as f64;
+    ds.mouse.abs(&ds.connector, x, 500.0);
+    {
+        let _logo = ds.kb.press(125);
+        let _click = ds.mouse.click(BTN_RIGHT.0);
+        run.sync().await;
+        ds.mouse.abs(&ds.connector, x + 100.0, 500.0);
+        run.sync().await;
+    }
+    client.sync().await;
+    tassert!(w1.tl.width.get() >= w2.tl.width.get() + 150);
+
+    Ok(())
+}
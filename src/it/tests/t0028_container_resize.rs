@@ -0,0 +1,48 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    jay_config::Direction,
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test resizing and equalizing tiled windows with the keyboard
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+
+    let client = run.create_client().await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+    let w2 = client.create_window().await?;
+    w2.map2().await?;
+
+    client.sync().await;
+    let width = w1.tl.width.get();
+    tassert_eq!(w2.tl.width.get(), width);
+
+    // w2 is focused. Growing it to the left takes the space from w1.
+    run.cfg.resize(seat, Direction::Left, 100)?;
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get(), width - 100);
+    tassert_eq!(w2.tl.width.get(), width + 100);
+
+    // w2 has no neighbor to the right. The left edge is moved instead.
+    run.cfg.resize(seat, Direction::Right, 50)?;
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get(), width - 150);
+    tassert_eq!(w2.tl.width.get(), width + 150);
+
+    // Shrinking gives the space back.
+    run.cfg.resize(seat, Direction::Left, -50)?;
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get(), width - 100);
+    tassert_eq!(w2.tl.width.get(), width + 100);
+
+    run.cfg.equalize(seat)?;
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get(), width);
+    tassert_eq!(w2.tl.width.get(), width);
+
+    Ok(())
+}
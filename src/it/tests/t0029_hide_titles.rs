@@ -0,0 +1,39 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test hiding the title bars of single-child and mono containers
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+
+    run.cfg.set_hide_titles(true)?;
+
+    let th = run.state.theme.sizes.title_height.get() + 1;
+    let height = 600 - th;
+
+    let client = run.create_client().await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+    client.sync().await;
+    tassert_eq!(w1.tl.height.get(), height);
+
+    let w2 = client.create_window().await?;
+    w2.map2().await?;
+    client.sync().await;
+    tassert_eq!(w1.tl.height.get(), height - th);
+    tassert_eq!(w2.tl.height.get(), height - th);
+
+    run.cfg.set_mono(seat, true)?;
+    client.sync().await;
+    tassert_eq!(w2.tl.height.get(), height);
+
+    run.cfg.seat_set_hide_titles(seat, false)?;
+    client.sync().await;
+    tassert_eq!(w2.tl.height.get(), height - th);
+
+    Ok(())
+}
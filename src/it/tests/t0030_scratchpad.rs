@@ -0,0 +1,41 @@
+use {
+    crate::{
+        it::{test_error::TestResult, testrun::TestRun},
+        tree::{Node, ToplevelNode},
+    },
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test moving windows to the scratchpad and toggling them into view
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+
+    let client = run.create_client().await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+    let w2 = client.create_window().await?;
+    w2.map2().await?;
+
+    run.cfg.move_to_scratchpad(seat)?;
+    client.sync().await;
+    tassert!(!w2.tl.server.node_visible());
+    tassert!(w2.tl.server.tl_data().in_scratchpad.get());
+
+    run.cfg.toggle_scratchpad(seat, None)?;
+    client.sync().await;
+    tassert!(w2.tl.server.node_visible());
+    tassert!(w2.tl.server.tl_data().is_floating.get());
+
+    run.cfg.toggle_scratchpad(seat, None)?;
+    client.sync().await;
+    tassert!(!w2.tl.server.node_visible());
+
+    run.cfg.toggle_scratchpad(seat, Some("does not match"))?;
+    client.sync().await;
+    tassert!(!w2.tl.server.node_visible());
+
+    Ok(())
+}
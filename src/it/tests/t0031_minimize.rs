@@ -0,0 +1,45 @@
+use {
+    crate::{
+        it::{test_error::TestResult, testrun::TestRun},
+        tree::{Node, ToplevelNode},
+    },
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test minimizing and restoring windows
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+
+    let client = run.create_client().await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+    let w2 = client.create_window().await?;
+    w2.map2().await?;
+
+    run.cfg.minimize(seat)?;
+    client.sync().await;
+    tassert!(!w2.tl.server.node_visible());
+    tassert!(w2.tl.server.tl_data().is_minimized());
+    tassert_eq!(w1.tl.width.get(), 800);
+
+    w1.tl.set_minimized()?;
+    client.sync().await;
+    tassert!(!w1.tl.server.node_visible());
+    tassert!(w1.tl.server.tl_data().is_minimized());
+
+    run.cfg.restore_last_minimized(seat)?;
+    client.sync().await;
+    tassert!(w1.tl.server.node_visible());
+    tassert!(!w1.tl.server.tl_data().is_minimized());
+    tassert!(w2.tl.server.tl_data().is_minimized());
+
+    run.cfg.restore_last_minimized(seat)?;
+    client.sync().await;
+    tassert!(w2.tl.server.node_visible());
+    tassert_eq!(w1.tl.width.get(), w2.tl.width.get());
+
+    Ok(())
+}
@@ -0,0 +1,40 @@
+use {
+    crate::{
+        it::{test_error::TestResult, testrun::TestRun},
+        tree::ToplevelNode,
+    },
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test maximizing and unmaximizing floating windows
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+
+    let client = run.create_client().await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+
+    run.cfg.set_floating(seat, true)?;
+    client.sync().await;
+    let width = w1.tl.width.get();
+    let height = w1.tl.height.get();
+
+    w1.tl.set_maximized()?;
+    client.sync().await;
+    let bw = run.state.theme.sizes.border_width.get();
+    tassert!(w1.tl.server.tl_data().is_maximized.get());
+    tassert!(w1.tl.states.borrow().contains(&1));
+    tassert_eq!(w1.tl.width.get(), 800 - 2 * bw);
+
+    w1.tl.unset_maximized()?;
+    client.sync().await;
+    tassert!(!w1.tl.server.tl_data().is_maximized.get());
+    tassert!(!w1.tl.states.borrow().contains(&1));
+    tassert_eq!(w1.tl.width.get(), width);
+    tassert_eq!(w1.tl.height.get(), height);
+
+    Ok(())
+}
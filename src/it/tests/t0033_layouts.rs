@@ -0,0 +1,59 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    jay_config::{ContainerLayout, Direction},
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test the master-stack, spiral, and columns layouts
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+
+    let client = run.create_client().await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+    let w2 = client.create_window().await?;
+    w2.map2().await?;
+    let w3 = client.create_window().await?;
+    w3.map2().await?;
+
+    let ta = run.state.theme.sizes.title_height.get() + 1;
+    let bw = run.state.theme.sizes.border_width.get();
+    let height = 600 - ta - ta;
+
+    for layout in [ContainerLayout::MasterStack, ContainerLayout::Spiral] {
+        run.cfg.set_layout(seat, layout)?;
+        client.sync().await;
+        tassert_eq!(w1.tl.height.get(), height);
+        tassert_eq!(w1.tl.width.get() + bw + w2.tl.width.get(), 800);
+        tassert_eq!(w2.tl.width.get(), w3.tl.width.get());
+        tassert_eq!(w2.tl.height.get() + bw + ta + w3.tl.height.get(), height);
+    }
+
+    run.cfg.set_layout(seat, ContainerLayout::Columns)?;
+    client.sync().await;
+    let widths = [w1.tl.width.get(), w2.tl.width.get(), w3.tl.width.get()];
+    tassert_eq!(widths.iter().sum::<i32>() + 2 * bw, 800);
+    tassert!(widths.iter().max().unwrap() - widths.iter().min().unwrap() <= 1);
+    for w in [&w1, &w2, &w3] {
+        tassert_eq!(w.tl.height.get(), height);
+    }
+
+    let width = w1.tl.width.get();
+    run.cfg.resize(seat, Direction::Right, 50)?;
+    run.cfg.equalize(seat)?;
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get(), width);
+
+    run.cfg.set_layout(seat, ContainerLayout::Split)?;
+    client.sync().await;
+    tassert_eq!(w1.tl.height.get(), height);
+    tassert_eq!(w3.tl.height.get(), height);
+    let widths = [w1.tl.width.get(), w2.tl.width.get(), w3.tl.width.get()];
+    tassert_eq!(widths.iter().sum::<i32>() + 2 * bw, 800);
+    tassert!(widths.iter().max().unwrap() - widths.iter().min().unwrap() <= 1);
+
+    Ok(())
+}
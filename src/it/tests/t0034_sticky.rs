@@ -0,0 +1,41 @@
+use {
+    crate::{
+        it::{test_error::TestResult, testrun::TestRun},
+        tree::{Node, ToplevelNode},
+    },
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test that sticky windows follow workspace switches
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+    run.cfg.show_workspace(seat, "1")?;
+
+    let client = run.create_client().await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+    let w2 = client.create_window().await?;
+    w2.map2().await?;
+
+    run.cfg.set_floating(seat, true)?;
+    run.cfg.toggle_sticky(seat)?;
+    client.sync().await;
+
+    run.cfg.show_workspace(seat, "2")?;
+    client.sync().await;
+    tassert!(w2.tl.server.node_visible());
+    tassert!(!w1.tl.server.node_visible());
+    let ws = w2.tl.server.tl_data().workspace.get();
+    tassert_eq!(ws.map(|ws| ws.name.clone()), Some("2".to_string()));
+
+    run.cfg.toggle_sticky(seat)?;
+    run.cfg.show_workspace(seat, "1")?;
+    client.sync().await;
+    tassert!(!w2.tl.server.node_visible());
+    tassert!(w1.tl.server.node_visible());
+
+    Ok(())
+}
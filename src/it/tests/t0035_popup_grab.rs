@@ -0,0 +1,122 @@
+use {
+    crate::{
+        ifs::wl_seat::BTN_LEFT,
+        it::{
+            test_client::{DefaultSeat, TestClient},
+            test_error::{TestErrorExt, TestResult},
+            test_ifs::{
+                test_shm_buffer::TestShmBuffer, test_surface::TestSurface,
+                test_xdg_popup::TestXdgPopup, test_xdg_surface::TestXdgSurface,
+            },
+            testrun::TestRun,
+        },
+        tree::Node,
+    },
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test that grabbing popups receive keyboard focus and are dismissed by outside input
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+
+    let client = run.create_client().await?;
+    let dss = client.get_default_seat().await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+
+    let client2 = run.create_client().await?;
+    let dss2 = client2.get_default_seat().await?;
+    let w2 = client2.create_window().await?;
+    w2.map2().await?;
+
+    ds.mouse.abs(&ds.connector, 600.0, 100.0);
+    client.sync().await;
+    let ptr_enters = dss.pointer.enter.expect()?;
+    ds.mouse.abs(&ds.connector, 100.0, 100.0);
+    client.sync().await;
+    let serial = ptr_enters.next().with_context(|| "pointer enter")?.serial;
+
+    // Grabs with an invalid serial are denied.
+    let p0 = Popup::map(&client, &dss, &w1.xdg, serial.wrapping_add(1000)).await?;
+    tassert!(p0.popup.done.get());
+    drop(p0);
+
+    // Clicks and motion outside of the popup do not dismiss it as long as they stay
+    // within the client. Other clients do not receive pointer events.
+    let kb_enters = dss.kb.enter.expect()?;
+    let p1 = Popup::map(&client, &dss, &w1.xdg, serial).await?;
+    let enter = kb_enters.next().with_context(|| "popup enter")?;
+    tassert_eq!(enter.surface, p1.surface.id);
+    tassert!(p1.popup.server.node_visible());
+    let ptr_enters2 = dss2.pointer.enter.expect()?;
+    ds.mouse.click(BTN_LEFT);
+    ds.mouse.abs(&ds.connector, 600.0, 100.0);
+    client.sync().await;
+    client2.sync().await;
+    tassert!(!p1.popup.done.get());
+    ptr_enters2.none()?;
+
+    // Clicking on a surface of another client dismisses the popup.
+    ds.mouse.click(BTN_LEFT);
+    client.sync().await;
+    tassert!(p1.popup.done.get());
+    drop(p1);
+
+    // Nested popups must be children of the topmost grabbing popup.
+    ds.mouse.abs(&ds.connector, 100.0, 100.0);
+    client.sync().await;
+    let p2 = Popup::map(&client, &dss, &w1.xdg, serial).await?;
+    let p3 = Popup::map(&client, &dss, &p2.xdg, serial).await?;
+    let p4 = Popup::map(&client, &dss, &p2.xdg, serial).await?;
+    tassert!(!p2.popup.done.get());
+    tassert!(!p3.popup.done.get());
+    tassert!(p4.popup.done.get());
+
+    // Moving the keyboard focus away from the client dismisses all popups.
+    run.cfg.show_workspace(seat, "2")?;
+    client.sync().await;
+    tassert!(p2.popup.done.get());
+    tassert!(p3.popup.done.get());
+
+    Ok(())
+}
+
+struct Popup {
+    popup: Rc<TestXdgPopup>,
+    xdg: Rc<TestXdgSurface>,
+    surface: Rc<TestSurface>,
+    _buffer: Rc<TestShmBuffer>,
+}
+
+impl Popup {
+    async fn map(
+        client: &TestClient,
+        seat: &DefaultSeat,
+        parent: &TestXdgSurface,
+        serial: u32,
+    ) -> TestResult<Self> {
+        let surface = client.comp.create_surface().await?;
+        let xdg = client.xdg.create_xdg_surface(surface.id).await?;
+        let pos = client.xdg.create_positioner()?;
+        pos.set_size(50, 50)?;
+        pos.set_anchor_rect(0, 0, 1, 1)?;
+        let popup = xdg.create_popup(parent, &pos).await?;
+        popup.grab(seat.seat.id, serial)?;
+        surface.commit()?;
+        client.sync().await;
+        let buffer = client.shm.create_buffer(50, 50)?;
+        surface.attach(buffer.id)?;
+        xdg.ack_configure(xdg.last_serial.get())?;
+        surface.commit()?;
+        client.sync().await;
+        Ok(Self {
+            popup,
+            xdg,
+            surface,
+            _buffer: buffer,
+        })
+    }
+}
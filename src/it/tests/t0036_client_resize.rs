@@ -0,0 +1,73 @@
+use {
+    crate::{
+        ifs::wl_seat::BTN_LEFT,
+        it::{test_error::TestResult, testrun::TestRun},
+        tree::Node,
+    },
+    std::rc::Rc,
+};
+
+testcase!();
+
+const EDGE_RIGHT: u32 = 8;
+const EDGE_BOTTOM_RIGHT: u32 = 10;
+
+/// Test client-initiated interactive resizes of tiled and floating windows
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+
+    let client = run.create_client().await?;
+    let dss = client.get_default_seat().await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+    let w2 = client.create_window().await?;
+    w2.map2().await?;
+    tassert_eq!(w1.tl.width.get(), w2.tl.width.get());
+
+    ds.mouse.abs(&ds.connector, 200.0, 500.0);
+    client.sync().await;
+    {
+        let _click = ds.mouse.click(BTN_LEFT);
+        client.sync().await;
+        w1.tl.resize(dss.seat.id, 0, EDGE_RIGHT)?;
+        client.sync().await;
+        ds.mouse.abs(&ds.connector, 300.0, 500.0);
+        run.sync().await;
+    }
+    w1.map().await?;
+    client.sync().await;
+    tassert!(w1.tl.width.get() >= w2.tl.width.get() + 150);
+
+    run.cfg.set_floating(seat, true)?;
+    client.sync().await;
+    w1.map().await?;
+    client.sync().await;
+    let width = w1.tl.width.get();
+    let height = w1.tl.height.get();
+    let pos = w1.tl.server.node_absolute_position();
+
+    ds.mouse.abs(
+        &ds.connector,
+        (pos.x1() + 10) as f64,
+        (pos.y1() + 10) as f64,
+    );
+    client.sync().await;
+    {
+        let _click = ds.mouse.click(BTN_LEFT);
+        client.sync().await;
+        w1.tl.resize(dss.seat.id, 0, EDGE_BOTTOM_RIGHT)?;
+        client.sync().await;
+        ds.mouse.abs(
+            &ds.connector,
+            (pos.x1() + 60) as f64,
+            (pos.y1() + 40) as f64,
+        );
+        run.sync().await;
+    }
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get(), width + 50);
+    tassert_eq!(w1.tl.height.get(), height + 30);
+
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use {
+    crate::{
+        ifs::wl_seat::BTN_LEFT,
+        it::{test_error::TestResult, testrun::TestRun},
+        tree::ToplevelNode,
+    },
+    std::rc::Rc,
+};
+
+testcase!();
+
+const KEY_ESC: u32 = 1;
+const KEY_ENTER: u32 = 28;
+const KEY_DOWN: u32 = 108;
+
+/// Test the compositor-drawn window menu
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+
+    let client = run.create_client().await?;
+    let dss = client.get_default_seat().await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+
+    // Escape closes the menu without doing anything.
+    w1.tl.show_window_menu(dss.seat.id, 0, 10, 10)?;
+    client.sync().await;
+    tassert!(ds.seat.window_menu().is_some());
+    ds.kb.press(KEY_ESC);
+    client.sync().await;
+    tassert!(ds.seat.window_menu().is_none());
+    tassert!(!w1.tl.server.tl_data().is_floating.get());
+
+    // The first entry toggles floating.
+    w1.tl.show_window_menu(dss.seat.id, 0, 10, 10)?;
+    client.sync().await;
+    ds.kb.press(KEY_DOWN);
+    ds.kb.press(KEY_ENTER);
+    client.sync().await;
+    tassert!(ds.seat.window_menu().is_none());
+    tassert!(w1.tl.server.tl_data().is_floating.get());
+
+    // Clicking the last entry closes the window.
+    w1.tl.show_window_menu(dss.seat.id, 0, 10, 10)?;
+    client.sync().await;
+    let menu = match ds.seat.window_menu() {
+        Some(m) => m,
+        _ => bail!("window menu is not shown"),
+    };
+    let pos = menu.position.get();
+    let last = menu.entries.len() as i32 - 1;
+    let y = pos.y1() + menu.border_width + last * menu.row_height + menu.row_height / 2;
+    ds.mouse
+        .abs(&ds.connector, (pos.x1() + pos.width() / 2) as f64, y as f64);
+    client.sync().await;
+    ds.mouse.click(BTN_LEFT);
+    client.sync().await;
+    tassert!(ds.seat.window_menu().is_none());
+    tassert!(w1.tl.close_requested.get());
+
+    // Clicking outside of the menu dismisses it.
+    w1.tl.show_window_menu(dss.seat.id, 0, 10, 10)?;
+    client.sync().await;
+    ds.mouse.abs(&ds.connector, 1.0, 1.0);
+    client.sync().await;
+    ds.mouse.click(BTN_LEFT);
+    client.sync().await;
+    tassert!(ds.seat.window_menu().is_none());
+
+    // Destroying the toplevel closes its menu.
+    w1.tl.show_window_menu(dss.seat.id, 0, 10, 10)?;
+    client.sync().await;
+    tassert!(ds.seat.window_menu().is_some());
+    w1.tl.destroy()?;
+    client.sync().await;
+    tassert!(ds.seat.window_menu().is_none());
+
+    Ok(())
+}
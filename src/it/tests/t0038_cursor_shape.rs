@@ -0,0 +1,53 @@
+use {
+    crate::{
+        cursor::KnownCursor,
+        it::{
+            test_error::{TestErrorExt, TestResult},
+            testrun::TestRun,
+        },
+    },
+    std::rc::Rc,
+};
+
+testcase!();
+
+const SHAPE_TEXT: u32 = 9;
+const SHAPE_NWSE_RESIZE: u32 = 29;
+
+/// Test that clients can set the cursor via wp_cursor_shape_manager_v1
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+
+    let client = run.create_client().await?;
+    let dss = client.get_default_seat().await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+
+    let manager = client.registry.get_cursor_shape_manager().await?;
+    let device = manager.get_pointer(&dss.pointer)?;
+
+    ds.mouse.abs(&ds.connector, 1.0, 1.0);
+    client.sync().await;
+    let enters = dss.pointer.enter.expect()?;
+    ds.mouse.abs(&ds.connector, 100.0, 100.0);
+    client.sync().await;
+    let enter = enters.next().with_context(|| "pointer enter")?;
+
+    device.set_shape(enter.serial, SHAPE_TEXT)?;
+    client.sync().await;
+    tassert_eq!(ds.seat.desired_known_cursor(), Some(KnownCursor::Text));
+
+    // Requests with an outdated serial are ignored.
+    device.set_shape(enter.serial.wrapping_sub(1), SHAPE_NWSE_RESIZE)?;
+    client.sync().await;
+    tassert_eq!(ds.seat.desired_known_cursor(), Some(KnownCursor::Text));
+
+    device.set_shape(enter.serial, SHAPE_NWSE_RESIZE)?;
+    client.sync().await;
+    tassert_eq!(
+        ds.seat.desired_known_cursor(),
+        Some(KnownCursor::ResizeTopLeftBottomRight)
+    );
+
+    Ok(())
+}
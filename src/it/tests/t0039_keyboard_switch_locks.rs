@@ -0,0 +1,82 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    jay_config::keyboard::syms::{SYM_F13, SYM_F14},
+    std::rc::Rc,
+};
+
+testcase!();
+
+const KEYMAP: &str = r#"
+xkb_keymap {
+    xkb_keycodes {
+          <1> = 9; # ESC
+          <2> = 10; # 1
+    };
+    xkb_types {
+    };
+    xkb_compatibility {
+        interpret Caps_Lock { action = LockMods(modifiers = Lock); };
+    };
+    xkb_symbols {
+        key <1> { symbols[Group1] = [ $SYM ], symbols[Group2] = [ $SYM ] };
+        key <2> { [ Caps_Lock ] };
+        modifier_map Lock { <2> };
+    };
+};
+"#;
+
+const LOCK: u32 = 1 << 1;
+
+/// Test that locked modifiers and the group survive switching between keyboards with
+/// different keymaps
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let ds = run.create_default_setup().await?;
+    let seat = ds.seat.id();
+
+    let kb2 = run.backend.add_keyboard("second-keyboard");
+    run.state.eng.yield_now().await;
+    run.cfg.set_input_device_seat(kb2.common.id, seat)?;
+
+    let seat_keymap = run.cfg.parse_keymap(&KEYMAP.replace("$SYM", "F13"))?;
+    let device_keymap = run.cfg.parse_keymap(&KEYMAP.replace("$SYM", "F14"))?;
+    run.cfg.set_keymap(seat, seat_keymap)?;
+    run.cfg.set_device_keymap(kb2.common.id, device_keymap)?;
+    run.cfg.add_shortcut(seat, SYM_F13)?;
+    run.cfg.add_shortcut(seat, SYM_F14)?;
+
+    let client = run.create_client().await?;
+    let dss = client.get_default_seat().await?;
+    let win = client.create_window().await?;
+    win.map2().await?;
+
+    let mods = dss.kb.modifiers.expect()?;
+    ds.kb.press(2);
+    client.sync().await;
+    tassert_eq!(mods.last()?.mods_locked, LOCK);
+
+    run.cfg.set_keyboard_group(seat, 1)?;
+    tassert_eq!(run.cfg.keyboard_groups.get(&seat), Some(1));
+
+    kb2.press(1);
+    client.sync().await;
+    tassert!(run.cfg.invoked_shortcuts.contains(&(seat, SYM_F14.into())));
+    let m = mods.last()?;
+    tassert_eq!(m.mods_locked, LOCK);
+    tassert_eq!(m.group, 1);
+    tassert_eq!(run.cfg.get_keyboard_group(seat)?.0, 1);
+    tassert_eq!(run.cfg.keyboard_groups.get(&seat), Some(1));
+
+    // Caps Lock on the second keyboard unlocks the modifier locked on the first one.
+    kb2.press(2);
+    client.sync().await;
+    tassert_eq!(mods.last()?.mods_locked, 0);
+
+    ds.kb.press(1);
+    client.sync().await;
+    tassert!(run.cfg.invoked_shortcuts.contains(&(seat, SYM_F13.into())));
+    let m = mods.last()?;
+    tassert_eq!(m.mods_locked, 0);
+    tassert_eq!(m.group, 1);
+
+    Ok(())
+}
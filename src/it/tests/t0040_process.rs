@@ -0,0 +1,80 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    jay_config::exec::{ExitStatus, Stdio},
+    std::rc::Rc,
+    uapi::c,
+};
+
+testcase!(uses_forker: true);
+
+/// Test the exit status and the stdio of processes spawned by the config
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let cfg = &run.cfg;
+
+    let process = cfg.spawn(
+        "sh",
+        &["-c", "exit 3"],
+        Stdio::Null,
+        Stdio::Null,
+        Default::default(),
+    )?;
+    tassert_eq!(run.wait_for_exit(process).await?, ExitStatus::Exited(3));
+
+    let process = cfg.spawn(
+        "sh",
+        &["-c", "kill -9 $$"],
+        Stdio::Null,
+        Stdio::Null,
+        Default::default(),
+    )?;
+    tassert_eq!(run.wait_for_exit(process).await?, ExitStatus::Signaled(9));
+
+    let process = cfg.spawn(
+        "/nonexistent/program",
+        &[],
+        Stdio::Null,
+        Stdio::Null,
+        Default::default(),
+    )?;
+    tassert_eq!(run.wait_for_exit(process).await?, ExitStatus::SpawnFailed);
+
+    // Running processes report their pid and can be signaled.
+    let process = cfg.spawn(
+        "sleep",
+        &["10"],
+        Stdio::Null,
+        Stdio::Null,
+        Default::default(),
+    )?;
+    run.state.wheel.timeout(50).await?;
+    let (pid, alive) = cfg.get_process(process)?;
+    tassert!(alive);
+    tassert!(pid.is_some());
+    cfg.signal_process(process, c::SIGTERM)?;
+    tassert_eq!(
+        run.wait_for_exit(process).await?,
+        ExitStatus::Signaled(c::SIGTERM)
+    );
+    tassert_eq!(cfg.get_process(process)?, (None, false));
+
+    // Stdout and stderr are connected to the file descriptors.
+    let (stdout_read, stdout_write) = uapi::pipe2(c::O_CLOEXEC | c::O_NONBLOCK)?;
+    let (stderr_read, stderr_write) = uapi::pipe2(c::O_CLOEXEC | c::O_NONBLOCK)?;
+    let process = cfg.spawn(
+        "sh",
+        &["-c", "echo out; echo err >&2"],
+        Stdio::Fd(stdout_write.raw()),
+        Stdio::Fd(stderr_write.raw()),
+        Default::default(),
+    )?;
+    drop(stdout_write);
+    drop(stderr_write);
+    tassert_eq!(run.wait_for_exit(process).await?, ExitStatus::Exited(0));
+    let mut buf = [0; 16];
+    let n = uapi::read(stdout_read.raw(), &mut buf[..])?.len();
+    tassert_eq!(&buf[..n], b"out\n");
+    let n = uapi::read(stderr_read.raw(), &mut buf[..])?.len();
+    tassert_eq!(&buf[..n], b"err\n");
+
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    jay_config::theme::sized::{INNER_GAP, OUTER_GAP},
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test inner, outer, and smart gaps
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let _ds = run.create_default_setup().await?;
+
+    run.cfg.set_size(INNER_GAP, 10)?;
+    run.cfg.set_size(OUTER_GAP, 20)?;
+
+    let ta = run.state.theme.sizes.title_height.get() + 1;
+    let bw = run.state.theme.sizes.border_width.get();
+    let height = 600 - ta - ta;
+
+    let client = run.create_client().await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get(), 800 - 40);
+    tassert_eq!(w1.tl.height.get(), height - 40);
+
+    let w2 = client.create_window().await?;
+    w2.map2().await?;
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get() + bw + 10 + w2.tl.width.get(), 800 - 40);
+    tassert_eq!(w2.tl.height.get(), height - 40);
+
+    // Smart gaps only remove the outer gap if there is a single window.
+    run.cfg.set_smart_gaps(true)?;
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get() + bw + 10 + w2.tl.width.get(), 800 - 40);
+
+    w2.tl.destroy()?;
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get(), 800);
+    tassert_eq!(w1.tl.height.get(), height);
+
+    let w3 = client.create_window().await?;
+    w3.map2().await?;
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get() + bw + 10 + w3.tl.width.get(), 800 - 40);
+    tassert_eq!(w3.tl.height.get(), height - 40);
+
+    run.cfg.set_smart_gaps(false)?;
+    run.cfg.set_size(OUTER_GAP, 0)?;
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get() + bw + 10 + w3.tl.width.get(), 800);
+    tassert_eq!(w3.tl.height.get(), height);
+
+    run.cfg.set_size(INNER_GAP, 0)?;
+    client.sync().await;
+    tassert_eq!(w1.tl.width.get() + bw + w3.tl.width.get(), 800);
+
+    Ok(())
+}
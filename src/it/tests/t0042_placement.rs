@@ -0,0 +1,118 @@
+use {
+    crate::{
+        it::{
+            test_error::{TestError, TestResult},
+            test_utils::test_window::TestWindow,
+            testrun::TestRun,
+        },
+        tree::ToplevelNode,
+    },
+    jay_config::{
+        _private::ipc::Placement,
+        exec::{ExitStatus, Stdio},
+    },
+    std::rc::Rc,
+    uapi::c,
+};
+
+testcase!(uses_forker: true);
+
+/// Test that the placement of a spawned program is applied to the toplevel that
+/// presents its activation token
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let _ds = run.create_default_setup().await?;
+
+    let client = run.create_client().await?;
+    let activation = client.registry.get_activation().await?;
+
+    let token = activation.get_activation_token()?;
+    token.commit()?;
+    client.sync().await;
+    tassert!(token.token.get().is_some());
+
+    // The spawned processes have already exited when the token is presented.
+    let ws_token = spawn(
+        &run,
+        Placement {
+            workspace: Some(run.cfg.get_workspace("placed")?),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let w1 = client.create_window().await?;
+    w1.map2().await?;
+    tassert!(workspace(&w1).as_deref() != Some("placed"));
+    activation.activate(&ws_token, &w1.surface)?;
+    client.sync().await;
+    tassert_eq!(workspace(&w1), Some("placed".to_string()));
+
+    let float_token = spawn(
+        &run,
+        Placement {
+            floating: Some(true),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let w2 = client.create_window().await?;
+    w2.map2().await?;
+    tassert!(!w2.tl.server.tl_data().is_floating.get());
+    activation.activate(&float_token, &w2.surface)?;
+    client.sync().await;
+    tassert!(w2.tl.server.tl_data().is_floating.get());
+
+    // Tokens can only be used once.
+    let w3 = client.create_window().await?;
+    w3.map2().await?;
+    activation.activate(&ws_token, &w3.surface)?;
+    client.sync().await;
+    tassert!(workspace(&w3).as_deref() != Some("placed"));
+
+    // Tokens presented before the toplevel is mapped are applied when it is mapped.
+    let float_token = spawn(
+        &run,
+        Placement {
+            floating: Some(true),
+            ..Default::default()
+        },
+    )
+    .await?;
+    let w4 = client.create_window().await?;
+    activation.activate(&float_token, &w4.surface)?;
+    w4.map2().await?;
+    tassert!(w4.tl.server.tl_data().is_floating.get());
+
+    Ok(())
+}
+
+/// Spawns a program with the placement and returns the activation token passed to it.
+async fn spawn(run: &TestRun, placement: Placement) -> Result<String, TestError> {
+    let (read, write) = uapi::pipe2(c::O_CLOEXEC | c::O_NONBLOCK)?;
+    let process = run.cfg.spawn(
+        "sh",
+        &[
+            "-c",
+            "printf '%s %s' \"$XDG_ACTIVATION_TOKEN\" \"$DESKTOP_STARTUP_ID\"",
+        ],
+        Stdio::Fd(write.raw()),
+        Stdio::Null,
+        placement,
+    )?;
+    drop(write);
+    tassert_eq!(run.wait_for_exit(process).await?, ExitStatus::Exited(0));
+    let mut buf = [0; 64];
+    let n = uapi::read(read.raw(), &mut buf[..])?.len();
+    let output = String::from_utf8_lossy(&buf[..n]).into_owned();
+    let (token, startup_id) = match output.split_once(' ') {
+        Some(t) => t,
+        _ => bail!("Unexpected output {:?}", output),
+    };
+    tassert!(!token.is_empty());
+    tassert_eq!(token, startup_id);
+    Ok(token.to_string())
+}
+
+fn workspace(window: &TestWindow) -> Option<String> {
+    let ws = window.tl.server.tl_data().workspace.get()?;
+    Some(ws.name.clone())
+}
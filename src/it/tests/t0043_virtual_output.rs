@@ -0,0 +1,37 @@
+use {
+    crate::it::{test_error::TestResult, testrun::TestRun},
+    std::rc::Rc,
+};
+
+testcase!();
+
+/// Test creating and removing virtual outputs via the config
+async fn test(run: Rc<TestRun>) -> TestResult {
+    let _ds = run.create_default_setup().await?;
+
+    let id = run.cfg.create_virtual_output(1024, 768, 30_000)?;
+    run.sync().await;
+
+    let output = match run.state.outputs.get(&id) {
+        Some(o) => o,
+        _ => bail!("Virtual output was not created"),
+    };
+    tassert_eq!(output.monitor_info.initial_mode.width, 1024);
+    tassert_eq!(output.monitor_info.initial_mode.height, 768);
+    tassert_eq!(
+        output.monitor_info.initial_mode.refresh_rate_millihz,
+        30_000
+    );
+    let pos = output.node.global.pos.get();
+    tassert_eq!((pos.width(), pos.height()), (1024, 768));
+    tassert!(run.state.root.outputs.get(&id).is_some());
+
+    run.cfg.remove_virtual_output(id)?;
+    run.sync().await;
+
+    tassert!(run.state.outputs.get(&id).is_none());
+    tassert!(run.state.root.outputs.get(&id).is_none());
+    tassert!(run.state.connectors.get(&id).is_none());
+
+    Ok(())
+}
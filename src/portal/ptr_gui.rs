@@ -693,11 +693,14 @@ impl WindowData {
                 format: ARGB8888,
                 modifier: INVALID_MODIFIER,
             };
-            let bo = match ctx
-                .ctx
-                .gbm
-                .create_bo(width, height, &format, GBM_BO_USE_RENDERING)
-            {
+            let gbm = match ctx.ctx.gbm() {
+                Ok(gbm) => gbm,
+                Err(e) => {
+                    log::error!("Could not allocate dmabuf: {}", ErrorFmt(e));
+                    return;
+                }
+            };
+            let bo = match gbm.create_bo(width, height, &format, GBM_BO_USE_RENDERING) {
                 Ok(b) => b,
                 Err(e) => {
                     log::error!("Could not allocate dmabuf: {}", ErrorFmt(e));
@@ -24,6 +24,7 @@ use {
     thiserror::Error,
 };
 
+mod cpu;
 mod egl;
 mod ext;
 mod gl;
@@ -96,4 +97,8 @@ pub enum RenderError {
     ExternalOnly,
     #[error("OpenGL context does not support external textures")]
     ExternalUnsupported,
+    #[error("The operation is not supported by the software renderer")]
+    Software,
+    #[error("The operation is only supported by the software renderer")]
+    NotSoftware,
 }
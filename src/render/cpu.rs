@@ -0,0 +1,280 @@
+#[cfg(test)]
+mod tests;
+
+use {
+    crate::{
+        format::Format,
+        rect::Rect,
+        render::{sys::GL_RGBA, RenderError},
+        theme::Color,
+    },
+    std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    },
+};
+
+/// An image stored in main memory.
+///
+/// Pixels are stored as premultiplied ARGB in row-major order without padding.
+pub struct CpuImage {
+    pub width: i32,
+    pub height: i32,
+    pub pixels: RefCell<Vec<u32>>,
+}
+
+impl CpuImage {
+    pub fn new(width: i32, height: i32) -> Rc<Self> {
+        let len = width.max(0) as usize * height.max(0) as usize;
+        Rc::new(Self {
+            width,
+            height,
+            pixels: RefCell::new(vec![0; len]),
+        })
+    }
+
+    pub fn import_shm(
+        data: &[Cell<u8>],
+        format: &Format,
+        width: i32,
+        height: i32,
+        stride: i32,
+    ) -> Result<Rc<Self>, RenderError> {
+        if !format.shm_supported || format.bpp != 4 {
+            return Err(RenderError::UnsupportedFormat);
+        }
+        // The sizes are controlled by clients and must not overflow.
+        let fits = match (
+            usize::try_from(width),
+            usize::try_from(height),
+            usize::try_from(stride),
+        ) {
+            (Ok(w), Ok(h), Ok(s)) => match (s.checked_mul(h), w.checked_mul(4)) {
+                (Some(size), Some(row)) => size <= data.len() && row <= s,
+                _ => false,
+            },
+            _ => false,
+        };
+        if !fits {
+            return Err(RenderError::SmallImageBuffer);
+        }
+        let swap = format.gl_format == GL_RGBA;
+        let image = Self::new(width, height);
+        {
+            let mut pixels = image.pixels.borrow_mut();
+            for y in 0..height as usize {
+                let row = &data[y * stride as usize..];
+                let dst = &mut pixels[y * width as usize..(y + 1) * width as usize];
+                for (x, dst) in dst.iter_mut().enumerate() {
+                    let src = &row[x * 4..x * 4 + 4];
+                    let mut bytes = [src[0].get(), src[1].get(), src[2].get(), src[3].get()];
+                    if swap {
+                        bytes.swap(0, 2);
+                    }
+                    if !format.has_alpha {
+                        bytes[3] = 255;
+                    }
+                    *dst = u32::from_le_bytes(bytes);
+                }
+            }
+        }
+        Ok(image)
+    }
+}
+
+/// A render target stored in main memory.
+pub struct CpuFramebuffer {
+    pub image: Rc<CpuImage>,
+    scissor: Cell<Option<Rect>>,
+}
+
+impl CpuFramebuffer {
+    pub fn new(image: &Rc<CpuImage>) -> Self {
+        Self {
+            image: image.clone(),
+            scissor: Cell::new(None),
+        }
+    }
+
+    /// Restricts all drawing operations performed by `f` to `scissor`.
+    ///
+    /// Like `glScissor`, a nested scissor replaces the outer one.
+    pub fn with_scissor<T, F: FnOnce() -> T>(&self, scissor: &Rect, f: F) -> T {
+        let prev = self.scissor.replace(Some(*scissor));
+        let res = f();
+        self.scissor.set(prev);
+        res
+    }
+
+    fn bounds(&self) -> (i32, i32, i32, i32) {
+        let mut bounds = (0, 0, self.image.width, self.image.height);
+        if let Some(s) = self.scissor.get() {
+            bounds.0 = bounds.0.max(s.x1());
+            bounds.1 = bounds.1.max(s.y1());
+            bounds.2 = bounds.2.min(s.x2());
+            bounds.3 = bounds.3.min(s.y2());
+        }
+        bounds
+    }
+
+    pub fn clear(&self, c: &Color) {
+        let color = to_argb(c);
+        let (x1, y1, x2, y2) = self.bounds();
+        let mut pixels = self.image.pixels.borrow_mut();
+        for y in y1..y2 {
+            let row = (y * self.image.width) as usize;
+            for px in &mut pixels[row + x1.max(0) as usize..row + x2.max(x1).max(0) as usize] {
+                *px = color;
+            }
+        }
+    }
+
+    /// Fills the rectangle `(x1, y1, x2, y2)` given in physical pixels.
+    ///
+    /// A pixel is covered if its center lies within the rectangle.
+    pub fn fill_rect(&self, rect: (f32, f32, f32, f32), c: &Color) {
+        let color = to_argb(c);
+        let (bx1, by1, bx2, by2) = self.bounds();
+        let x1 = (pixel_start(rect.0)).max(bx1);
+        let y1 = (pixel_start(rect.1)).max(by1);
+        let x2 = (pixel_start(rect.2)).min(bx2);
+        let y2 = (pixel_start(rect.3)).min(by2);
+        if x1 >= x2 || y1 >= y2 {
+            return;
+        }
+        let mut pixels = self.image.pixels.borrow_mut();
+        for y in y1..y2 {
+            let row = (y * self.image.width) as usize;
+            for px in &mut pixels[row + x1 as usize..row + x2 as usize] {
+                *px = blend(color, *px);
+            }
+        }
+    }
+
+    /// Draws `tex` into the rectangle of size `width`x`height` at `(x, y)`.
+    ///
+    /// `texcoord` contains the texture coordinates of the top-right, top-left,
+    /// bottom-right, and bottom-left corners of the rectangle. The texture is sampled with
+    /// nearest-neighbor filtering.
+    pub fn draw_texture(
+        &self,
+        tex: &CpuImage,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        texcoord: &[f32; 8],
+        alpha: bool,
+    ) {
+        if width <= 0 || height <= 0 || tex.width <= 0 || tex.height <= 0 {
+            return;
+        }
+        if std::ptr::eq(tex, &*self.image) {
+            log::warn!("Trying to draw a texture into its own framebuffer");
+            return;
+        }
+        let (bx1, by1, bx2, by2) = self.bounds();
+        let x1 = x.max(bx1);
+        let y1 = y.max(by1);
+        let x2 = (x + width).min(bx2);
+        let y2 = (y + height).min(by2);
+        if x1 >= x2 || y1 >= y2 {
+            return;
+        }
+        let [tr_u, tr_v, tl_u, tl_v, br_u, br_v, bl_u, bl_v] = *texcoord;
+        let (twidth, theight) = (tex.width as f32, tex.height as f32);
+        let src = tex.pixels.borrow();
+        let mut dst = self.image.pixels.borrow_mut();
+        let ds = 1.0 / width as f32;
+        for py in y1..y2 {
+            let t = (py - y) as f32 + 0.5;
+            let t = t / height as f32;
+            // Along a row, the texture coordinates are a linear function of the position.
+            let u0 = tl_u + (bl_u - tl_u) * t;
+            let v0 = tl_v + (bl_v - tl_v) * t;
+            let du = (tr_u - tl_u) + (tl_u - tr_u - bl_u + br_u) * t;
+            let dv = (tr_v - tl_v) + (tl_v - tr_v - bl_v + br_v) * t;
+            let row = (py * self.image.width) as usize;
+            for px in x1..x2 {
+                let s = ((px - x) as f32 + 0.5) * ds;
+                let u = u0 + du * s;
+                let v = v0 + dv * s;
+                let tx = ((u * twidth) as i32).clamp(0, tex.width - 1);
+                let ty = ((v * theight) as i32).clamp(0, tex.height - 1);
+                let texel = src[(ty * tex.width + tx) as usize];
+                let pixel = &mut dst[row + px as usize];
+                *pixel = match alpha {
+                    true => blend(texel, *pixel),
+                    false => texel | 0xff00_0000,
+                };
+            }
+        }
+    }
+
+    pub fn copy_to_shm(
+        &self,
+        x: i32,
+        y: i32,
+        width: i32,
+        height: i32,
+        format: &Format,
+        shm: &[Cell<u8>],
+    ) {
+        let swap = format.gl_format == GL_RGBA;
+        let stride = width as usize * 4;
+        let pixels = self.image.pixels.borrow();
+        for dy in 0..height {
+            let sy = y + dy;
+            if sy < 0 || sy >= self.image.height {
+                continue;
+            }
+            for dx in 0..width {
+                let sx = x + dx;
+                if sx < 0 || sx >= self.image.width {
+                    continue;
+                }
+                let offset = dy as usize * stride + dx as usize * 4;
+                let dst = match shm.get(offset..offset + 4) {
+                    Some(d) => d,
+                    _ => return,
+                };
+                let mut bytes = pixels[(sy * self.image.width + sx) as usize].to_le_bytes();
+                if swap {
+                    bytes.swap(0, 2);
+                }
+                for (d, b) in dst.iter().zip(bytes) {
+                    d.set(b);
+                }
+            }
+        }
+    }
+}
+
+fn pixel_start(c: f32) -> i32 {
+    (c - 0.5).ceil() as i32
+}
+
+fn to_argb(c: &Color) -> u32 {
+    let conv = |c: f32| (c.clamp(0.0, 1.0) * 255.0 + 0.5) as u32;
+    (conv(c.a) << 24) | (conv(c.r) << 16) | (conv(c.g) << 8) | conv(c.b)
+}
+
+/// Composites the premultiplied pixel `src` over `dst`.
+fn blend(src: u32, dst: u32) -> u32 {
+    let sa = src >> 24;
+    if sa == 255 {
+        return src;
+    }
+    if sa == 0 && src == 0 {
+        return dst;
+    }
+    let inv = 255 - sa;
+    let mut res = 0;
+    for shift in [0, 8, 16, 24] {
+        let s = (src >> shift) & 0xff;
+        let d = (dst >> shift) & 0xff;
+        let c = (s + (d * inv + 127) / 255).min(255);
+        res |= c << shift;
+    }
+    res
+}
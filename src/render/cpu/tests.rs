@@ -0,0 +1,161 @@
+use {
+    crate::{
+        format::{Format, FORMATS},
+        rect::Rect,
+        render::{
+            cpu::{blend, CpuFramebuffer, CpuImage},
+            RenderError,
+        },
+        theme::Color,
+    },
+    std::cell::Cell,
+};
+
+const IDENTITY: [f32; 8] = [1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0];
+
+fn format(name: &str) -> &'static Format {
+    FORMATS.iter().find(|f| f.name == name).unwrap()
+}
+
+fn shm(bytes: &[u8]) -> Vec<Cell<u8>> {
+    bytes.iter().copied().map(Cell::new).collect()
+}
+
+fn bytes(shm: &[Cell<u8>]) -> Vec<u8> {
+    shm.iter().map(|b| b.get()).collect()
+}
+
+#[test]
+fn import_shm_format() {
+    let argb = shm(&[0x33, 0x22, 0x11, 0x44]);
+    let abgr = shm(&[0x11, 0x22, 0x33, 0x44]);
+    let image = CpuImage::import_shm(&argb, format("argb8888"), 1, 1, 4).unwrap();
+    assert_eq!(&image.pixels.borrow()[..], &[0x44112233]);
+    let image = CpuImage::import_shm(&abgr, format("abgr8888"), 1, 1, 4).unwrap();
+    assert_eq!(&image.pixels.borrow()[..], &[0x44112233]);
+    let image = CpuImage::import_shm(&abgr, format("xbgr8888"), 1, 1, 4).unwrap();
+    assert_eq!(&image.pixels.borrow()[..], &[0xff112233]);
+}
+
+#[test]
+fn import_shm_stride() {
+    let data = shm(&[1, 2, 3, 4, 0, 0, 0, 0, 5, 6, 7, 8, 0, 0, 0, 0]);
+    let image = CpuImage::import_shm(&data, format("argb8888"), 1, 2, 8).unwrap();
+    assert_eq!(&image.pixels.borrow()[..], &[0x04030201, 0x08070605]);
+    assert!(matches!(
+        CpuImage::import_shm(&data, format("argb8888"), 1, 3, 8),
+        Err(RenderError::SmallImageBuffer)
+    ));
+    assert!(matches!(
+        CpuImage::import_shm(&data, format("nv12"), 1, 2, 8),
+        Err(RenderError::UnsupportedFormat)
+    ));
+}
+
+#[test]
+fn import_shm_invalid_sizes() {
+    let data = shm(&[0; 16]);
+    let argb = format("argb8888");
+    for (width, height, stride) in [
+        (-1, 1, 4),
+        (1, -1, 4),
+        (1, 1, -4),
+        (1, i32::MAX, 8),
+        (i32::MAX, 1, 8),
+        (1, 0x4000_0001, 0x4000_0000),
+    ] {
+        assert!(
+            matches!(
+                CpuImage::import_shm(&data, argb, width, height, stride),
+                Err(RenderError::SmallImageBuffer)
+            ),
+            "{}x{} stride {}",
+            width,
+            height,
+            stride
+        );
+    }
+}
+
+#[test]
+fn blend_pixels() {
+    assert_eq!(blend(0xff102030, 0xff405060), 0xff102030);
+    assert_eq!(blend(0, 0xff405060), 0xff405060);
+    assert_eq!(blend(0x80400000, 0xff0000ff), 0xff40007f);
+    assert_eq!(blend(0x80400000, 0), 0x80400000);
+}
+
+#[test]
+fn fill_rect_blends() {
+    let image = CpuImage::new(2, 1);
+    let fb = CpuFramebuffer::new(&image);
+    fb.clear(&Color::from_rgba_straight(0, 0, 255, 255));
+    let color = Color {
+        r: 0.25,
+        g: 0.0,
+        b: 0.0,
+        a: 0.5,
+    };
+    fb.fill_rect((0.0, 0.0, 1.0, 1.0), &color);
+    assert_eq!(&image.pixels.borrow()[..], &[0xff40007f, 0xff0000ff]);
+}
+
+#[test]
+fn draw_texture_scaled_and_clipped() {
+    let (a, b, c, d) = (0xff000001, 0xff000002, 0xff000003, 0xff000004);
+    let tex = CpuImage::new(2, 2);
+    tex.pixels.borrow_mut().copy_from_slice(&[a, b, c, d]);
+    let image = CpuImage::new(8, 8);
+    let fb = CpuFramebuffer::new(&image);
+    // The texture is scaled by 4 horizontally and by 2 vertically. The leftmost two
+    // columns lie outside of the framebuffer and everything right of x = 5 is cut off
+    // by the scissor.
+    fb.with_scissor(&Rect::new(0, 0, 5, 8).unwrap(), || {
+        fb.draw_texture(&tex, -2, 2, 8, 4, &IDENTITY, true);
+    });
+    let pixels = image.pixels.borrow();
+    for y in 0..8 {
+        for x in 0..8 {
+            let expected = match (x, y) {
+                (0..=4, 2..=5) => match (x >= 2, y >= 4) {
+                    (false, false) => a,
+                    (true, false) => b,
+                    (false, true) => c,
+                    (true, true) => d,
+                },
+                _ => 0,
+            };
+            assert_eq!(pixels[y * 8 + x], expected, "pixel {}x{}", x, y);
+        }
+    }
+}
+
+#[test]
+fn draw_texture_opaque() {
+    let tex = CpuImage::new(1, 1);
+    tex.pixels.borrow_mut()[0] = 0x00102030;
+    let image = CpuImage::new(1, 1);
+    let fb = CpuFramebuffer::new(&image);
+    fb.draw_texture(&tex, 0, 0, 1, 1, &IDENTITY, false);
+    assert_eq!(image.pixels.borrow()[0], 0xff102030);
+}
+
+#[test]
+fn copy_to_shm_round_trip() {
+    let data = [
+        0x01, 0x02, 0x03, 0xff, 0x04, 0x05, 0x06, 0xff, 0x07, 0x08, 0x09, 0xff, //
+        0x0a, 0x0b, 0x0c, 0xff, 0x0d, 0x0e, 0x0f, 0xff, 0x10, 0x11, 0x12, 0xff,
+    ];
+    let abgr = format("abgr8888");
+    let tex = CpuImage::import_shm(&shm(&data), abgr, 3, 2, 12).unwrap();
+    let image = CpuImage::new(3, 2);
+    let fb = CpuFramebuffer::new(&image);
+    fb.draw_texture(&tex, 0, 0, 3, 2, &IDENTITY, false);
+    let out = shm(&[0; 24]);
+    fb.copy_to_shm(0, 0, 3, 2, abgr, &out);
+    assert_eq!(bytes(&out), data);
+    // Copying in a format with a different channel order swaps red and blue.
+    let out = shm(&[0; 4]);
+    fb.copy_to_shm(1, 1, 1, 1, format("argb8888"), &out);
+    assert_eq!(bytes(&out), [0x0f, 0x0e, 0x0d, 0xff]);
+}
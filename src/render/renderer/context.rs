@@ -2,15 +2,18 @@ use {
     crate::{
         format::{Format, XRGB8888},
         render::{
+            cpu::CpuImage,
             egl::{
                 context::EglContext,
                 display::{EglDisplay, EglFormat},
             },
             ext::GlExt,
-            gl::{
-                program::GlProgram, render_buffer::GlRenderBuffer, sys::GLint, texture::GlTexture,
+            gl::{program::GlProgram, sys::GLint, texture::GlTexture},
+            renderer::{
+                framebuffer::Framebuffer,
+                image::{Image, ImageKind},
+                texture::TextureKind,
             },
-            renderer::{framebuffer::Framebuffer, image::Image},
             RenderError, Texture,
         },
         video::{
@@ -53,8 +56,17 @@ pub(super) struct TexProgs {
 }
 
 pub struct RenderContext {
+    pub(super) kind: RenderContextKind,
+}
+
+pub(super) enum RenderContextKind {
+    Gl(GlRenderContext),
+    Cpu,
+}
+
+pub(super) struct GlRenderContext {
     pub(super) ctx: Rc<EglContext>,
-    pub gbm: Rc<GbmDevice>,
+    pub(super) gbm: Rc<GbmDevice>,
 
     pub(super) render_node: Rc<CString>,
 
@@ -81,12 +93,45 @@ pub enum ResetStatus {
 }
 
 impl RenderContext {
+    /// Creates a render context that renders on the CPU.
+    ///
+    /// Such a context does not require a DRM device but it only supports SHM buffers.
+    pub fn software() -> Self {
+        Self {
+            kind: RenderContextKind::Cpu,
+        }
+    }
+
+    pub fn is_software(&self) -> bool {
+        matches!(self.kind, RenderContextKind::Cpu)
+    }
+
+    pub(super) fn gl(&self) -> &GlRenderContext {
+        match &self.kind {
+            RenderContextKind::Gl(gl) => gl,
+            RenderContextKind::Cpu => unreachable!("GL operation on a software render context"),
+        }
+    }
+
     pub fn reset_status(&self) -> Option<ResetStatus> {
-        self.ctx.reset_status()
+        match &self.kind {
+            RenderContextKind::Gl(gl) => gl.ctx.reset_status(),
+            RenderContextKind::Cpu => None,
+        }
     }
 
     pub fn supports_external_texture(&self) -> bool {
-        self.ctx.ext.contains(GlExt::GL_OES_EGL_IMAGE_EXTERNAL)
+        match &self.kind {
+            RenderContextKind::Gl(gl) => gl.ctx.ext.contains(GlExt::GL_OES_EGL_IMAGE_EXTERNAL),
+            RenderContextKind::Cpu => false,
+        }
+    }
+
+    pub fn gbm(&self) -> Result<&Rc<GbmDevice>, RenderError> {
+        match &self.kind {
+            RenderContextKind::Gl(gl) => Ok(&gl.gbm),
+            RenderContextKind::Cpu => Err(RenderError::Software),
+        }
     }
 
     pub fn from_drm_device(drm: &Drm) -> Result<Self, RenderError> {
@@ -103,9 +148,81 @@ impl RenderContext {
             return Err(RenderError::XRGB888);
         }
         let ctx = dpy.create_context()?;
-        ctx.with_current(|| unsafe { Self::new(&ctx, &node) })
+        let gl = ctx.with_current(|| unsafe { GlRenderContext::new(&ctx, &node) })?;
+        Ok(Self {
+            kind: RenderContextKind::Gl(gl),
+        })
+    }
+
+    pub fn render_node(&self) -> Option<Rc<CString>> {
+        match &self.kind {
+            RenderContextKind::Gl(gl) => Some(gl.render_node.clone()),
+            RenderContextKind::Cpu => None,
+        }
+    }
+
+    pub fn formats(&self) -> Rc<AHashMap<u32, EglFormat>> {
+        match &self.kind {
+            RenderContextKind::Gl(gl) => gl.ctx.dpy.formats.clone(),
+            RenderContextKind::Cpu => Default::default(),
+        }
+    }
+
+    pub fn dmabuf_fb(self: &Rc<Self>, buf: &DmaBuf) -> Result<Rc<Framebuffer>, RenderError> {
+        self.dmabuf_img(buf)?.to_framebuffer()
+    }
+
+    pub fn dmabuf_img(self: &Rc<Self>, buf: &DmaBuf) -> Result<Rc<Image>, RenderError> {
+        let gl = match &self.kind {
+            RenderContextKind::Gl(gl) => gl,
+            RenderContextKind::Cpu => return Err(RenderError::Software),
+        };
+        gl.ctx.with_current(|| {
+            let img = gl.ctx.dpy.import_dmabuf(buf)?;
+            Ok(Rc::new(Image {
+                ctx: self.clone(),
+                kind: ImageKind::Gl(img),
+            }))
+        })
+    }
+
+    /// Creates an image in main memory.
+    ///
+    /// This is only supported by software render contexts.
+    pub fn cpu_img(self: &Rc<Self>, width: i32, height: i32) -> Result<Rc<Image>, RenderError> {
+        match &self.kind {
+            RenderContextKind::Gl(_) => Err(RenderError::NotSoftware),
+            RenderContextKind::Cpu => Ok(Rc::new(Image {
+                ctx: self.clone(),
+                kind: ImageKind::Cpu(CpuImage::new(width, height)),
+            })),
+        }
+    }
+
+    pub fn shmem_texture(
+        self: &Rc<Self>,
+        data: &[Cell<u8>],
+        format: &'static Format,
+        width: i32,
+        height: i32,
+        stride: i32,
+    ) -> Result<Rc<Texture>, RenderError> {
+        let kind = match &self.kind {
+            RenderContextKind::Gl(gl) => TextureKind::Gl(GlTexture::import_shm(
+                &gl.ctx, data, format, width, height, stride,
+            )?),
+            RenderContextKind::Cpu => {
+                TextureKind::Cpu(CpuImage::import_shm(data, format, width, height, stride)?)
+            }
+        };
+        Ok(Rc::new(Texture {
+            ctx: self.clone(),
+            kind,
+        }))
     }
+}
 
+impl GlRenderContext {
     unsafe fn new(ctx: &Rc<EglContext>, node: &Rc<CString>) -> Result<Self, RenderError> {
         let tex_vert = include_str!("../shaders/tex.vert.glsl");
         let tex_prog =
@@ -155,49 +272,4 @@ impl RenderContext {
             fill_prog,
         })
     }
-
-    pub fn render_node(&self) -> Rc<CString> {
-        self.render_node.clone()
-    }
-
-    pub fn formats(&self) -> Rc<AHashMap<u32, EglFormat>> {
-        self.ctx.dpy.formats.clone()
-    }
-
-    pub fn dmabuf_fb(self: &Rc<Self>, buf: &DmaBuf) -> Result<Rc<Framebuffer>, RenderError> {
-        self.ctx.with_current(|| unsafe {
-            let img = self.ctx.dpy.import_dmabuf(buf)?;
-            let rb = GlRenderBuffer::from_image(&img, &self.ctx)?;
-            let fb = rb.create_framebuffer()?;
-            Ok(Rc::new(Framebuffer {
-                ctx: self.clone(),
-                gl: fb,
-            }))
-        })
-    }
-
-    pub fn dmabuf_img(self: &Rc<Self>, buf: &DmaBuf) -> Result<Rc<Image>, RenderError> {
-        self.ctx.with_current(|| {
-            let img = self.ctx.dpy.import_dmabuf(buf)?;
-            Ok(Rc::new(Image {
-                ctx: self.clone(),
-                gl: img,
-            }))
-        })
-    }
-
-    pub fn shmem_texture(
-        self: &Rc<Self>,
-        data: &[Cell<u8>],
-        format: &'static Format,
-        width: i32,
-        height: i32,
-        stride: i32,
-    ) -> Result<Rc<Texture>, RenderError> {
-        let gl = GlTexture::import_shm(&self.ctx, data, format, width, height, stride)?;
-        Ok(Rc::new(Texture {
-            ctx: self.clone(),
-            gl,
-        }))
-    }
 }
@@ -5,6 +5,7 @@ use {
         format::{Format, ARGB8888, XRGB8888},
         rect::Rect,
         render::{
+            cpu::CpuFramebuffer,
             gl::{
                 frame_buffer::GlFrameBuffer,
                 sys::{
@@ -12,12 +13,17 @@ use {
                     GL_FRAMEBUFFER,
                 },
             },
-            renderer::{context::RenderContext, renderer::Renderer, renderer_base::RendererBase},
+            renderer::{
+                context::RenderContext,
+                renderer::Renderer,
+                renderer_base::{RenderTarget, RendererBase},
+            },
             sys::{glBlendFunc, glFlush, glReadnPixels, GL_ONE, GL_ONE_MINUS_SRC_ALPHA},
             RenderResult, Texture,
         },
         scale::Scale,
         state::State,
+        theme::Color,
         tree::Node,
     },
     std::{
@@ -29,7 +35,12 @@ use {
 
 pub struct Framebuffer {
     pub(super) ctx: Rc<RenderContext>,
-    pub(super) gl: GlFrameBuffer,
+    pub(super) kind: FramebufferKind,
+}
+
+pub(super) enum FramebufferKind {
+    Gl(GlFrameBuffer),
+    Cpu(CpuFramebuffer),
 }
 
 impl Debug for Framebuffer {
@@ -39,38 +50,64 @@ impl Debug for Framebuffer {
 }
 
 impl Framebuffer {
+    /// Binds the framebuffer, optionally clears it, and invokes `f` with the render target.
+    fn draw(&self, clear: Option<&Color>, f: impl FnOnce(RenderTarget<'_>)) {
+        match &self.kind {
+            FramebufferKind::Gl(fb) => {
+                let ctx = self.ctx.gl();
+                let _ = ctx.ctx.with_current(|| {
+                    unsafe {
+                        glBindFramebuffer(GL_FRAMEBUFFER, fb.fbo);
+                        glViewport(0, 0, fb.width, fb.height);
+                        if let Some(c) = clear {
+                            glClearColor(c.r, c.g, c.b, c.a);
+                            glClear(GL_COLOR_BUFFER_BIT);
+                        }
+                        glBlendFunc(GL_ONE, GL_ONE_MINUS_SRC_ALPHA);
+                    }
+                    f(RenderTarget::Gl(ctx, fb));
+                    unsafe {
+                        glFlush();
+                    }
+                    Ok(())
+                });
+            }
+            FramebufferKind::Cpu(fb) => {
+                if let Some(c) = clear {
+                    fb.clear(c);
+                }
+                f(RenderTarget::Cpu(fb));
+            }
+        }
+    }
+
+    fn size(&self) -> (i32, i32) {
+        match &self.kind {
+            FramebufferKind::Gl(fb) => (fb.width, fb.height),
+            FramebufferKind::Cpu(fb) => (fb.image.width, fb.image.height),
+        }
+    }
+
     pub fn clear(&self) {
         self.clear_with(0.0, 0.0, 0.0, 0.0);
     }
 
     pub fn clear_with(&self, r: f32, g: f32, b: f32, a: f32) {
-        let _ = self.ctx.ctx.with_current(|| {
-            unsafe {
-                glBindFramebuffer(GL_FRAMEBUFFER, self.gl.fbo);
-                glViewport(0, 0, self.gl.width, self.gl.height);
-                glClearColor(r, g, b, a);
-                glClear(GL_COLOR_BUFFER_BIT);
-            }
-            Ok(())
-        });
+        self.draw(Some(&Color { r, g, b, a }), |_| ());
     }
 
     pub fn copy_texture(&self, state: &State, texture: &Texture, x: i32, y: i32, alpha: bool) {
-        let _ = self.ctx.ctx.with_current(|| {
-            unsafe {
-                glBindFramebuffer(GL_FRAMEBUFFER, self.gl.fbo);
-                glViewport(0, 0, self.gl.width, self.gl.height);
-                if alpha {
-                    glClearColor(0.0, 0.0, 0.0, 0.0);
-                    glClear(GL_COLOR_BUFFER_BIT);
-                }
-                glBlendFunc(GL_ONE, GL_ONE_MINUS_SRC_ALPHA);
-            }
+        let clear = match alpha {
+            true => Some(Color::TRANSPARENT),
+            false => None,
+        };
+        let (width, height) = self.size();
+        self.draw(clear.as_ref(), |target| {
             let scale = Scale::from_int(1);
             let mut renderer = Renderer {
                 base: RendererBase {
                     ctx: &self.ctx,
-                    fb: &self.gl,
+                    target,
                     scaled: false,
                     scale,
                     scalef: 1.0,
@@ -78,7 +115,7 @@ impl Framebuffer {
                 state,
                 on_output: false,
                 result: &mut RenderResult::default(),
-                logical_extents: Rect::new_sized(0, 0, self.gl.width, self.gl.height).unwrap(),
+                logical_extents: Rect::new_sized(0, 0, width, height).unwrap(),
             };
             let format = match alpha {
                 true => ARGB8888,
@@ -87,10 +124,6 @@ impl Framebuffer {
             renderer
                 .base
                 .render_texture(texture, x, y, format, None, None, scale);
-            unsafe {
-                glFlush();
-            }
-            Ok(())
         });
     }
 
@@ -103,11 +136,18 @@ impl Framebuffer {
         format: &Format,
         shm: &[Cell<u8>],
     ) {
-        let y = self.gl.height - y - height;
-        let _ = self.ctx.ctx.with_current(|| {
+        let fb = match &self.kind {
+            FramebufferKind::Gl(fb) => fb,
+            FramebufferKind::Cpu(fb) => {
+                fb.copy_to_shm(x, y, width, height, format, shm);
+                return;
+            }
+        };
+        let y = fb.height - y - height;
+        let _ = self.ctx.gl().ctx.with_current(|| {
             unsafe {
-                glBindFramebuffer(GL_FRAMEBUFFER, self.gl.fbo);
-                glViewport(0, 0, self.gl.width, self.gl.height);
+                glBindFramebuffer(GL_FRAMEBUFFER, fb.fbo);
+                glViewport(0, 0, fb.width, fb.height);
                 glReadnPixels(
                     x,
                     y,
@@ -124,24 +164,15 @@ impl Framebuffer {
     }
 
     pub fn render_custom(&self, scale: Scale, f: impl FnOnce(&mut RendererBase)) {
-        let _ = self.ctx.ctx.with_current(|| {
-            unsafe {
-                glBindFramebuffer(GL_FRAMEBUFFER, self.gl.fbo);
-                glViewport(0, 0, self.gl.width, self.gl.height);
-                glBlendFunc(GL_ONE, GL_ONE_MINUS_SRC_ALPHA);
-            }
+        self.draw(None, |target| {
             let mut renderer = RendererBase {
                 ctx: &self.ctx,
-                fb: &self.gl,
+                target,
                 scaled: scale != 1,
                 scale,
                 scalef: scale.to_f64(),
             };
             f(&mut renderer);
-            unsafe {
-                glFlush();
-            }
-            Ok(())
         });
     }
 
@@ -155,19 +186,13 @@ impl Framebuffer {
         scale: Scale,
         render_hardware_cursor: bool,
     ) {
-        let _ = self.ctx.ctx.with_current(|| {
-            let c = state.theme.colors.background.get();
-            unsafe {
-                glBindFramebuffer(GL_FRAMEBUFFER, self.gl.fbo);
-                glViewport(0, 0, self.gl.width, self.gl.height);
-                glClearColor(c.r, c.g, c.b, 1.0);
-                glClear(GL_COLOR_BUFFER_BIT);
-                glBlendFunc(GL_ONE, GL_ONE_MINUS_SRC_ALPHA);
-            }
+        let c = state.theme.colors.background.get();
+        let clear = Color { a: 1.0, ..c };
+        self.draw(Some(&clear), |target| {
             let mut renderer = Renderer {
                 base: RendererBase {
                     ctx: &self.ctx,
-                    fb: &self.gl,
+                    target,
                     scaled: scale != 1,
                     scale,
                     scalef: scale.to_f64(),
@@ -203,27 +228,16 @@ impl Framebuffer {
                     }
                 }
             }
-            unsafe {
-                glFlush();
-            }
-            Ok(())
         });
     }
 
     pub fn render_hardware_cursor(&self, cursor: &dyn Cursor, state: &State, scale: Scale) {
-        let _ = self.ctx.ctx.with_current(|| {
-            unsafe {
-                glBindFramebuffer(GL_FRAMEBUFFER, self.gl.fbo);
-                glViewport(0, 0, self.gl.width, self.gl.height);
-                glClearColor(0.0, 0.0, 0.0, 0.0);
-                glClear(GL_COLOR_BUFFER_BIT);
-                glBlendFunc(GL_ONE, GL_ONE_MINUS_SRC_ALPHA);
-            }
+        self.draw(Some(&Color::TRANSPARENT), |target| {
             let mut res = RenderResult::default();
             let mut renderer = Renderer {
                 base: RendererBase {
                     ctx: &self.ctx,
-                    fb: &self.gl,
+                    target,
                     scaled: scale != 1,
                     scale,
                     scalef: scale.to_f64(),
@@ -234,10 +248,6 @@ impl Framebuffer {
                 logical_extents: Rect::new_empty(0, 0),
             };
             cursor.render_hardware_cursor(&mut renderer);
-            unsafe {
-                glFlush();
-            }
-            Ok(())
         });
     }
 }
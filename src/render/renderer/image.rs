@@ -1,7 +1,9 @@
 use {
     crate::render::{
+        cpu::{CpuFramebuffer, CpuImage},
         egl::image::EglImage,
         gl::{render_buffer::GlRenderBuffer, texture::GlTexture},
+        renderer::{framebuffer::FramebufferKind, texture::TextureKind},
         Framebuffer, RenderContext, RenderError, Texture,
     },
     std::rc::Rc,
@@ -9,33 +11,54 @@ use {
 
 pub struct Image {
     pub(super) ctx: Rc<RenderContext>,
-    pub(super) gl: Rc<EglImage>,
+    pub(super) kind: ImageKind,
+}
+
+pub(super) enum ImageKind {
+    Gl(Rc<EglImage>),
+    Cpu(Rc<CpuImage>),
 }
 
 impl Image {
     pub fn width(&self) -> i32 {
-        self.gl.width
+        match &self.kind {
+            ImageKind::Gl(gl) => gl.width,
+            ImageKind::Cpu(cpu) => cpu.width,
+        }
     }
 
     pub fn height(&self) -> i32 {
-        self.gl.height
+        match &self.kind {
+            ImageKind::Gl(gl) => gl.height,
+            ImageKind::Cpu(cpu) => cpu.height,
+        }
     }
 
     pub fn to_texture(self: &Rc<Self>) -> Result<Rc<Texture>, RenderError> {
+        let kind = match &self.kind {
+            ImageKind::Gl(img) => TextureKind::Gl(GlTexture::import_img(&self.ctx.gl().ctx, img)?),
+            ImageKind::Cpu(img) => TextureKind::Cpu(img.clone()),
+        };
         Ok(Rc::new(Texture {
             ctx: self.ctx.clone(),
-            gl: GlTexture::import_img(&self.ctx.ctx, &self.gl)?,
+            kind,
         }))
     }
 
     pub fn to_framebuffer(&self) -> Result<Rc<Framebuffer>, RenderError> {
-        self.ctx.ctx.with_current(|| unsafe {
-            let rb = GlRenderBuffer::from_image(&self.gl, &self.ctx.ctx)?;
-            let fb = rb.create_framebuffer()?;
-            Ok(Rc::new(Framebuffer {
-                ctx: self.ctx.clone(),
-                gl: fb,
-            }))
-        })
+        let kind = match &self.kind {
+            ImageKind::Gl(img) => {
+                let ctx = &self.ctx.gl().ctx;
+                ctx.with_current(|| unsafe {
+                    let rb = GlRenderBuffer::from_image(img, ctx)?;
+                    Ok(FramebufferKind::Gl(rb.create_framebuffer()?))
+                })?
+            }
+            ImageKind::Cpu(img) => FramebufferKind::Cpu(CpuFramebuffer::new(img)),
+        };
+        Ok(Rc::new(Framebuffer {
+            ctx: self.ctx.clone(),
+            kind,
+        }))
     }
 }
@@ -10,13 +10,13 @@ use {
             wp_presentation_feedback::WpPresentationFeedback,
         },
         rect::Rect,
-        render::{gl::frame_buffer::with_scissor, renderer::renderer_base::RendererBase},
+        render::renderer::renderer_base::RendererBase,
         scale::Scale,
         state::State,
         theme::Color,
         tree::{
-            ContainerNode, DisplayNode, FloatNode, OutputNode, PlaceholderNode, ToplevelNode,
-            WorkspaceNode,
+            ContainerNode, DisplayNode, FloatNode, Node, OutputNode, PlaceholderNode, ToplevelNode,
+            WindowMenuNode, WorkspaceNode, WINDOW_MENU_PADDING,
         },
     },
     std::{
@@ -71,15 +71,17 @@ impl Renderer<'_> {
     }
 
     pub fn render_output(&mut self, output: &OutputNode, x: i32, y: i32) {
+        let opos = output.global.pos.get();
         if self.state.lock.locked.get() {
             if let Some(surface) = output.lock_surface.get() {
                 if surface.surface.buffer.get().is_some() {
                     self.render_surface(&surface.surface, x, y);
                 }
+            } else if self.state.lock.lock.get().is_none() {
+                self.render_lock_fallback(output, opos, x, y);
             }
             return;
         }
-        let opos = output.global.pos.get();
         macro_rules! render_layer {
             ($layer:expr) => {
                 for ls in $layer.iter() {
@@ -161,9 +163,30 @@ impl Renderer<'_> {
         render_layer!(output.layers[3]);
     }
 
+    fn render_lock_fallback(&mut self, output: &OutputNode, opos: Rect, x: i32, y: i32) {
+        let c = self.state.theme.colors.background.get();
+        self.base.fill_boxes2(
+            slice::from_ref(&Rect::new_sized(0, 0, opos.width(), opos.height()).unwrap()),
+            &c,
+            x,
+            y,
+        );
+        let rd = output.render_data.borrow_mut();
+        if let Some(fallback) = &rd.lock_fallback {
+            let scale = output.preferred_scale.get();
+            let (x, y) = self
+                .base
+                .scale_point(x + fallback.tex_x, y + fallback.tex_y);
+            self.base
+                .render_texture(&fallback.tex, x, y, ARGB8888, None, None, scale);
+        }
+    }
+
     pub fn render_workspace(&mut self, workspace: &WorkspaceNode, x: i32, y: i32) {
         if let Some(node) = workspace.container.get() {
-            self.render_container(&node, x, y)
+            let pos = workspace.position.get();
+            let cpos = node.node_absolute_position();
+            self.render_container(&node, x + cpos.x1() - pos.x1(), y + cpos.y1() - pos.y1())
         }
     }
 
@@ -217,32 +240,30 @@ impl Renderer<'_> {
             }
         }
         if let Some(child) = container.mono_child.get() {
-            unsafe {
-                let body = container.mono_body.get().move_(x, y);
-                let body = self.base.scale_rect(body);
-                with_scissor(&body, || {
-                    let content = container.mono_content.get();
-                    child
-                        .node
-                        .node_render(self, x + content.x1(), y + content.y1());
-                });
-            }
+            let body = container.mono_body.get().move_(x, y);
+            let body = self.base.scale_rect(body);
+            let target = self.base.target;
+            target.with_scissor(&body, || {
+                let content = container.mono_content.get();
+                child
+                    .node
+                    .node_render(self, x + content.x1(), y + content.y1());
+            });
         } else {
             for child in container.children.iter() {
                 let body = child.body.get();
                 if body.x1() >= container.width.get() || body.y1() >= container.height.get() {
-                    break;
+                    continue;
                 }
                 let body = body.move_(x, y);
                 let body = self.base.scale_rect(body);
-                unsafe {
-                    with_scissor(&body, || {
-                        let content = child.content.get();
-                        child
-                            .node
-                            .node_render(self, x + content.x1(), y + content.y1());
-                    });
-                }
+                let target = self.base.target;
+                target.with_scissor(&body, || {
+                    let content = child.content.get();
+                    child
+                        .node
+                        .node_render(self, x + content.x1(), y + content.y1());
+                });
             }
         }
     }
@@ -385,20 +406,57 @@ impl Renderer<'_> {
         )
         .unwrap();
         let scissor_body = self.base.scale_rect(body);
-        unsafe {
-            with_scissor(&scissor_body, || {
-                child.node_render(self, body.x1(), body.y1());
-            });
+        let target = self.base.target;
+        target.with_scissor(&scissor_body, || {
+            child.node_render(self, body.x1(), body.y1());
+        });
+    }
+
+    pub fn render_window_menu(&mut self, menu: &WindowMenuNode, x: i32, y: i32) {
+        let pos = menu.position.get();
+        let theme = &self.state.theme;
+        let bw = menu.border_width;
+        let rh = menu.row_height;
+        let bc = theme.colors.border.get();
+        let borders = [
+            Rect::new_sized(x, y, pos.width(), bw).unwrap(),
+            Rect::new_sized(x, y + bw, bw, pos.height() - bw).unwrap(),
+            Rect::new_sized(x + pos.width() - bw, y + bw, bw, pos.height() - bw).unwrap(),
+            Rect::new_sized(x + bw, y + pos.height() - bw, pos.width() - 2 * bw, bw).unwrap(),
+        ];
+        self.base.fill_boxes(&borders, &bc);
+        let body =
+            Rect::new_sized(x + bw, y + bw, pos.width() - 2 * bw, pos.height() - 2 * bw).unwrap();
+        let c = theme.colors.unfocused_title_background.get();
+        self.base.fill_boxes(slice::from_ref(&body), &c);
+        let selected = menu.selected.get();
+        if let Some(idx) = selected {
+            let row =
+                Rect::new_sized(body.x1(), body.y1() + idx as i32 * rh, body.width(), rh).unwrap();
+            let c = theme.colors.focused_title_background.get();
+            self.base.fill_boxes(slice::from_ref(&row), &c);
+        }
+        if let Some(textures) = menu.textures.get(&self.base.scale) {
+            for (idx, tex) in textures.iter().enumerate() {
+                let tex = match selected == Some(idx) {
+                    true => &tex.selected,
+                    false => &tex.normal,
+                };
+                let (x, y) = self
+                    .base
+                    .scale_point(body.x1() + WINDOW_MENU_PADDING, body.y1() + idx as i32 * rh);
+                self.base
+                    .render_texture(tex, x, y, ARGB8888, None, None, self.base.scale);
+            }
         }
     }
 
     pub fn render_layer_surface(&mut self, surface: &ZwlrLayerSurfaceV1, x: i32, y: i32) {
-        unsafe {
-            let body = surface.position().at_point(x, y);
-            let body = self.base.scale_rect(body);
-            with_scissor(&body, || {
-                self.render_surface(&surface.surface, x, y);
-            });
-        }
+        let body = surface.position().at_point(x, y);
+        let body = self.base.scale_rect(body);
+        let target = self.base.target;
+        target.with_scissor(&body, || {
+            self.render_surface(&surface.surface, x, y);
+        });
     }
 }
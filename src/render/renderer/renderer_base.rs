@@ -3,8 +3,9 @@ use {
         format::Format,
         rect::Rect,
         render::{
+            cpu::CpuFramebuffer,
             gl::{
-                frame_buffer::GlFrameBuffer,
+                frame_buffer::{with_scissor, GlFrameBuffer},
                 sys::{
                     glActiveTexture, glBindTexture, glDisableVertexAttribArray, glDrawArrays,
                     glEnableVertexAttribArray, glTexParameteri, glUniform1i, glUniform4f,
@@ -13,7 +14,10 @@ use {
                 },
                 texture::image_target,
             },
-            renderer::context::RenderContext,
+            renderer::{
+                context::{GlRenderContext, RenderContext},
+                texture::TextureKind,
+            },
             sys::{glClear, glClearColor, glDisable, glEnable, GL_BLEND, GL_COLOR_BUFFER_BIT},
             Texture,
         },
@@ -24,9 +28,39 @@ use {
     std::rc::Rc,
 };
 
+#[derive(Copy, Clone)]
+pub(super) enum RenderTarget<'a> {
+    Gl(&'a GlRenderContext, &'a GlFrameBuffer),
+    Cpu(&'a CpuFramebuffer),
+}
+
+impl RenderTarget<'_> {
+    fn width(self) -> i32 {
+        match self {
+            RenderTarget::Gl(_, fb) => fb.width,
+            RenderTarget::Cpu(fb) => fb.image.width,
+        }
+    }
+
+    fn height(self) -> i32 {
+        match self {
+            RenderTarget::Gl(_, fb) => fb.height,
+            RenderTarget::Cpu(fb) => fb.image.height,
+        }
+    }
+
+    /// Restricts all rendering performed by `f` to `scissor`.
+    pub(super) fn with_scissor<T, F: FnOnce() -> T>(self, scissor: &Rect, f: F) -> T {
+        match self {
+            RenderTarget::Gl(..) => unsafe { with_scissor(scissor, f) },
+            RenderTarget::Cpu(fb) => fb.with_scissor(scissor, f),
+        }
+    }
+}
+
 pub struct RendererBase<'a> {
     pub(super) ctx: &'a Rc<RenderContext>,
-    pub(super) fb: &'a GlFrameBuffer,
+    pub(super) target: RenderTarget<'a>,
     pub(super) scaled: bool,
     pub(super) scale: Scale,
     pub(super) scalef: f64,
@@ -38,7 +72,7 @@ impl RendererBase<'_> {
     }
 
     pub fn physical_extents(&self) -> Rect {
-        Rect::new_sized(0, 0, self.fb.width, self.fb.height).unwrap()
+        Rect::new_sized(0, 0, self.target.width(), self.target.height()).unwrap()
     }
 
     pub fn scale_point(&self, mut x: i32, mut y: i32) -> (i32, i32) {
@@ -80,25 +114,20 @@ impl RendererBase<'_> {
     }
 
     fn xf_to_f(&self, x: f32) -> f32 {
-        2.0 * (x / self.fb.width as f32) - 1.0
+        2.0 * (x / self.target.width() as f32) - 1.0
     }
 
     fn yf_to_f(&self, y: f32) -> f32 {
-        2.0 * (y / self.fb.height as f32) - 1.0
-    }
-
-    fn x_to_f(&self, x: i32) -> f32 {
-        2.0 * (x as f32 / self.fb.width as f32) - 1.0
-    }
-
-    fn y_to_f(&self, y: i32) -> f32 {
-        2.0 * (y as f32 / self.fb.height as f32) - 1.0
+        2.0 * (y / self.target.height() as f32) - 1.0
     }
 
     pub fn clear(&self, c: &Color) {
-        unsafe {
-            glClearColor(c.r, c.g, c.b, c.a);
-            glClear(GL_COLOR_BUFFER_BIT);
+        match self.target {
+            RenderTarget::Gl(..) => unsafe {
+                glClearColor(c.r, c.g, c.b, c.a);
+                glClear(GL_COLOR_BUFFER_BIT);
+            },
+            RenderTarget::Cpu(fb) => fb.clear(c),
         }
     }
 
@@ -111,25 +140,17 @@ impl RendererBase<'_> {
             return;
         }
         let (dx, dy) = self.scale_point(dx, dy);
-        let mut pos = Vec::with_capacity(boxes.len() * 12);
+        let mut rects = Vec::with_capacity(boxes.len());
         for bx in boxes {
             let bx = self.scale_rect(*bx);
-            let x1 = self.x_to_f(bx.x1() + dx);
-            let y1 = self.y_to_f(bx.y1() + dy);
-            let x2 = self.x_to_f(bx.x2() + dx);
-            let y2 = self.y_to_f(bx.y2() + dy);
-            pos.extend_from_slice(&[
-                // triangle 1
-                x2, y1, // top right
-                x1, y1, // top left
-                x1, y2, // bottom left
-                // triangle 2
-                x2, y1, // top right
-                x1, y2, // bottom left
-                x2, y2, // bottom right
-            ]);
+            rects.push((
+                (bx.x1() + dx) as f32,
+                (bx.y1() + dy) as f32,
+                (bx.x2() + dx) as f32,
+                (bx.y2() + dy) as f32,
+            ));
         }
-        self.fill_boxes3(&pos, color)
+        self.fill_rects(&rects, color)
     }
 
     pub fn fill_boxes_f(&self, boxes: &[(f32, f32, f32, f32)], color: &Color) {
@@ -141,13 +162,31 @@ impl RendererBase<'_> {
             return;
         }
         let (dx, dy) = self.scale_point_f(dx, dy);
-        let mut pos = Vec::with_capacity(boxes.len() * 12);
+        let mut rects = Vec::with_capacity(boxes.len());
         for bx in boxes {
             let (x1, y1, x2, y2) = self.scale_rect_f(*bx);
-            let x1 = self.xf_to_f(x1 + dx);
-            let y1 = self.yf_to_f(y1 + dy);
-            let x2 = self.xf_to_f(x2 + dx);
-            let y2 = self.yf_to_f(y2 + dy);
+            rects.push((x1 + dx, y1 + dy, x2 + dx, y2 + dy));
+        }
+        self.fill_rects(&rects, color)
+    }
+
+    /// Fills rectangles given in physical coordinates.
+    fn fill_rects(&self, rects: &[(f32, f32, f32, f32)], color: &Color) {
+        let ctx = match self.target {
+            RenderTarget::Gl(ctx, _) => ctx,
+            RenderTarget::Cpu(fb) => {
+                for rect in rects {
+                    fb.fill_rect(*rect, color);
+                }
+                return;
+            }
+        };
+        let mut pos = Vec::with_capacity(rects.len() * 12);
+        for &(x1, y1, x2, y2) in rects {
+            let x1 = self.xf_to_f(x1);
+            let y1 = self.yf_to_f(y1);
+            let x2 = self.xf_to_f(x2);
+            let y2 = self.yf_to_f(y2);
             pos.extend_from_slice(&[
                 // triangle 1
                 x2, y1, // top right
@@ -159,24 +198,20 @@ impl RendererBase<'_> {
                 x2, y2, // bottom right
             ]);
         }
-        self.fill_boxes3(&pos, color)
-    }
-
-    fn fill_boxes3(&self, boxes: &[f32], color: &Color) {
         unsafe {
-            glUseProgram(self.ctx.fill_prog.prog);
-            glUniform4f(self.ctx.fill_prog_color, color.r, color.g, color.b, color.a);
+            glUseProgram(ctx.fill_prog.prog);
+            glUniform4f(ctx.fill_prog_color, color.r, color.g, color.b, color.a);
             glVertexAttribPointer(
-                self.ctx.fill_prog_pos as _,
+                ctx.fill_prog_pos as _,
                 2,
                 GL_FLOAT,
                 GL_FALSE,
                 0,
-                boxes.as_ptr() as _,
+                pos.as_ptr() as _,
             );
-            glEnableVertexAttribArray(self.ctx.fill_prog_pos as _);
-            glDrawArrays(GL_TRIANGLES, 0, (boxes.len() / 2) as _);
-            glDisableVertexAttribArray(self.ctx.fill_prog_pos as _);
+            glEnableVertexAttribArray(ctx.fill_prog_pos as _);
+            glDrawArrays(GL_TRIANGLES, 0, (pos.len() / 2) as _);
+            glDisableVertexAttribArray(ctx.fill_prog_pos as _);
         }
     }
 
@@ -190,24 +225,53 @@ impl RendererBase<'_> {
         tsize: Option<(i32, i32)>,
         tscale: Scale,
     ) {
-        assert!(rc_eq(&self.ctx.ctx, &texture.ctx.ctx));
+        assert!(rc_eq(self.ctx, &texture.ctx));
+
+        static DEFAULT_TEXCOORD: [f32; 8] = [1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0];
+
+        let texcoord: &[f32; 8] = match tpoints {
+            None => &DEFAULT_TEXCOORD,
+            Some(tp) => tp,
+        };
+
+        let (twidth, theight) = if let Some(size) = tsize {
+            size
+        } else {
+            let (mut w, mut h) = (texture.width(), texture.height());
+            if tscale != self.scale {
+                let tscale = tscale.to_f64();
+                w = (w as f64 * self.scalef / tscale).round() as _;
+                h = (h as f64 * self.scalef / tscale).round() as _;
+            }
+            (w, h)
+        };
+
+        let (ctx, fb, gl) = match (self.target, &texture.kind) {
+            (RenderTarget::Gl(ctx, fb), TextureKind::Gl(gl)) => (ctx, fb, gl),
+            (RenderTarget::Cpu(fb), TextureKind::Cpu(img)) => {
+                fb.draw_texture(img, x, y, twidth, theight, texcoord, format.has_alpha);
+                return;
+            }
+            _ => unreachable!(),
+        };
+
         unsafe {
             glActiveTexture(GL_TEXTURE0);
 
-            let target = image_target(texture.gl.external_only);
+            let target = image_target(gl.external_only);
 
-            glBindTexture(target, texture.gl.tex);
+            glBindTexture(target, gl.tex);
             glTexParameteri(target, GL_TEXTURE_MIN_FILTER, GL_LINEAR);
 
-            let progs = match texture.gl.external_only {
-                true => match &self.ctx.tex_external {
+            let progs = match gl.external_only {
+                true => match &ctx.tex_external {
                     Some(p) => p,
                     _ => {
                         log::error!("Trying to render an external-only texture but context does not support the required extension");
                         return;
                     }
                 },
-                false => &self.ctx.tex_internal,
+                false => &ctx.tex_internal,
             };
             let prog = match format.has_alpha {
                 true => {
@@ -224,27 +288,8 @@ impl RendererBase<'_> {
 
             glUniform1i(prog.tex, 0);
 
-            static DEFAULT_TEXCOORD: [f32; 8] = [1.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 1.0];
-
-            let texcoord: &[f32; 8] = match tpoints {
-                None => &DEFAULT_TEXCOORD,
-                Some(tp) => tp,
-            };
-
-            let f_width = self.fb.width as f32;
-            let f_height = self.fb.height as f32;
-
-            let (twidth, theight) = if let Some(size) = tsize {
-                size
-            } else {
-                let (mut w, mut h) = (texture.gl.width, texture.gl.height);
-                if tscale != self.scale {
-                    let tscale = tscale.to_f64();
-                    w = (w as f64 * self.scalef / tscale).round() as _;
-                    h = (h as f64 * self.scalef / tscale).round() as _;
-                }
-                (w, h)
-            };
+            let f_width = fb.width as f32;
+            let f_height = fb.height as f32;
 
             let x1 = 2.0 * (x as f32 / f_width) - 1.0;
             let y1 = 2.0 * (y as f32 / f_height) - 1.0;
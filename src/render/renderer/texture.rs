@@ -1,5 +1,5 @@
 use {
-    crate::render::{gl::texture::GlTexture, renderer::context::RenderContext},
+    crate::render::{cpu::CpuImage, gl::texture::GlTexture, renderer::context::RenderContext},
     std::{
         fmt::{Debug, Formatter},
         rc::Rc,
@@ -8,7 +8,12 @@ use {
 
 pub struct Texture {
     pub(super) ctx: Rc<RenderContext>,
-    pub(super) gl: GlTexture,
+    pub(super) kind: TextureKind,
+}
+
+pub(super) enum TextureKind {
+    Gl(GlTexture),
+    Cpu(Rc<CpuImage>),
 }
 
 impl Debug for Texture {
@@ -19,10 +24,16 @@ impl Debug for Texture {
 
 impl Texture {
     pub fn width(&self) -> i32 {
-        self.gl.width
+        match &self.kind {
+            TextureKind::Gl(gl) => gl.width,
+            TextureKind::Cpu(cpu) => cpu.width,
+        }
     }
 
     pub fn height(&self) -> i32 {
-        self.gl.height
+        match &self.kind {
+            TextureKind::Gl(gl) => gl.height,
+            TextureKind::Cpu(cpu) => cpu.height,
+        }
     }
 }
@@ -1,18 +1,19 @@
 use {
     crate::{
         format::XRGB8888,
-        render::RenderError,
+        render::{Framebuffer, RenderError},
         scale::Scale,
         state::State,
+        utils::oserror::OsError,
         video::{
             drm::DrmError,
             gbm::{GbmBo, GbmError, GBM_BO_USE_LINEAR, GBM_BO_USE_RENDERING},
             ModifiedFormat, INVALID_MODIFIER,
         },
     },
-    std::{ops::Deref, rc::Rc},
+    std::{cell::Cell, ops::Deref, rc::Rc},
     thiserror::Error,
-    uapi::OwnedFd,
+    uapi::{c, OwnedFd},
 };
 
 #[derive(Debug, Error)]
@@ -27,11 +28,23 @@ pub enum ScreenshooterError {
     RenderError(#[from] RenderError),
     #[error(transparent)]
     DrmError(#[from] DrmError),
+    #[error("Could not create a memfd")]
+    CreateMemfd(#[source] OsError),
+    #[error("Could not write to the memfd")]
+    WriteMemfd(#[source] OsError),
 }
 
-pub struct Screenshot {
-    pub drm: Rc<OwnedFd>,
-    pub bo: GbmBo,
+pub enum Screenshot {
+    Dmabuf {
+        drm: Rc<OwnedFd>,
+        bo: GbmBo,
+    },
+    Shm {
+        fd: Rc<OwnedFd>,
+        width: i32,
+        height: i32,
+        stride: i32,
+    },
 }
 
 pub fn take_screenshot(state: &State) -> Result<Screenshot, ScreenshooterError> {
@@ -43,17 +56,56 @@ pub fn take_screenshot(state: &State) -> Result<Screenshot, ScreenshooterError>
     if extents.is_empty() {
         return Err(ScreenshooterError::EmptyDisplay);
     }
+    if ctx.is_software() {
+        let (width, height) = (extents.width(), extents.height());
+        let fb = ctx.cpu_img(width, height)?.to_framebuffer()?;
+        render(state, &fb);
+        let stride = width * 4;
+        let mut data = vec![0u8; (stride * height) as usize];
+        fb.copy_to_shm(
+            0,
+            0,
+            width,
+            height,
+            XRGB8888,
+            Cell::from_mut(&mut data[..]).as_slice_of_cells(),
+        );
+        let fd = match uapi::memfd_create("screenshot", c::MFD_CLOEXEC) {
+            Ok(fd) => fd,
+            Err(e) => return Err(ScreenshooterError::CreateMemfd(e.into())),
+        };
+        let mut pos = 0;
+        while pos < data.len() {
+            match uapi::write(fd.raw(), &data[pos..]) {
+                Ok(n) => pos += n,
+                Err(e) => return Err(ScreenshooterError::WriteMemfd(e.into())),
+            }
+        }
+        return Ok(Screenshot::Shm {
+            fd: Rc::new(fd),
+            width,
+            height,
+            stride,
+        });
+    }
+    let gbm = ctx.gbm()?;
     let format = ModifiedFormat {
         format: XRGB8888,
         modifier: INVALID_MODIFIER,
     };
-    let bo = ctx.gbm.create_bo(
+    let bo = gbm.create_bo(
         extents.width(),
         extents.height(),
         &format,
         GBM_BO_USE_RENDERING | GBM_BO_USE_LINEAR,
     )?;
     let fb = ctx.dmabuf_fb(bo.dmabuf())?;
+    render(state, &fb);
+    let drm = gbm.drm.dup_render()?.fd().clone();
+    Ok(Screenshot::Dmabuf { drm, bo })
+}
+
+fn render(state: &State, fb: &Framebuffer) {
     fb.render(
         state.root.deref(),
         state,
@@ -63,6 +115,4 @@ pub fn take_screenshot(state: &State) -> Result<Screenshot, ScreenshooterError>
         Scale::from_int(1),
         true,
     );
-    let drm = ctx.gbm.drm.dup_render()?.fd().clone();
-    Ok(Screenshot { drm, bo })
 }
@@ -20,7 +20,7 @@ use {
             jay_seat_events::JaySeatEvents,
             jay_workspace_watcher::JayWorkspaceWatcher,
             wl_drm::WlDrmGlobal,
-            wl_seat::{SeatIds, WlSeatGlobal},
+            wl_seat::{collect_kb_foci, SeatIds, WlSeatGlobal},
             wl_surface::{
                 zwp_idle_inhibitor_v1::{IdleInhibitorId, IdleInhibitorIds, ZwpIdleInhibitorV1},
                 NoneSurfaceExt, WlSurface,
@@ -36,7 +36,8 @@ use {
         theme::Theme,
         tree::{
             ContainerNode, ContainerSplit, Direction, DisplayNode, FloatNode, Node, NodeIds,
-            NodeVisitorBase, OutputNode, PlaceholderNode, ToplevelNode, WorkspaceNode,
+            NodeVisitorBase, OutputNode, PlaceholderNode, PlacementIntent, Placements,
+            ToplevelNode, WorkspaceNode,
         },
         utils::{
             asyncevent::AsyncEvent, clonecell::CloneCell, copyhashmap::CopyHashMap,
@@ -56,7 +57,7 @@ use {
         fmt::{Debug, Formatter},
         mem,
         num::Wrapping,
-        ops::DerefMut,
+        ops::{Deref, DerefMut},
         rc::Rc,
         sync::Arc,
         time::Duration,
@@ -119,11 +120,14 @@ pub struct State {
     pub lock: ScreenlockState,
     pub scales: RefCounted<Scale>,
     pub cursor_sizes: RefCounted<u32>,
+    pub cursor_theme: RefCell<Option<String>>,
     pub hardware_tick_cursor: AsyncQueue<Option<Rc<dyn Cursor>>>,
     pub testers: RefCell<AHashMap<(ClientId, JaySeatEventsId), Rc<JaySeatEvents>>>,
     pub render_ctx_watchers: CopyHashMap<(ClientId, JayRenderCtxId), Rc<JayRenderCtx>>,
     pub workspace_watchers: CopyHashMap<(ClientId, JayWorkspaceWatcherId), Rc<JayWorkspaceWatcher>>,
     pub default_workspace_capture: Cell<bool>,
+    pub placements: Placements,
+    pub scratchpad: CloneCell<Option<Rc<WorkspaceNode>>>,
 }
 
 // impl Drop for State {
@@ -141,8 +145,12 @@ impl Debug for State {
 pub struct ScreenlockState {
     pub locked: Cell<bool>,
     pub lock: CloneCell<Option<Rc<ExtSessionLockV1>>>,
+    pub fallback_text: CloneCell<Rc<String>>,
 }
 
+pub const DEFAULT_LOCK_FALLBACK_TEXT: &str =
+    "The screen locker has died. Run `jay unlock` from another VT to unlock the session.";
+
 pub struct XWaylandState {
     pub enabled: Cell<bool>,
     pub handler: RefCell<Option<SpawnedFuture<()>>>,
@@ -156,6 +164,16 @@ pub struct IdleState {
     pub timeout_changed: Cell<bool>,
     pub inhibitors: CopyHashMap<IdleInhibitorId, Rc<ZwpIdleInhibitorV1>>,
     pub inhibitors_changed: Cell<bool>,
+    pub stages: CopyHashMap<u64, Rc<IdleStage>>,
+    pub stages_changed: Cell<bool>,
+}
+
+pub struct IdleStage {
+    pub id: u64,
+    pub name: Rc<String>,
+    pub timeout: Cell<Duration>,
+    pub disable_outputs: Cell<bool>,
+    pub active: Cell<bool>,
 }
 
 impl IdleState {
@@ -176,6 +194,21 @@ impl IdleState {
         self.inhibitors_changed.set(true);
         self.change.trigger();
     }
+
+    pub fn add_stage(&self, stage: &Rc<IdleStage>) {
+        self.stages.set(stage.id, stage.clone());
+        self.stages_changed();
+    }
+
+    pub fn remove_stage(&self, stage: &IdleStage) {
+        self.stages.remove(&stage.id);
+        self.stages_changed();
+    }
+
+    pub fn stages_changed(&self) {
+        self.stages_changed.set(true);
+        self.change.trigger();
+    }
 }
 
 pub struct InputDeviceData {
@@ -189,6 +222,7 @@ pub struct DeviceHandlerData {
     pub seat: CloneCell<Option<Rc<WlSeatGlobal>>>,
     pub px_per_scroll_wheel: Cell<f64>,
     pub device: Rc<dyn InputDevice>,
+    pub keymap: CloneCell<Option<Rc<XkbKeymap>>>,
 }
 
 pub struct ConnectorData {
@@ -378,6 +412,11 @@ impl State {
         }
     }
 
+    pub fn set_cursor_theme(&self, theme: Option<String>) {
+        *self.cursor_theme.borrow_mut() = theme;
+        self.reload_cursors();
+    }
+
     fn reload_cursors(&self) {
         if let Some(ctx) = self.render_ctx.get() {
             let cursors = match ServerCursors::load(&ctx, self) {
@@ -449,17 +488,94 @@ impl State {
         } else {
             let container =
                 ContainerNode::new(self, ws, ws.clone(), node, ContainerSplit::Horizontal);
+            container.set_layout(ws.layout.get());
             ws.set_container(&container);
         }
     }
 
+    /// Maps a new toplevel according to the placement intent of the process that spawned
+    /// it.
+    pub fn map_placed(
+        self: &Rc<Self>,
+        node: Rc<dyn ToplevelNode>,
+        intent: &PlacementIntent,
+        wants_floating: bool,
+        float_size: Option<(i32, i32)>,
+    ) {
+        let output = intent
+            .output
+            .and_then(|c| self.outputs.get(&c))
+            .map(|o| o.node.clone());
+        let ws = match &intent.workspace {
+            Some(name) => match self.workspaces.get(name.as_str()) {
+                Some(ws) => Some(ws),
+                _ => {
+                    let output = output
+                        .or_else(|| self.seat_queue.last().map(|s| s.get_output()))
+                        .filter(|o| !o.is_dummy);
+                    output.map(|o| o.create_workspace(name))
+                }
+            },
+            _ => output.map(|o| o.ensure_workspace()),
+        };
+        let floating = intent.floating.unwrap_or(wants_floating || intent.sticky);
+        let ws = match (ws, floating) {
+            (Some(ws), _) => ws,
+            (_, true) => self.float_map_ws(),
+            (_, false) => {
+                self.map_tiled(node);
+                return;
+            }
+        };
+        if floating {
+            let (width, height) = float_size.unwrap_or_else(|| node.tl_data().float_size(&ws));
+            let float = self.map_floating(node.clone(), width, height, &ws);
+            float.sticky.set(intent.sticky);
+        } else {
+            self.map_tiled_on(node.clone(), &ws);
+        }
+        if node.node_visible() {
+            if let Some(seat) = self.seat_queue.last() {
+                node.node_do_focus(&seat, Direction::Unspecified);
+            }
+        }
+    }
+
+    /// Applies the placement intent that a client presented for one of its toplevels.
+    ///
+    /// If the toplevel has not yet been mapped, the intent is applied when it is mapped.
+    pub fn apply_placement(
+        self: &Rc<Self>,
+        node: Rc<dyn ToplevelNode>,
+        intent: Rc<PlacementIntent>,
+    ) {
+        let data = node.tl_data();
+        let parent = match data.parent.get() {
+            Some(p) => p,
+            _ => {
+                data.placement.set(Some(intent));
+                return;
+            }
+        };
+        if data.is_fullscreen.get() || data.is_minimized() {
+            return;
+        }
+        let floating = data.is_floating.get();
+        let float_size = match floating {
+            true => Some((data.float_width.get(), data.float_height.get())),
+            false => None,
+        };
+        parent.cnode_remove_child2(node.tl_as_node(), true);
+        self.map_placed(node, &intent, floating, float_size);
+    }
+
     pub fn map_floating(
         self: &Rc<Self>,
         node: Rc<dyn ToplevelNode>,
         mut width: i32,
         mut height: i32,
         workspace: &Rc<WorkspaceNode>,
-    ) {
+    ) -> Rc<FloatNode> {
         node.clone().tl_set_workspace(workspace);
         width += 2 * self.theme.sizes.border_width.get();
         height += 2 * self.theme.sizes.border_width.get() + self.theme.sizes.title_height.get();
@@ -480,7 +596,7 @@ impl State {
             }
             Rect::new_sized(x1, y1, width, height).unwrap()
         };
-        FloatNode::new(self, workspace, position, node);
+        FloatNode::new(self, workspace, position, node)
     }
 
     pub fn show_workspace(&self, seat: &Rc<WlSeatGlobal>, name: &str) {
@@ -527,6 +643,106 @@ impl State {
         self.dummy_output.get().unwrap().ensure_workspace()
     }
 
+    pub fn move_to_scratchpad(self: &Rc<Self>, tl: Rc<dyn ToplevelNode>) {
+        let scratchpad = match self.scratchpad.get() {
+            Some(ws) => ws,
+            _ => return,
+        };
+        let data = tl.tl_data();
+        if data.is_fullscreen.get() {
+            return;
+        }
+        let old_ws = match data.workspace.get() {
+            Some(ws) => ws,
+            _ => return,
+        };
+        if old_ws.id == scratchpad.id {
+            return;
+        }
+        let cn = match data
+            .parent
+            .get()
+            .and_then(|p| p.node_into_containing_node())
+        {
+            Some(cn) => cn,
+            _ => return,
+        };
+        let (width, height) = data.float_size(&old_ws);
+        let kb_foci = collect_kb_foci(tl.clone().tl_into_node());
+        cn.cnode_remove_child2(tl.tl_as_node(), true);
+        for seat in kb_foci {
+            old_ws.clone().node_do_focus(&seat, Direction::Unspecified);
+        }
+        data.in_scratchpad.set(true);
+        let bw = self.theme.sizes.border_width.get();
+        let th = self.theme.sizes.title_height.get();
+        let position = Rect::new_sized(0, 0, width + 2 * bw, height + 2 * bw + th).unwrap();
+        FloatNode::new(self, &scratchpad, position, tl);
+    }
+
+    pub fn toggle_scratchpad(self: &Rc<Self>, seat: &Rc<WlSeatGlobal>, filter: Option<&str>) {
+        let output = seat.get_output();
+        if output.is_dummy {
+            return;
+        }
+        let ws = output.ensure_workspace();
+        let mut shown = vec![];
+        let mut hidden = None;
+        for stacked in self.root.stacked.iter() {
+            let float = match stacked
+                .deref()
+                .clone()
+                .stacked_into_node()
+                .node_into_float()
+            {
+                Some(f) => f,
+                _ => continue,
+            };
+            let tl = match float.child.get() {
+                Some(tl) => tl,
+                _ => continue,
+            };
+            let data = tl.tl_data();
+            if !data.in_scratchpad.get() {
+                continue;
+            }
+            if let Some(filter) = filter {
+                if !data.title.borrow().contains(filter) {
+                    continue;
+                }
+            }
+            if float.workspace.get().id == ws.id {
+                shown.push(tl);
+            } else if hidden.is_none() {
+                hidden = Some(tl);
+            }
+        }
+        if !shown.is_empty() {
+            for tl in shown {
+                self.move_to_scratchpad(tl);
+            }
+            return;
+        }
+        let tl = match hidden {
+            Some(tl) => tl,
+            _ => return,
+        };
+        let data = tl.tl_data();
+        let cn = match data
+            .parent
+            .get()
+            .and_then(|p| p.node_into_containing_node())
+        {
+            Some(cn) => cn,
+            _ => return,
+        };
+        cn.cnode_remove_child2(tl.tl_as_node(), true);
+        let (width, height) = data.float_size(&ws);
+        self.map_floating(tl.clone(), width, height, &ws);
+        tl.tl_into_node()
+            .node_do_focus(seat, Direction::Unspecified);
+    }
+
     pub fn set_status(&self, status: &str) {
         let status = Rc::new(status.to_owned());
         self.status.set(status.clone());
@@ -537,6 +753,30 @@ impl State {
         self.damage();
     }
 
+    pub fn set_lock_fallback_text(&self, text: &str) {
+        self.lock.fallback_text.set(Rc::new(text.to_owned()));
+        if self.lock.locked.get() {
+            for output in self.root.outputs.lock().values() {
+                output.schedule_update_render_data();
+            }
+            self.damage();
+        }
+    }
+
+    pub fn locker_died(&self) {
+        if !self.lock.locked.get() {
+            return;
+        }
+        log::warn!("The screen locker died while the session was locked");
+        for output in self.root.outputs.lock().values() {
+            output.schedule_update_render_data();
+        }
+        self.damage();
+        if let Some(config) = self.config.get() {
+            config.locker_died();
+        }
+    }
+
     pub fn input_occurred(&self) {
         if !self.idle.input.replace(true) {
             self.idle.change.trigger();
@@ -600,6 +840,7 @@ impl State {
         self.xwayland.handler.borrow_mut().take();
         self.xwayland.queue.clear();
         self.idle.inhibitors.clear();
+        self.idle.stages.clear();
         self.idle.change.clear();
         for (_, drm_dev) in self.drm_devs.lock().drain() {
             drm_dev.handler.take();
@@ -637,6 +878,9 @@ impl State {
         if let Some(output) = self.dummy_output.set(None) {
             output.clear();
         }
+        if let Some(ws) = self.scratchpad.set(None) {
+            ws.clear();
+        }
         self.wheel.clear();
         self.eng.clear();
     }
@@ -13,7 +13,10 @@ use {
     },
     std::rc::Rc,
 };
-pub use {hardware_cursor::handle_hardware_cursor_tick, idle::idle};
+pub use {
+    hardware_cursor::handle_hardware_cursor_tick,
+    idle::{idle, idle_notification},
+};
 
 pub async fn handle_backend_events(state: Rc<State>) {
     let mut beh = BackendEventHandler { state };
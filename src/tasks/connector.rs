@@ -116,6 +116,7 @@ impl ConnectorHandler {
                 captured_inactive_workspaces: Default::default(),
                 titles: Default::default(),
                 status: None,
+                lock_fallback: None,
             }),
             state: self.state.clone(),
             is_dummy: false,
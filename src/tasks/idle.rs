@@ -1,6 +1,7 @@
 use {
     crate::{
         backend::Backend,
+        ifs::ext_idle_notification_v1::ExtIdleNotificationV1,
         state::State,
         utils::{
             errorfmt::ErrorFmt,
@@ -13,9 +14,6 @@ use {
 };
 
 pub async fn idle(state: Rc<State>, backend: Rc<dyn Backend>) {
-    if !backend.supports_idle() {
-        return;
-    }
     let timer = match TimerFd::new(c::CLOCK_MONOTONIC) {
         Ok(t) => t,
         Err(e) => {
@@ -27,9 +25,11 @@ pub async fn idle(state: Rc<State>, backend: Rc<dyn Backend>) {
     state.idle.timeout_changed.set(true);
     let mut idle = Idle {
         state,
+        supports_idle: backend.supports_idle(),
         backend,
         timer,
         idle: false,
+        outputs_disabled: false,
         dead: false,
         is_inhibited: false,
         last_input: now(),
@@ -40,8 +40,10 @@ pub async fn idle(state: Rc<State>, backend: Rc<dyn Backend>) {
 struct Idle {
     state: Rc<State>,
     backend: Rc<dyn Backend>,
+    supports_idle: bool,
     timer: TimerFd,
     idle: bool,
+    outputs_disabled: bool,
     dead: bool,
     is_inhibited: bool,
     last_input: c::timespec,
@@ -64,49 +66,114 @@ impl Idle {
             self.dead = true;
             return;
         }
-        let timeout = self.state.idle.timeout.get();
-        let since = duration_since(self.last_input);
-        if since >= timeout {
-            if !timeout.is_zero() && !self.is_inhibited {
-                if let Some(config) = self.state.config.get() {
-                    config.idle();
-                }
-                self.backend.set_idle(true);
-                self.idle = true;
-            }
-        } else {
-            self.program_timer2(timeout - since);
-        }
+        self.update_deadlines();
     }
 
     fn handle_idle_changes(&mut self) {
+        let mut update = false;
         if self.state.idle.inhibitors_changed.replace(false) {
             let is_inhibited = self.state.idle.inhibitors.len() > 0;
             if self.is_inhibited != is_inhibited {
                 self.is_inhibited = is_inhibited;
+                for seat in self.state.globals.seats.lock().values() {
+                    seat.idle_inhibited_changed();
+                }
                 if !self.is_inhibited {
-                    self.program_timer();
+                    self.last_input = now();
+                    update = true;
                 }
             }
         }
         if self.state.idle.timeout_changed.replace(false) {
-            self.program_timer();
+            update = true;
+        }
+        if self.state.idle.stages_changed.replace(false) {
+            update = true;
         }
         if self.state.idle.input.replace(false) {
             self.last_input = now();
-            if self.idle {
-                self.backend.set_idle(false);
-                self.idle = false;
-                self.program_timer();
+            if self.resume() {
+                update = true;
             }
         }
+        if update {
+            self.update_deadlines();
+        }
+    }
+
+    fn resume(&mut self) -> bool {
+        let mut resumed = self.idle;
+        self.idle = false;
+        let config = self.state.config.get();
+        for stage in self.state.idle.stages.lock().values() {
+            if stage.active.replace(false) {
+                resumed = true;
+                if let Some(config) = &config {
+                    config.idle_stage_resumed(stage.id);
+                }
+            }
+        }
+        resumed
     }
 
-    fn program_timer(&mut self) {
-        self.program_timer2(self.state.idle.timeout.get());
+    fn update_deadlines(&mut self) {
+        let since = duration_since(self.last_input);
+        let config = self.state.config.get();
+        let mut next = None::<Duration>;
+        let mut update_next = |timeout: Duration| {
+            let remaining = timeout - since;
+            if next.map(|n| remaining < n).unwrap_or(true) {
+                next = Some(remaining);
+            }
+        };
+        let timeout = self.state.idle.timeout.get();
+        if !timeout.is_zero() {
+            if since < timeout {
+                update_next(timeout);
+            } else if !self.idle && !self.is_inhibited {
+                if let Some(config) = &config {
+                    config.idle();
+                }
+                self.idle = true;
+            }
+        }
+        let mut stages: Vec<_> = self.state.idle.stages.lock().values().cloned().collect();
+        stages.sort_by_key(|s| s.timeout.get());
+        for stage in &stages {
+            let timeout = stage.timeout.get();
+            if timeout.is_zero() {
+                if stage.active.replace(false) {
+                    if let Some(config) = &config {
+                        config.idle_stage_resumed(stage.id);
+                    }
+                }
+                continue;
+            }
+            if since < timeout {
+                update_next(timeout);
+            } else if !stage.active.get() && !self.is_inhibited {
+                stage.active.set(true);
+                if let Some(config) = &config {
+                    config.idle_stage_entered(stage.id);
+                }
+            }
+        }
+        let disable_outputs = self.idle
+            || stages
+                .iter()
+                .any(|s| s.active.get() && s.disable_outputs.get());
+        if self.outputs_disabled != disable_outputs {
+            self.outputs_disabled = disable_outputs;
+            if self.supports_idle {
+                self.backend.set_idle(disable_outputs);
+            }
+        }
+        if let Some(next) = next {
+            self.program_timer(next);
+        }
     }
 
-    fn program_timer2(&mut self, timeout: Duration) {
+    fn program_timer(&mut self, timeout: Duration) {
         if let Err(e) = self.timer.program(Some(timeout), None) {
             log::error!("Could not program idle timer: {}", ErrorFmt(e));
             self.dead = true;
@@ -114,6 +181,34 @@ impl Idle {
     }
 }
 
+pub async fn idle_notification(notification: Rc<ExtIdleNotificationV1>) {
+    let state = notification.client.state.clone();
+    loop {
+        if state.idle.inhibitors.len() > 0 {
+            // The timeout starts over once the inhibitors are gone.
+            select! {
+                _ = notification.inhibited_changed.triggered().fuse() => {},
+                _ = notification.activity.triggered().fuse() => {},
+            }
+            continue;
+        }
+        if notification.timeout_ms > 0 {
+            let res = select! {
+                res = state.wheel.timeout(notification.timeout_ms).fuse() => res,
+                _ = notification.inhibited_changed.triggered().fuse() => continue,
+                _ = notification.activity.triggered().fuse() => continue,
+            };
+            if let Err(e) = res {
+                log::error!("Could not wait for idle timeout: {}", ErrorFmt(e));
+                return;
+            }
+        }
+        notification.send_idled();
+        notification.activity.triggered().await;
+        notification.send_resumed();
+    }
+}
+
 fn now() -> c::timespec {
     let mut now = uapi::pod_zeroed();
     let _ = uapi::clock_gettime(c::CLOCK_MONOTONIC, &mut now);
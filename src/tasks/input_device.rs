@@ -13,6 +13,7 @@ pub fn handle(state: &Rc<State>, dev: Rc<dyn InputDevice>) {
         seat: Default::default(),
         px_per_scroll_wheel: Cell::new(PX_PER_SCROLL),
         device: dev.clone(),
+        keymap: Default::default(),
     });
     let ae = Rc::new(AsyncEvent::default());
     let oh = DeviceHandler {
@@ -61,6 +62,7 @@ impl DeviceHandler {
                 }
                 if any_events {
                     seat.mark_last_active();
+                    seat.idle_activity();
                     self.state.input_occurred();
                 }
             } else {
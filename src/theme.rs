@@ -17,6 +17,13 @@ fn to_u8(c: f32) -> u8 {
 }
 
 impl Color {
+    pub const TRANSPARENT: Self = Self {
+        r: 0.0,
+        g: 0.0,
+        b: 0.0,
+        a: 0.0,
+    };
+
     pub fn from_gray(g: u8) -> Self {
         Self::from_rgb(g, g, g)
     }
@@ -173,6 +180,8 @@ macro_rules! sizes {
 sizes! {
     title_height = (1, 1000, 17),
     border_width = (1, 1000, 4),
+    inner_gap = (0, 1000, 0),
+    outer_gap = (0, 1000, 0),
 }
 
 pub const DEFAULT_FONT: &str = "monospace 8";
@@ -181,6 +190,8 @@ pub struct Theme {
     pub colors: ThemeColors,
     pub sizes: ThemeSizes,
     pub font: RefCell<String>,
+    pub smart_gaps: Cell<bool>,
+    pub hide_titles: Cell<bool>,
 }
 
 impl Default for Theme {
@@ -189,6 +200,8 @@ impl Default for Theme {
             colors: Default::default(),
             sizes: Default::default(),
             font: RefCell::new(DEFAULT_FONT.to_string()),
+            smart_gaps: Cell::new(false),
+            hide_titles: Cell::new(false),
         }
     }
 }
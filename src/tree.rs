@@ -19,8 +19,8 @@ use {
     },
 };
 pub use {
-    container::*, containing::*, display::*, float::*, output::*, placeholder::*, stacked::*,
-    toplevel::*, walker::*, workspace::*,
+    container::*, containing::*, display::*, float::*, output::*, placeholder::*, placement::*,
+    stacked::*, toplevel::*, walker::*, window_menu::*, workspace::*,
 };
 
 mod container;
@@ -29,9 +29,11 @@ mod display;
 mod float;
 mod output;
 mod placeholder;
+mod placement;
 mod stacked;
 mod toplevel;
 mod walker;
+mod window_menu;
 mod workspace;
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
@@ -27,7 +27,7 @@ use {
         },
     },
     ahash::AHashMap,
-    jay_config::Axis,
+    jay_config::{Axis, ContainerLayout as ConfigLayout},
     smallvec::SmallVec,
     std::{
         cell::{Cell, RefCell},
@@ -63,6 +63,36 @@ impl Into<Axis> for ContainerSplit {
     }
 }
 
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum ContainerLayout {
+    Split,
+    MasterStack,
+    Spiral,
+    Columns,
+}
+
+impl From<ConfigLayout> for ContainerLayout {
+    fn from(l: ConfigLayout) -> Self {
+        match l {
+            ConfigLayout::Split => Self::Split,
+            ConfigLayout::MasterStack => Self::MasterStack,
+            ConfigLayout::Spiral => Self::Spiral,
+            ConfigLayout::Columns => Self::Columns,
+        }
+    }
+}
+
+impl Into<ConfigLayout> for ContainerLayout {
+    fn into(self) -> ConfigLayout {
+        match self {
+            ContainerLayout::Split => ConfigLayout::Split,
+            ContainerLayout::MasterStack => ConfigLayout::MasterStack,
+            ContainerLayout::Spiral => ConfigLayout::Spiral,
+            ContainerLayout::Columns => ConfigLayout::Columns,
+        }
+    }
+}
+
 #[allow(dead_code)]
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub enum ContainerFocus {
@@ -93,6 +123,7 @@ pub struct ContainerNode {
     pub id: ContainerNodeId,
     pub parent: CloneCell<Rc<dyn ContainingNode>>,
     pub split: Cell<ContainerSplit>,
+    pub layout: Cell<ContainerLayout>,
     pub mono_child: CloneCell<Option<NodeRef<ContainerChild>>>,
     pub mono_body: Cell<Rect>,
     pub mono_content: Cell<Rect>,
@@ -114,6 +145,7 @@ pub struct ContainerNode {
     state: Rc<State>,
     pub render_data: RefCell<ContainerRenderData>,
     scroller: Scroller,
+    hide_titles: Cell<Option<bool>>,
     toplevel_data: ToplevelData,
 }
 
@@ -189,6 +221,7 @@ impl ContainerNode {
             id: state.node_ids.next(),
             parent: CloneCell::new(parent.clone()),
             split: Cell::new(split),
+            layout: Cell::new(ContainerLayout::Split),
             mono_child: CloneCell::new(None),
             mono_body: Cell::new(Default::default()),
             mono_content: Cell::new(Default::default()),
@@ -210,6 +243,7 @@ impl ContainerNode {
             state: state.clone(),
             render_data: Default::default(),
             scroller: Default::default(),
+            hide_titles: Cell::new(None),
             toplevel_data: ToplevelData::new(state, Default::default(), None),
         });
         slf.tl_set_parent(parent);
@@ -318,6 +352,16 @@ impl ContainerNode {
         // log::info!("add_child");
         self.schedule_layout();
         self.cancel_seat_ops();
+        self.update_workspace_extents();
+    }
+
+    /// Lets the workspace re-compute the extents of this container if this container is
+    /// the top-level container of the workspace. The outer gaps depend on the children
+    /// of the container if smart gaps are enabled.
+    fn update_workspace_extents(&self) {
+        if let Some(ws) = self.parent.get().node_into_workspace() {
+            ws.update_container_extents();
+        }
     }
 
     fn cancel_seat_ops(&self) {
@@ -352,7 +396,10 @@ impl ContainerNode {
         if let Some(child) = self.mono_child.get() {
             self.perform_mono_layout(&child);
         } else {
-            self.perform_split_layout();
+            match self.layout.get() {
+                ContainerLayout::Split => self.perform_split_layout(),
+                layout => self.perform_tile_layout(layout),
+            }
         }
         self.state.tree_changed();
         // log::info!("perform_layout");
@@ -368,10 +415,10 @@ impl ContainerNode {
         self.mono_content
             .set(child.content.get().at_point(mb.x1(), mb.y1()));
 
-        let th = self.state.theme.sizes.title_height.get();
-        let bw = self.state.theme.sizes.border_width.get();
+        let th = self.title_height();
+        let spacing = self.spacing();
         let num_children = self.num_children.get() as i32;
-        let content_width = self.width.get().sub(bw * (num_children - 1)).max(0);
+        let content_width = self.width.get().sub(spacing * (num_children - 1)).max(0);
         let width_per_child = content_width / num_children;
         let mut rem = content_width % num_children;
         let mut pos = 0;
@@ -384,14 +431,15 @@ impl ContainerNode {
             child
                 .title_rect
                 .set(Rect::new_sized(pos, 0, width, th).unwrap());
-            pos += width + bw;
+            pos += width + spacing;
         }
     }
 
     fn perform_split_layout(self: &Rc<Self>) {
         let sum_factors = self.sum_factors.get();
-        let border_width = self.state.theme.sizes.border_width.get();
-        let title_height = self.state.theme.sizes.title_height.get();
+        let spacing = self.spacing();
+        let title_height = self.title_height();
+        let title_area = self.title_area();
         let split = self.split.get();
         let (content_size, other_content_size) = match split {
             ContainerSplit::Horizontal => (self.content_width.get(), self.content_height.get()),
@@ -410,16 +458,14 @@ impl ContainerNode {
             body_size = body_size.min(remaining_content_size);
             remaining_content_size -= body_size;
             let (x1, y1, width, height) = match split {
-                ContainerSplit::Horizontal => {
-                    (pos, title_height + 1, body_size, other_content_size)
-                }
-                _ => (0, pos + title_height + 1, other_content_size, body_size),
+                ContainerSplit::Horizontal => (pos, title_area, body_size, other_content_size),
+                _ => (0, pos + title_area, other_content_size, body_size),
             };
             let body = Rect::new_sized(x1, y1, width, height).unwrap();
             child.body.set(body);
-            pos += body_size + border_width;
+            pos += body_size + spacing;
             if split == ContainerSplit::Vertical {
-                pos += title_height + 1;
+                pos += title_area;
             }
         }
         if remaining_content_size > 0 {
@@ -436,24 +482,18 @@ impl ContainerNode {
                 let (x1, y1, width, height, size) = match split {
                     ContainerSplit::Horizontal => {
                         let width = body.width() + add;
-                        (pos, title_height + 1, width, other_content_size, width)
+                        (pos, title_area, width, other_content_size, width)
                     }
                     _ => {
                         let height = body.height() + add;
-                        (
-                            0,
-                            pos + title_height + 1,
-                            other_content_size,
-                            height,
-                            height,
-                        )
+                        (0, pos + title_area, other_content_size, height, height)
                     }
                 };
                 body = Rect::new_sized(x1, y1, width, height).unwrap();
                 child.body.set(body);
-                pos += size + border_width;
+                pos += size + spacing;
                 if split == ContainerSplit::Vertical {
-                    pos += title_height + 1;
+                    pos += title_area;
                 }
             }
         }
@@ -463,7 +503,7 @@ impl ContainerNode {
             child.title_rect.set(
                 Rect::new_sized(
                     body.x1(),
-                    body.y1() - title_height - 1,
+                    body.y1() - title_area,
                     body.width(),
                     title_height,
                 )
@@ -475,22 +515,112 @@ impl ContainerNode {
         }
     }
 
+    fn perform_tile_layout(self: &Rc<Self>, layout: ContainerLayout) {
+        let spacing = self.spacing();
+        let title_height = self.title_height();
+        let title_area = self.title_area();
+        let area = Rect::new_sized(0, 0, self.width.get(), self.height.get()).unwrap();
+        let num_children = self.num_children.get();
+        let mut tiles = Vec::with_capacity(num_children);
+        match layout {
+            ContainerLayout::MasterStack => {
+                master_stack_tiles(area, num_children, spacing, &mut tiles)
+            }
+            ContainerLayout::Spiral => spiral_tiles(area, num_children, spacing, &mut tiles),
+            _ => split_evenly(
+                area,
+                ContainerSplit::Horizontal,
+                num_children,
+                spacing,
+                &mut tiles,
+            ),
+        }
+        for (child, tile) in self.children.iter().zip(tiles) {
+            let body = Rect::new_sized(
+                tile.x1(),
+                tile.y1() + title_area,
+                tile.width(),
+                (tile.height() - title_area).max(0),
+            )
+            .unwrap();
+            child.body.set(body);
+            child
+                .title_rect
+                .set(Rect::new_sized(tile.x1(), tile.y1(), tile.width(), title_height).unwrap());
+            let body = body.move_(self.abs_x1.get(), self.abs_y1.get());
+            child.node.clone().tl_change_extents(&body);
+            child.position_content();
+        }
+    }
+
+    pub fn set_layout(self: &Rc<Self>, layout: ContainerLayout) {
+        if self.layout.replace(layout) != layout {
+            self.schedule_layout();
+            self.update_title();
+        }
+    }
+
+    fn spacing(&self) -> i32 {
+        let sizes = &self.state.theme.sizes;
+        sizes.border_width.get() + sizes.inner_gap.get()
+    }
+
+    pub fn hides_titles(&self) -> bool {
+        self.hide_titles
+            .get()
+            .unwrap_or_else(|| self.state.theme.hide_titles.get())
+    }
+
+    pub fn set_hide_titles(self: &Rc<Self>, hide: bool) {
+        self.hide_titles.set(Some(hide));
+        self.update_content_size();
+        self.schedule_layout();
+    }
+
+    fn titles_hidden(&self) -> bool {
+        self.hides_titles() && (self.mono_child.get().is_some() || self.num_children.get() == 1)
+    }
+
+    fn title_height(&self) -> i32 {
+        match self.titles_hidden() {
+            true => 0,
+            false => self.state.theme.sizes.title_height.get(),
+        }
+    }
+
+    fn title_area(&self) -> i32 {
+        match self.titles_hidden() {
+            true => 0,
+            false => self.state.theme.sizes.title_height.get() + 1,
+        }
+    }
+
+    pub fn is_single_window(&self) -> bool {
+        if self.num_children.get() != 1 {
+            return false;
+        }
+        match self.children.first() {
+            Some(c) => !c.node.node_is_container(),
+            None => false,
+        }
+    }
+
     fn update_content_size(&self) {
-        let border_width = self.state.theme.sizes.border_width.get();
-        let title_height = self.state.theme.sizes.title_height.get();
+        let spacing = self.spacing();
+        let title_area = self.title_area();
         let nc = self.num_children.get();
         match self.split.get() {
             ContainerSplit::Horizontal => {
-                let new_content_size = self.width.get().sub((nc - 1) as i32 * border_width).max(0);
+                let new_content_size = self.width.get().sub((nc - 1) as i32 * spacing).max(0);
                 self.content_width.set(new_content_size);
                 self.content_height
-                    .set(self.height.get().sub(title_height + 1).max(0));
+                    .set(self.height.get().sub(title_area).max(0));
             }
             ContainerSplit::Vertical => {
                 let new_content_size = self
                     .height
                     .get()
-                    .sub(title_height + 1 + (nc - 1) as i32 * (border_width + title_height + 1))
+                    .sub(title_area + (nc - 1) as i32 * (spacing + title_area))
                     .max(0);
                 self.content_height.set(new_content_size);
                 self.content_width.set(self.width.get());
@@ -499,16 +629,16 @@ impl ContainerNode {
         self.mono_body.set(
             Rect::new_sized(
                 0,
-                title_height + 1,
+                title_area,
                 self.width.get(),
-                self.height.get().sub(title_height + 1).max(0),
+                self.height.get().sub(title_area).max(0),
             )
             .unwrap(),
         );
     }
 
     fn pointer_move(self: &Rc<Self>, seat: &Rc<WlSeatGlobal>, mut x: i32, mut y: i32) {
-        let title_height = self.state.theme.sizes.title_height.get();
+        let title_area = self.title_area();
         let mut seats = self.seats.borrow_mut();
         let seat_state = seats.entry(seat.id()).or_insert_with(|| SeatState {
             cursor: KnownCursor::Default,
@@ -570,10 +700,12 @@ impl ContainerNode {
             }
             return;
         }
-        let new_cursor = if self.mono_child.get().is_some() {
+        let resizable =
+            self.mono_child.get().is_none() && self.layout.get() == ContainerLayout::Split;
+        let new_cursor = if !resizable {
             KnownCursor::Default
         } else if self.split.get() == ContainerSplit::Horizontal {
-            if y < title_height + 1 {
+            if y < title_area {
                 KnownCursor::Default
             } else {
                 KnownCursor::ResizeLeftRight
@@ -583,7 +715,7 @@ impl ContainerNode {
             for child in self.children.iter() {
                 let body = child.body.get();
                 if body.y1() > y {
-                    if body.y1() - y > title_height + 1 {
+                    if body.y1() - y > title_area {
                         cursor = KnownCursor::ResizeTopBottom
                     }
                     break;
@@ -601,10 +733,15 @@ impl ContainerNode {
     fn update_title(self: &Rc<Self>) {
         let mut title = self.toplevel_data.title.borrow_mut();
         title.clear();
-        let split = match (self.mono_child.get().is_some(), self.split.get()) {
+        let split = match (self.mono_child.get().is_some(), self.layout.get()) {
             (true, _) => "T",
-            (_, ContainerSplit::Horizontal) => "H",
-            (_, ContainerSplit::Vertical) => "V",
+            (_, ContainerLayout::MasterStack) => "M",
+            (_, ContainerLayout::Spiral) => "S",
+            (_, ContainerLayout::Columns) => "C",
+            (_, ContainerLayout::Split) => match self.split.get() {
+                ContainerSplit::Horizontal => "H",
+                ContainerSplit::Vertical => "V",
+            },
         };
         title.push_str(split);
         title.push_str("[");
@@ -630,7 +767,8 @@ impl ContainerNode {
         let mut rd = self.render_data.borrow_mut();
         let rd = rd.deref_mut();
         let theme = &self.state.theme;
-        let th = theme.sizes.title_height.get();
+        let th = self.title_height();
+        let hidden = self.titles_hidden();
         let bw = theme.sizes.border_width.get();
         let font = theme.font.borrow_mut();
         let cwidth = self.width.get();
@@ -647,11 +785,25 @@ impl ContainerNode {
         let last_active = self.focus_history.last().map(|v| v.node.node_id());
         let mono = self.mono_child.get().is_some();
         let split = self.split.get();
+        let tiled = !mono && self.layout.get() != ContainerLayout::Split;
         let have_active = self.children.iter().any(|c| c.active.get());
         let scales = self.state.scales.lock();
         for (i, child) in self.children.iter().enumerate() {
             let rect = child.title_rect.get();
-            if i > 0 {
+            if tiled {
+                let body = child.body.get();
+                let tile = Rect::new(rect.x1(), rect.y1(), body.x2(), body.y2()).unwrap();
+                if tile.x1() > 0 {
+                    rd.border_rects.push(
+                        Rect::new_sized(tile.x1() - bw, tile.y1(), bw, tile.height()).unwrap(),
+                    );
+                }
+                if tile.y1() > 0 {
+                    rd.border_rects.push(
+                        Rect::new_sized(tile.x1(), tile.y1() - bw, tile.width(), bw).unwrap(),
+                    );
+                }
+            } else if i > 0 && !hidden {
                 let rect = if mono {
                     Rect::new_sized(rect.x1() - bw, 0, bw, th)
                 } else if split == ContainerSplit::Horizontal {
@@ -671,7 +823,7 @@ impl ContainerNode {
                 rd.title_rects.push(rect);
                 theme.colors.unfocused_title_text.get()
             };
-            if !mono {
+            if !mono && !hidden {
                 let rect = Rect::new_sized(rect.x1(), rect.y2(), rect.width(), 1).unwrap();
                 rd.underline_rects.push(rect);
             }
@@ -706,7 +858,7 @@ impl ContainerNode {
                 }
             }
         }
-        if mono {
+        if mono && !hidden {
             rd.underline_rects
                 .push(Rect::new_sized(0, th, cwidth, 1).unwrap());
         }
@@ -780,6 +932,7 @@ impl ContainerNode {
             }
         }
         self.mono_child.set(child);
+        self.update_content_size();
         // log::info!("set_mono");
         self.schedule_layout();
         self.update_title();
@@ -911,6 +1064,69 @@ impl ContainerNode {
         }
     }
 
+    /// Moves the edge of `child` that faces `direction` by `delta` pixels.
+    ///
+    /// The space is taken from or given to the neighbor of the child in that direction. If
+    /// this container cannot resize the child along that axis, the request is forwarded
+    /// to the parent container. Containers that don't use the split layout cannot resize
+    /// their children.
+    pub fn resize_child(
+        self: &Rc<Self>,
+        child: &dyn Node,
+        direction: Direction,
+        delta: i32,
+    ) -> bool {
+        let (split, prev) = direction_to_split(direction);
+        if self.layout.get() != ContainerLayout::Split {
+            return false;
+        }
+        if direction != Direction::Unspecified
+            && split == self.split.get()
+            && self.mono_child.get().is_none()
+        {
+            let cc = self
+                .child_nodes
+                .borrow()
+                .get(&child.node_id())
+                .map(|l| l.to_ref());
+            let neighbor = cc.as_ref().and_then(|cc| match prev {
+                true => cc.prev(),
+                false => cc.next(),
+            });
+            if let (Some(cc), Some(neighbor)) = (cc, neighbor) {
+                let content_size = match split {
+                    ContainerSplit::Horizontal => self.content_width.get(),
+                    ContainerSplit::Vertical => self.content_height.get(),
+                };
+                if content_size <= 0 {
+                    return false;
+                }
+                let title_area = self.title_area();
+                let px_per_factor = content_size as f64 / self.sum_factors.get();
+                let child_size = cc.factor.get() * px_per_factor;
+                let total = child_size + neighbor.factor.get() * px_per_factor;
+                let min_size = (title_area as f64).min(total / 2.0);
+                let child_size = (child_size + delta as f64).clamp(min_size, total - min_size);
+                cc.factor.set(child_size / px_per_factor);
+                neighbor.factor.set((total - child_size) / px_per_factor);
+                self.schedule_layout();
+                return true;
+            }
+        }
+        match self.parent.get().node_into_container() {
+            Some(parent) => parent.resize_child(self.deref(), direction, delta),
+            _ => false,
+        }
+    }
+
+    pub fn equalize(self: &Rc<Self>) {
+        for child in self.children.iter() {
+            child.factor.set(1.0);
+        }
+        self.sum_factors.set(self.num_children.get() as f64);
+        self.schedule_layout();
+    }
+
     pub fn insert_child(self: &Rc<Self>, node: Rc<dyn ToplevelNode>, direction: Direction) {
         let (split, right) = direction_to_split(direction);
         if split != self.split.get() || right {
@@ -1115,7 +1331,8 @@ impl Node for ContainerNode {
                 return;
             }
             let (kind, child) = 'res: {
-                let mono = self.mono_child.get().is_some();
+                let split =
+                    self.mono_child.get().is_none() && self.layout.get() == ContainerLayout::Split;
                 for child in self.children.iter() {
                     let rect = child.title_rect.get();
                     if rect.contains(seat_data.x, seat_data.y) {
@@ -1125,7 +1342,7 @@ impl Node for ContainerNode {
                             .clone()
                             .node_do_focus(seat, Direction::Unspecified);
                         break 'res (SeatOpKind::Move, child);
-                    } else if !mono {
+                    } else if split {
                         if self.split.get() == ContainerSplit::Horizontal {
                             if seat_data.x < rect.x1() {
                                 break 'res (
@@ -1169,7 +1386,7 @@ impl Node for ContainerNode {
             Some(s) => s,
             _ => return,
         };
-        if seat_data.y > self.state.theme.sizes.title_height.get() {
+        if seat_data.y >= self.title_area() {
             return;
         }
         let cur_mc = match self.mono_child.get() {
@@ -1283,6 +1500,7 @@ impl ContainingNode for ContainerNode {
             let body = body.move_(self.abs_x1.get(), self.abs_y1.get());
             new.clone().tl_change_extents(&body);
         }
+        self.update_workspace_extents();
     }
 
     fn cnode_remove_child2(self: Rc<Self>, child: &dyn Node, preserve_focus: bool) {
@@ -1336,6 +1554,7 @@ impl ContainingNode for ContainerNode {
         // log::info!("cnode_remove_child2");
         self.schedule_layout();
         self.cancel_seat_ops();
+        self.update_workspace_extents();
     }
 
     fn cnode_accepts_child(&self, _node: &dyn Node) -> bool {
@@ -1439,3 +1658,70 @@ fn direction_to_split(dir: Direction) -> (ContainerSplit, bool) {
         Direction::Unspecified => (ContainerSplit::Horizontal, true),
     }
 }
+
+/// Splits `rect` along `split` into `n` tiles of equal size separated by `spacing`.
+fn split_evenly(rect: Rect, split: ContainerSplit, n: usize, spacing: i32, tiles: &mut Vec<Rect>) {
+    if n == 0 {
+        return;
+    }
+    let n = n as i32;
+    let (mut pos, total) = match split {
+        ContainerSplit::Horizontal => (rect.x1(), rect.width()),
+        ContainerSplit::Vertical => (rect.y1(), rect.height()),
+    };
+    let content = total.sub((n - 1) * spacing).max(0);
+    let size_per = content / n;
+    let mut rem = content % n;
+    for _ in 0..n {
+        let mut size = size_per;
+        if rem > 0 {
+            size += 1;
+            rem -= 1;
+        }
+        let tile = match split {
+            ContainerSplit::Horizontal => Rect::new_sized(pos, rect.y1(), size, rect.height()),
+            ContainerSplit::Vertical => Rect::new_sized(rect.x1(), pos, rect.width(), size),
+        };
+        tiles.push(tile.unwrap());
+        pos += size + spacing;
+    }
+}
+
+/// Splits `rect` along `split` into two halves separated by `spacing`.
+fn split_half(rect: Rect, split: ContainerSplit, spacing: i32) -> (Rect, Rect) {
+    let mut tiles = Vec::with_capacity(2);
+    split_evenly(rect, split, 2, spacing, &mut tiles);
+    (tiles[0], tiles[1])
+}
+
+fn master_stack_tiles(area: Rect, n: usize, spacing: i32, tiles: &mut Vec<Rect>) {
+    if n <= 1 {
+        tiles.push(area);
+        return;
+    }
+    let (master, stack) = split_half(area, ContainerSplit::Horizontal, spacing);
+    tiles.push(master);
+    split_evenly(stack, ContainerSplit::Vertical, n - 1, spacing, tiles);
+}
+
+fn spiral_tiles(area: Rect, n: usize, spacing: i32, tiles: &mut Vec<Rect>) {
+    let mut rest = area;
+    for i in 0..n {
+        if i == n - 1 {
+            tiles.push(rest);
+            break;
+        }
+        let split = match i % 2 {
+            0 => ContainerSplit::Horizontal,
+            _ => ContainerSplit::Vertical,
+        };
+        let (first, second) = split_half(rest, split, spacing);
+        // Every other pair of tiles is taken from the end so that the tiles spiral inwards.
+        let (tile, remaining) = match i % 4 {
+            0 | 1 => (first, second),
+            _ => (second, first),
+        };
+        tiles.push(tile);
+        rest = remaining;
+    }
+}
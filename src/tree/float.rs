@@ -34,6 +34,8 @@ pub struct FloatNode {
     pub state: Rc<State>,
     pub visible: Cell<bool>,
     pub position: Cell<Rect>,
+    pub unmaximized_position: Cell<Option<Rect>>,
+    pub sticky: Cell<bool>,
     pub display_link: Cell<Option<LinkedNode<Rc<dyn StackedNode>>>>,
     pub workspace_link: Cell<Option<LinkedNode<Rc<dyn StackedNode>>>>,
     pub workspace: CloneCell<Rc<WorkspaceNode>>,
@@ -101,6 +103,8 @@ impl FloatNode {
             state: state.clone(),
             visible: Cell::new(ws.stacked_visible()),
             position: Cell::new(position),
+            unmaximized_position: Cell::new(None),
+            sticky: Cell::new(false),
             display_link: Cell::new(None),
             workspace_link: Cell::new(None),
             workspace: CloneCell::new(ws.clone()),
@@ -123,6 +127,9 @@ impl FloatNode {
         child.tl_set_parent(floater.clone());
         child.tl_set_visible(floater.visible.get());
         floater.schedule_layout();
+        if child.tl_data().is_maximized.get() {
+            floater.set_maximized(true);
+        }
         floater
     }
 
@@ -161,6 +168,28 @@ impl FloatNode {
         self.schedule_render_titles();
     }
 
+    pub fn set_position(self: &Rc<Self>, position: Rect) {
+        self.position.set(position);
+        self.schedule_layout();
+    }
+
+    pub fn set_maximized(self: &Rc<Self>, maximized: bool) {
+        let position = if maximized {
+            let position = self.position.get();
+            if self.unmaximized_position.get().is_none() {
+                self.unmaximized_position.set(Some(position));
+            }
+            self.workspace.get().position.get()
+        } else {
+            match self.unmaximized_position.take() {
+                Some(p) => p,
+                _ => return,
+            }
+        };
+        self.set_position(position);
+        self.state.damage();
+    }
+
     pub fn schedule_render_titles(self: &Rc<Self>) {
         if !self.render_titles_scheduled.replace(true) {
             self.state.pending_float_titles.push(self.clone());
@@ -337,13 +366,18 @@ impl FloatNode {
     }
 
     fn set_workspace(self: &Rc<Self>, ws: &Rc<WorkspaceNode>) {
+        self.move_to_workspace(ws);
+        self.stacked_set_visible(ws.stacked_visible());
+    }
+
+    /// Moves the float to another workspace without changing its visibility.
+    pub fn move_to_workspace(self: &Rc<Self>, ws: &Rc<WorkspaceNode>) {
         if let Some(c) = self.child.get() {
             c.tl_set_workspace(ws);
         }
         self.workspace_link
             .set(Some(ws.stacked.add_last(self.clone())));
         self.workspace.set(ws.clone());
-        self.stacked_set_visible(ws.stacked_visible());
     }
 }
 
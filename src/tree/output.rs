@@ -24,7 +24,8 @@ use {
         state::State,
         text,
         tree::{
-            walker::NodeVisitor, Direction, FindTreeResult, FoundNode, Node, NodeId, WorkspaceNode,
+            walker::NodeVisitor, ContainerLayout, Direction, FindTreeResult, FoundNode, Node,
+            NodeId, StackedNode, WorkspaceNode,
         },
         utils::{
             clonecell::CloneCell, copyhashmap::CopyHashMap, errorfmt::ErrorFmt,
@@ -145,6 +146,7 @@ impl OutputNode {
         rd.captured_inactive_workspaces.clear();
         rd.active_workspace = None;
         rd.status = None;
+        rd.lock_fallback = None;
         let mut pos = 0;
         let font = self.state.theme.font.borrow_mut();
         let theme = &self.state.theme;
@@ -257,6 +259,39 @@ impl OutputNode {
                 tex: title,
             });
         }
+        'set_lock_fallback: {
+            if !self.state.lock.locked.get() || self.state.lock.lock.get().is_some() {
+                break 'set_lock_fallback;
+            }
+            let ctx = match self.state.render_ctx.get() {
+                Some(ctx) => ctx,
+                _ => break 'set_lock_fallback,
+            };
+            let text = self.state.lock.fallback_text.get();
+            if text.is_empty() {
+                break 'set_lock_fallback;
+            }
+            let tc = self.state.theme.colors.bar_text.get();
+            let tex = match text::render_fitting(&ctx, None, &font, &text, tc, false, scale) {
+                Ok(t) => t,
+                Err(e) => {
+                    log::error!("Could not render lock fallback text: {}", ErrorFmt(e));
+                    break 'set_lock_fallback;
+                }
+            };
+            let mut width = tex.width();
+            let mut height = tex.height();
+            if let Some(scale) = scale {
+                width = (width as f64 / scale).round() as _;
+                height = (height as f64 / scale).round() as _;
+            }
+            let opos = self.global.pos.get();
+            rd.lock_fallback = Some(OutputStatus {
+                tex_x: (opos.width() - width) / 2,
+                tex_y: (opos.height() - height) / 2,
+                tex,
+            });
+        }
     }
 
     pub fn ensure_workspace(self: &Rc<Self>) -> Rc<WorkspaceNode> {
@@ -277,10 +312,26 @@ impl OutputNode {
 
     pub fn show_workspace(&self, ws: &Rc<WorkspaceNode>) -> bool {
         let mut seats = SmallVec::new();
+        let mut sticky = vec![];
         if let Some(old) = self.workspace.set(Some(ws.clone())) {
             if old.id == ws.id {
                 return false;
             }
+            for stacked in old.stacked.iter() {
+                if let Some(float) = stacked
+                    .deref()
+                    .clone()
+                    .stacked_into_node()
+                    .node_into_float()
+                {
+                    if float.sticky.get() {
+                        sticky.push(float);
+                    }
+                }
+            }
+            for float in &sticky {
+                float.move_to_workspace(ws);
+            }
             collect_kb_foci2(old.clone(), &mut seats);
             if old.is_empty() {
                 for jw in old.jay_workspaces.lock().values() {
@@ -295,6 +346,9 @@ impl OutputNode {
             }
         }
         ws.set_visible(true);
+        for float in sticky {
+            float.stacked_set_visible(ws.stacked_visible());
+        }
         if let Some(fs) = ws.fullscreen.get() {
             fs.tl_change_extents(&self.global.pos.get());
         }
@@ -313,6 +367,8 @@ impl OutputNode {
             position: Cell::new(Default::default()),
             container: Default::default(),
             stacked: Default::default(),
+            minimized: Default::default(),
+            layout: Cell::new(ContainerLayout::Split),
             seat_state: Default::default(),
             name: name.to_string(),
             output_link: Cell::new(None),
@@ -488,6 +544,7 @@ pub struct OutputRenderData {
     pub captured_inactive_workspaces: Vec<Rect>,
     pub titles: Vec<OutputTitle>,
     pub status: Option<OutputStatus>,
+    pub lock_fallback: Option<OutputStatus>,
 }
 
 impl Debug for OutputNode {
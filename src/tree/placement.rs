@@ -0,0 +1,97 @@
+use {
+    crate::{backend::ConnectorId, forker::SpawnedProcess, time::Time, utils::numcell::NumCell},
+    std::{cell::RefCell, rc::Rc, time::Duration},
+    uapi::c,
+};
+
+/// How many ancestors of a client are inspected when looking for the process that
+/// spawned it.
+const MAX_ANCESTORS: usize = 16;
+
+/// How long an intent waits for the first toplevel of the spawned program.
+///
+/// The intent does not depend on the lifetime of the spawned process since launchers
+/// often fork the actual program and exit immediately.
+const INTENT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The placement requested for the first toplevel of a spawned process.
+pub struct PlacementIntent {
+    pub process: Rc<SpawnedProcess>,
+    pub token: String,
+    pub workspace: Option<Rc<String>>,
+    pub output: Option<ConnectorId>,
+    pub floating: Option<bool>,
+    pub sticky: bool,
+}
+
+struct PendingIntent {
+    expires: Time,
+    intent: Rc<PlacementIntent>,
+}
+
+#[derive(Default)]
+pub struct Placements {
+    next_id: NumCell<u64>,
+    intents: RefCell<Vec<PendingIntent>>,
+}
+
+impl Placements {
+    /// Returns a new activation token.
+    ///
+    /// The tokens of intents are passed to the spawned program in the
+    /// `DESKTOP_STARTUP_ID` and `XDG_ACTIVATION_TOKEN` environment variables.
+    pub fn next_token(&self) -> String {
+        format!("jay-token-{}", self.next_id.fetch_add(1))
+    }
+
+    /// Registers an intent. The intent is forgotten once it has been taken or after
+    /// [`INTENT_TIMEOUT`].
+    pub fn add(&self, intent: PlacementIntent) {
+        let now = Time::now_unchecked();
+        let mut intents = self.intents.borrow_mut();
+        intents.retain(|i| i.expires > now);
+        intents.push(PendingIntent {
+            expires: now + INTENT_TIMEOUT,
+            intent: Rc::new(intent),
+        });
+    }
+
+    /// Removes and returns the intent that applies to a new toplevel.
+    ///
+    /// Toplevels are matched by their token or, if that fails, by the pid of the client
+    /// or one of its ancestors.
+    pub fn take(&self, pid: Option<c::pid_t>, token: Option<&[u8]>) -> Option<Rc<PlacementIntent>> {
+        let now = Time::now_unchecked();
+        let mut intents = self.intents.borrow_mut();
+        intents.retain(|i| i.expires > now);
+        if intents.is_empty() {
+            return None;
+        }
+        let find = |f: &dyn Fn(&PlacementIntent) -> bool| intents.iter().position(|i| f(&i.intent));
+        let mut idx = None;
+        if let Some(token) = token {
+            idx = find(&|i| i.token.as_bytes() == token);
+        }
+        let mut pid = pid;
+        for _ in 0..MAX_ANCESTORS {
+            if idx.is_some() {
+                break;
+            }
+            let p = match pid {
+                Some(p) if p > 1 => p,
+                _ => break,
+            };
+            idx = find(&|i| i.process.pid() == Some(p));
+            pid = parent_pid(p);
+        }
+        idx.map(|idx| intents.remove(idx).intent)
+    }
+}
+
+fn parent_pid(pid: c::pid_t) -> Option<c::pid_t> {
+    let stat = std::fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+    // The command name can contain spaces and parentheses. The state and the parent pid
+    // follow the last closing parenthesis.
+    let rest = &stat[stat.rfind(')')? + 1..];
+    rest.split_ascii_whitespace().nth(1)?.parse().ok()
+}
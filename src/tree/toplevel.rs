@@ -4,8 +4,13 @@ use {
         ifs::wl_seat::{collect_kb_foci, collect_kb_foci2, NodeSeatState, SeatId},
         rect::Rect,
         state::State,
-        tree::{ContainingNode, Direction, Node, OutputNode, PlaceholderNode, WorkspaceNode},
-        utils::{clonecell::CloneCell, numcell::NumCell, smallmap::SmallMap},
+        tree::{
+            ContainingNode, Direction, Node, OutputNode, PlaceholderNode, PlacementIntent,
+            WorkspaceNode,
+        },
+        utils::{
+            clonecell::CloneCell, linkedlist::LinkedNode, numcell::NumCell, smallmap::SmallMap,
+        },
     },
     std::{
         cell::{Cell, RefCell},
@@ -144,6 +149,14 @@ pub trait ToplevelNode: Node {
         // nothing
     }
 
+    fn tl_set_minimized(&self, minimized: bool) {
+        let _ = minimized;
+    }
+
+    fn tl_set_maximized(&self, maximized: bool) {
+        let _ = maximized;
+    }
+
     fn tl_set_visible(&self, visible: bool);
     fn tl_destroy(&self);
 
@@ -157,23 +170,34 @@ pub struct FullscreenedData {
     pub workspace: Rc<WorkspaceNode>,
 }
 
+pub struct MinimizedData {
+    pub link: LinkedNode<Rc<dyn ToplevelNode>>,
+    pub floating: bool,
+}
+
 pub struct ToplevelData {
     pub active: Cell<bool>,
     pub client: Option<Rc<Client>>,
     pub state: Rc<State>,
     pub active_surfaces: NumCell<u32>,
     pub focus_node: SmallMap<SeatId, Rc<dyn Node>, 1>,
+    pub kb_groups: SmallMap<SeatId, u32, 1>,
     pub visible: Cell<bool>,
     pub is_floating: Cell<bool>,
+    pub in_scratchpad: Cell<bool>,
     pub float_width: Cell<i32>,
     pub float_height: Cell<i32>,
     pub is_fullscreen: Cell<bool>,
+    pub is_maximized: Cell<bool>,
     pub fullscrceen_data: RefCell<Option<FullscreenedData>>,
+    pub minimized_data: RefCell<Option<MinimizedData>>,
     pub workspace: CloneCell<Option<Rc<WorkspaceNode>>>,
     pub title: RefCell<String>,
     pub parent: CloneCell<Option<Rc<dyn ContainingNode>>>,
     pub pos: Cell<Rect>,
     pub seat_state: NodeSeatState,
+    /// A placement that was requested before the toplevel was mapped.
+    pub placement: CloneCell<Option<Rc<PlacementIntent>>>,
 }
 
 impl ToplevelData {
@@ -184,17 +208,22 @@ impl ToplevelData {
             state: state.clone(),
             active_surfaces: Default::default(),
             focus_node: Default::default(),
+            kb_groups: Default::default(),
             visible: Cell::new(false),
             is_floating: Default::default(),
+            in_scratchpad: Default::default(),
             float_width: Default::default(),
             float_height: Default::default(),
             is_fullscreen: Default::default(),
+            is_maximized: Default::default(),
             fullscrceen_data: Default::default(),
+            minimized_data: Default::default(),
             workspace: Default::default(),
             title: RefCell::new(title),
             parent: Default::default(),
             pos: Default::default(),
             seat_state: Default::default(),
+            placement: Default::default(),
         }
     }
 
@@ -219,8 +248,12 @@ impl ToplevelData {
             parent.cnode_remove_child(node);
         }
         self.workspace.take();
+        for seat in self.state.globals.seats.lock().values() {
+            seat.toplevel_destroyed(node.node_id());
+        }
         self.seat_state.destroy_node(node);
         self.focus_node.clear();
+        self.kb_groups.clear();
     }
 
     pub fn set_fullscreen(
@@ -337,6 +370,79 @@ impl ToplevelData {
             .destroy_node(fd.placeholder.deref());
     }
 
+    pub fn set_maximized(&self, node: &dyn ToplevelNode, maximized: bool) {
+        if self.is_maximized.replace(maximized) == maximized {
+            return;
+        }
+        node.tl_set_maximized(maximized);
+        if let Some(float) = self.parent.get().and_then(|p| p.node_into_float()) {
+            float.set_maximized(maximized);
+        }
+    }
+
+    pub fn is_minimized(&self) -> bool {
+        self.minimized_data.borrow().is_some()
+    }
+
+    pub fn minimize(&self, node: Rc<dyn ToplevelNode>) -> bool {
+        if self.is_fullscreen.get() {
+            log::info!("Cannot minimize a fullscreen node");
+            return false;
+        }
+        if node.node_is_placeholder() {
+            return false;
+        }
+        if self.is_minimized() {
+            return true;
+        }
+        let (parent, ws) = match (self.parent.get(), self.workspace.get()) {
+            (Some(p), Some(ws)) => (p, ws),
+            _ => return false,
+        };
+        let floating = self.is_floating.get();
+        let kb_foci = collect_kb_foci(node.clone().tl_into_node());
+        parent.cnode_remove_child2(node.tl_as_node(), true);
+        node.tl_set_visible(false);
+        *self.minimized_data.borrow_mut() = Some(MinimizedData {
+            link: ws.minimized.add_last(node.clone()),
+            floating,
+        });
+        node.tl_set_parent(ws.clone());
+        node.tl_set_minimized(true);
+        for seat in kb_foci {
+            ws.clone().node_do_focus(&seat, Direction::Unspecified);
+        }
+        self.state.tree_changed();
+        true
+    }
+
+    pub fn restore(&self, node: Rc<dyn ToplevelNode>) {
+        let md = match self.minimized_data.borrow_mut().take() {
+            Some(md) => md,
+            _ => return,
+        };
+        drop(md.link);
+        let ws = match self.workspace.get() {
+            Some(ws) => ws,
+            _ => return,
+        };
+        self.parent.take();
+        if md.floating {
+            let (width, height) = self.float_size(&ws);
+            self.state.map_floating(node.clone(), width, height, &ws);
+        } else {
+            self.state.map_tiled_on(node.clone(), &ws);
+        }
+        node.tl_set_minimized(false);
+        if node.tl_as_node().node_visible() {
+            if let Some(seat) = self.state.seat_queue.last() {
+                node.tl_into_node()
+                    .node_do_focus(&seat, Direction::Unspecified);
+            }
+        }
+        self.state.tree_changed();
+    }
+
     pub fn set_visible(&self, node: &dyn Node, visible: bool) {
         self.visible.set(visible);
         self.seat_state.set_visible(node, visible)
@@ -8,7 +8,8 @@ use {
             WlSurface,
         },
         tree::{
-            ContainerNode, DisplayNode, FloatNode, Node, OutputNode, PlaceholderNode, WorkspaceNode,
+            ContainerNode, DisplayNode, FloatNode, Node, OutputNode, PlaceholderNode,
+            WindowMenuNode, WorkspaceNode,
         },
     },
     std::rc::Rc,
@@ -62,6 +63,10 @@ pub trait NodeVisitorBase: Sized {
     fn visit_lock_surface(&mut self, node: &Rc<ExtSessionLockSurfaceV1>) {
         node.node_visit_children(self);
     }
+
+    fn visit_window_menu(&mut self, node: &Rc<WindowMenuNode>) {
+        node.node_visit_children(self);
+    }
 }
 
 pub trait NodeVisitor {
@@ -77,6 +82,7 @@ pub trait NodeVisitor {
     fn visit_xwindow(&mut self, node: &Rc<Xwindow>);
     fn visit_placeholder(&mut self, node: &Rc<PlaceholderNode>);
     fn visit_lock_surface(&mut self, node: &Rc<ExtSessionLockSurfaceV1>);
+    fn visit_window_menu(&mut self, node: &Rc<WindowMenuNode>);
 }
 
 impl<T: NodeVisitorBase> NodeVisitor for T {
@@ -127,6 +133,10 @@ impl<T: NodeVisitorBase> NodeVisitor for T {
     fn visit_lock_surface(&mut self, node: &Rc<ExtSessionLockSurfaceV1>) {
         <T as NodeVisitorBase>::visit_lock_surface(self, node)
     }
+
+    fn visit_window_menu(&mut self, node: &Rc<WindowMenuNode>) {
+        <T as NodeVisitorBase>::visit_window_menu(self, node)
+    }
 }
 
 pub struct GenericNodeVisitor<F> {
@@ -197,6 +207,11 @@ impl<F: FnMut(Rc<dyn Node>)> NodeVisitor for GenericNodeVisitor<F> {
         (self.f)(node.clone());
         node.node_visit_children(self);
     }
+
+    fn visit_window_menu(&mut self, node: &Rc<WindowMenuNode>) {
+        (self.f)(node.clone());
+        node.node_visit_children(self);
+    }
 }
 
 // pub fn visit_containers<F: FnMut(&Rc<ContainerNode>)>(f: F) -> impl NodeVisitor {
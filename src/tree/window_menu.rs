@@ -0,0 +1,375 @@
+use {
+    crate::{
+        backend::KeyState,
+        cursor::KnownCursor,
+        fixed::Fixed,
+        ifs::wl_seat::{wl_keyboard, NodeSeatState, WlSeatGlobal, BTN_LEFT},
+        rect::Rect,
+        render::{Renderer, Texture},
+        scale::Scale,
+        state::State,
+        text,
+        tree::{FindTreeResult, FoundNode, Node, NodeId, NodeVisitor, StackedNode, ToplevelNode},
+        utils::{errorfmt::ErrorFmt, linkedlist::LinkedNode, smallmap::SmallMap},
+    },
+    std::{
+        cell::{Cell, RefCell},
+        rc::Rc,
+    },
+};
+
+tree_id!(WindowMenuNodeId);
+
+const KEY_ESC: u32 = 1;
+const KEY_ENTER: u32 = 28;
+const KEY_KPENTER: u32 = 96;
+const KEY_UP: u32 = 103;
+const KEY_DOWN: u32 = 108;
+
+/// The horizontal space between the border of the menu and the entry texts.
+pub const WINDOW_MENU_PADDING: i32 = 8;
+
+const MIN_TEXT_WIDTH: i32 = 100;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum WindowMenuAction {
+    ToggleFloating,
+    ToggleFullscreen,
+    Minimize,
+    MoveToWorkspace(String),
+    Close,
+}
+
+pub struct WindowMenuEntry {
+    pub label: String,
+    pub action: WindowMenuAction,
+}
+
+pub struct WindowMenuTextures {
+    pub normal: Rc<Texture>,
+    pub selected: Rc<Texture>,
+}
+
+/// A menu drawn by the compositor in response to `xdg_toplevel.show_window_menu`.
+pub struct WindowMenuNode {
+    id: WindowMenuNodeId,
+    state: Rc<State>,
+    seat: Rc<WlSeatGlobal>,
+    pub toplevel: Rc<dyn ToplevelNode>,
+    pub entries: Vec<WindowMenuEntry>,
+    pub position: Cell<Rect>,
+    pub border_width: i32,
+    pub row_height: i32,
+    pub selected: Cell<Option<usize>>,
+    pointer_row: Cell<Option<usize>>,
+    pub textures: SmallMap<Scale, Rc<Vec<WindowMenuTextures>>, 2>,
+    display_link: RefCell<Option<LinkedNode<Rc<dyn StackedNode>>>>,
+    seat_state: NodeSeatState,
+}
+
+impl WindowMenuNode {
+    /// Creates a menu for the toplevel with its top-left corner at the given absolute
+    /// position and stacks it above all other nodes.
+    pub fn install(
+        state: &Rc<State>,
+        seat: &Rc<WlSeatGlobal>,
+        toplevel: Rc<dyn ToplevelNode>,
+        x: i32,
+        y: i32,
+    ) -> Rc<Self> {
+        let theme = &state.theme;
+        let border_width = theme.sizes.border_width.get();
+        let row_height = theme.sizes.title_height.get();
+        let entries = create_entries(state, &*toplevel);
+        let mut text_width = MIN_TEXT_WIDTH;
+        {
+            let font = theme.font.borrow_mut();
+            for entry in &entries {
+                match text::measure(&font, &entry.label, false, None, false) {
+                    Ok(m) => text_width = text_width.max(m.ink_rect.x2()),
+                    Err(e) => {
+                        log::warn!("Could not measure {}: {}", entry.label, ErrorFmt(e));
+                    }
+                }
+            }
+        }
+        let width = text_width + 2 * (border_width + WINDOW_MENU_PADDING);
+        let height = entries.len() as i32 * row_height + 2 * border_width;
+        let (mut x, mut y) = (x, y);
+        if let Some(ws) = toplevel.tl_data().workspace.get() {
+            let opos = ws.output.get().global.pos.get();
+            x = x.min(opos.x2() - width).max(opos.x1());
+            y = y.min(opos.y2() - height).max(opos.y1());
+        }
+        let slf = Rc::new(Self {
+            id: state.node_ids.next(),
+            state: state.clone(),
+            seat: seat.clone(),
+            toplevel,
+            entries,
+            position: Cell::new(Rect::new_sized(x, y, width, height).unwrap()),
+            border_width,
+            row_height,
+            selected: Cell::new(None),
+            pointer_row: Cell::new(None),
+            textures: Default::default(),
+            display_link: Default::default(),
+            seat_state: Default::default(),
+        });
+        slf.update_textures();
+        *slf.display_link.borrow_mut() = Some(state.root.stacked.add_last(slf.clone()));
+        state.tree_changed();
+        state.damage();
+        slf
+    }
+
+    fn update_textures(&self) {
+        self.textures.clear();
+        let ctx = match self.state.render_ctx.get() {
+            Some(c) => c,
+            _ => return,
+        };
+        let theme = &self.state.theme;
+        let font = theme.font.borrow_mut();
+        let normal_color = theme.colors.unfocused_title_text.get();
+        let selected_color = theme.colors.focused_title_text.get();
+        let pos = self.position.get();
+        let scales = self.state.scales.lock();
+        for (scale, _) in scales.iter() {
+            let mut width = pos.width() - 2 * (self.border_width + WINDOW_MENU_PADDING);
+            let mut height = self.row_height;
+            let mut scalef = None;
+            if *scale != 1 {
+                let scale = scale.to_f64();
+                width = (width as f64 * scale).round() as _;
+                height = (height as f64 * scale).round() as _;
+                scalef = Some(scale);
+            }
+            if width <= 0 || height <= 0 {
+                continue;
+            }
+            let mut textures = vec![];
+            for entry in &self.entries {
+                let render =
+                    |color| text::render(&ctx, width, height, &font, &entry.label, color, scalef);
+                match (render(normal_color), render(selected_color)) {
+                    (Ok(normal), Ok(selected)) => {
+                        textures.push(WindowMenuTextures { normal, selected })
+                    }
+                    (Err(e), _) | (_, Err(e)) => {
+                        log::error!("Could not render {}: {}", entry.label, ErrorFmt(e));
+                        return;
+                    }
+                }
+            }
+            self.textures.insert(*scale, Rc::new(textures));
+        }
+    }
+
+    /// Removes the menu from the display without touching the keyboard focus.
+    pub fn unlink(&self) {
+        if self.display_link.borrow_mut().take().is_some() {
+            self.state.tree_changed();
+            self.state.damage();
+        }
+    }
+
+    pub fn destroy(&self) {
+        self.unlink();
+        self.seat_state.destroy_node(self);
+    }
+
+    fn row_at(&self, y: i32) -> Option<usize> {
+        let y = y - self.border_width;
+        if y < 0 || self.row_height <= 0 {
+            return None;
+        }
+        let row = (y / self.row_height) as usize;
+        (row < self.entries.len()).then_some(row)
+    }
+
+    fn select(&self, row: Option<usize>) {
+        if self.selected.replace(row) != row {
+            self.state.damage();
+        }
+    }
+
+    fn pointer_move(&self, y: Fixed) {
+        let row = self.row_at(y.round_down());
+        self.pointer_row.set(row);
+        if row.is_some() {
+            self.select(row);
+        }
+    }
+
+    fn activate(&self, idx: usize) {
+        let action = match self.entries.get(idx) {
+            Some(e) => e.action.clone(),
+            _ => return,
+        };
+        let seat = &self.seat;
+        let tl = self.toplevel.clone();
+        seat.close_window_menu();
+        if !tl.node_visible() {
+            return;
+        }
+        let data = tl.tl_data();
+        match action {
+            WindowMenuAction::ToggleFloating => {
+                seat.set_tl_floating(tl.clone(), !data.is_floating.get())
+            }
+            WindowMenuAction::ToggleFullscreen => {
+                tl.clone().tl_set_fullscreen(!data.is_fullscreen.get())
+            }
+            WindowMenuAction::Minimize => {
+                data.minimize(tl.clone());
+            }
+            WindowMenuAction::MoveToWorkspace(name) => {
+                if let Some(ws) = self.state.workspaces.get(&name) {
+                    seat.set_tl_workspace(tl.clone(), &ws);
+                }
+            }
+            WindowMenuAction::Close => tl.tl_close(),
+        }
+    }
+}
+
+fn create_entries(state: &State, tl: &dyn ToplevelNode) -> Vec<WindowMenuEntry> {
+    let data = tl.tl_data();
+    let mut entries = vec![];
+    let mut entry = |label: &str, action| {
+        entries.push(WindowMenuEntry {
+            label: label.to_string(),
+            action,
+        })
+    };
+    match data.is_floating.get() {
+        true => entry("Tile", WindowMenuAction::ToggleFloating),
+        false => entry("Float", WindowMenuAction::ToggleFloating),
+    }
+    match data.is_fullscreen.get() {
+        true => entry("Exit fullscreen", WindowMenuAction::ToggleFullscreen),
+        false => entry("Fullscreen", WindowMenuAction::ToggleFullscreen),
+    }
+    entry("Minimize", WindowMenuAction::Minimize);
+    let current = data.workspace.get().map(|ws| ws.id);
+    let mut workspaces: Vec<_> = state
+        .workspaces
+        .lock()
+        .values()
+        .filter(|ws| Some(ws.id) != current)
+        .map(|ws| ws.name.clone())
+        .collect();
+    workspaces.sort();
+    for name in workspaces {
+        entry(
+            &format!("Move to workspace {}", name),
+            WindowMenuAction::MoveToWorkspace(name),
+        );
+    }
+    entry("Close", WindowMenuAction::Close);
+    entries
+}
+
+impl Node for WindowMenuNode {
+    fn node_id(&self) -> NodeId {
+        self.id.into()
+    }
+
+    fn node_seat_state(&self) -> &NodeSeatState {
+        &self.seat_state
+    }
+
+    fn node_visit(self: Rc<Self>, visitor: &mut dyn NodeVisitor) {
+        visitor.visit_window_menu(&self);
+    }
+
+    fn node_visit_children(&self, _visitor: &mut dyn NodeVisitor) {
+        // nothing
+    }
+
+    fn node_visible(&self) -> bool {
+        true
+    }
+
+    fn node_absolute_position(&self) -> Rect {
+        self.position.get()
+    }
+
+    fn node_find_tree_at(&self, _x: i32, _y: i32, _tree: &mut Vec<FoundNode>) -> FindTreeResult {
+        FindTreeResult::AcceptsInput
+    }
+
+    fn node_render(&self, renderer: &mut Renderer, x: i32, y: i32) {
+        renderer.render_window_menu(self, x, y);
+    }
+
+    fn node_on_key(&self, _seat: &WlSeatGlobal, _time_usec: u64, key: u32, state: u32) {
+        if state != wl_keyboard::PRESSED {
+            return;
+        }
+        let len = self.entries.len();
+        let selected = self.selected.get();
+        match key {
+            KEY_ESC => self.seat.close_window_menu(),
+            KEY_ENTER | KEY_KPENTER => {
+                if let Some(idx) = selected {
+                    self.activate(idx);
+                }
+            }
+            KEY_UP => self.select(Some(match selected {
+                Some(idx) if idx > 0 => idx - 1,
+                _ => len - 1,
+            })),
+            KEY_DOWN => self.select(Some(match selected {
+                Some(idx) if idx + 1 < len => idx + 1,
+                _ => 0,
+            })),
+            _ => {}
+        }
+    }
+
+    fn node_on_button(
+        self: Rc<Self>,
+        _seat: &Rc<WlSeatGlobal>,
+        _time_usec: u64,
+        button: u32,
+        state: KeyState,
+        _serial: u32,
+    ) {
+        if button != BTN_LEFT || state != KeyState::Pressed {
+            return;
+        }
+        if let Some(idx) = self.pointer_row.get() {
+            self.activate(idx);
+        }
+    }
+
+    fn node_on_unfocus(&self, seat: &WlSeatGlobal) {
+        seat.remove_window_menu(self);
+    }
+
+    fn node_on_leave(&self, _seat: &WlSeatGlobal) {
+        self.pointer_row.set(None);
+    }
+
+    fn node_on_pointer_enter(self: Rc<Self>, _seat: &Rc<WlSeatGlobal>, _x: Fixed, y: Fixed) {
+        self.pointer_move(y);
+    }
+
+    fn node_on_pointer_focus(&self, seat: &Rc<WlSeatGlobal>) {
+        seat.set_known_cursor(KnownCursor::Default);
+    }
+
+    fn node_on_pointer_motion(self: Rc<Self>, _seat: &Rc<WlSeatGlobal>, _x: Fixed, y: Fixed) {
+        self.pointer_move(y);
+    }
+}
+
+impl StackedNode for WindowMenuNode {
+    stacked_node_impl!();
+
+    fn stacked_set_visible(&self, _visible: bool) {
+        // nothing
+    }
+}
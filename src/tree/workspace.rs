@@ -11,9 +11,10 @@ use {
         rect::Rect,
         render::Renderer,
         tree::{
-            container::ContainerNode, walker::NodeVisitor, ContainingNode, Direction,
-            FindTreeResult, FoundNode, Node, NodeId, NodeVisitorBase, OutputNode, StackedNode,
-            ToplevelNode,
+            container::{ContainerLayout, ContainerNode},
+            walker::NodeVisitor,
+            ContainingNode, Direction, FindTreeResult, FoundNode, Node, NodeId, NodeVisitorBase,
+            OutputNode, StackedNode, ToplevelNode,
         },
         utils::{
             clonecell::CloneCell,
@@ -34,6 +35,8 @@ pub struct WorkspaceNode {
     pub position: Cell<Rect>,
     pub container: CloneCell<Option<Rc<ContainerNode>>>,
     pub stacked: LinkedList<Rc<dyn StackedNode>>,
+    pub minimized: LinkedList<Rc<dyn ToplevelNode>>,
+    pub layout: Cell<ContainerLayout>,
     pub seat_state: NodeSeatState,
     pub name: String,
     pub output_link: Cell<Option<LinkedNode<Rc<WorkspaceNode>>>>,
@@ -72,16 +75,42 @@ impl WorkspaceNode {
     }
 
     pub fn set_container(self: &Rc<Self>, container: &Rc<ContainerNode>) {
-        let pos = self.position.get();
+        self.container.set(Some(container.clone()));
+        let pos = self.container_rect();
         container.clone().tl_change_extents(&pos);
         container.clone().tl_set_workspace(self);
         container.tl_set_parent(self.clone());
         container.tl_set_visible(self.stacked_visible());
-        self.container.set(Some(container.clone()));
+    }
+
+    pub fn container_rect(&self) -> Rect {
+        let pos = self.position.get();
+        let state = &self.output.get().state;
+        let gap = state.theme.sizes.outer_gap.get();
+        if gap == 0 {
+            return pos;
+        }
+        if state.theme.smart_gaps.get() {
+            if let Some(c) = self.container.get() {
+                if c.is_single_window() {
+                    return pos;
+                }
+            }
+        }
+        Rect::new_sized(
+            pos.x1() + gap,
+            pos.y1() + gap,
+            (pos.width() - 2 * gap).max(0),
+            (pos.height() - 2 * gap).max(0),
+        )
+        .unwrap()
     }
 
     pub fn is_empty(&self) -> bool {
-        self.stacked.is_empty() && self.fullscreen.get().is_none() && self.container.get().is_none()
+        self.stacked.is_empty()
+            && self.minimized.is_empty()
+            && self.fullscreen.get().is_none()
+            && self.container.get().is_none()
     }
 
     pub fn stacked_visible(&self) -> bool {
@@ -91,7 +120,28 @@ impl WorkspaceNode {
     pub fn change_extents(&self, rect: &Rect) {
         self.position.set(*rect);
         if let Some(c) = self.container.get() {
-            c.tl_change_extents(rect);
+            c.tl_change_extents(&self.container_rect());
+        }
+    }
+
+    /// Applies the extents returned by [`Self::container_rect`] to the container.
+    ///
+    /// This must be called whenever the result of that function might have changed, e.g.
+    /// when the outer gap changes or when a child is added to or removed from the
+    /// container.
+    pub fn update_container_extents(&self) {
+        if let Some(c) = self.container.get() {
+            let rect = self.container_rect();
+            if rect != c.tl_data().pos.get() {
+                c.tl_change_extents(&rect);
+            }
+        }
+    }
+
+    pub fn restore_last_minimized(&self) {
+        if let Some(node) = self.minimized.last() {
+            let node = node.deref().clone();
+            node.tl_data().restore(node.clone());
         }
     }
 
@@ -140,6 +190,9 @@ impl Node for WorkspaceNode {
         if let Some(fs) = self.fullscreen.get() {
             fs.tl_into_node().node_visit(visitor);
         }
+        for node in self.minimized.iter() {
+            node.deref().clone().tl_into_node().node_visit(visitor);
+        }
     }
 
     fn node_visible(&self) -> bool {
@@ -160,12 +213,17 @@ impl Node for WorkspaceNode {
 
     fn node_find_tree_at(&self, x: i32, y: i32, tree: &mut Vec<FoundNode>) -> FindTreeResult {
         if let Some(n) = self.container.get() {
-            tree.push(FoundNode {
-                node: n.clone(),
-                x,
-                y,
-            });
-            n.node_find_tree_at(x, y, tree);
+            let pos = self.position.get();
+            let cpos = n.node_absolute_position();
+            let (x, y) = (x + pos.x1() - cpos.x1(), y + pos.y1() - cpos.y1());
+            if x >= 0 && y >= 0 && x < cpos.width() && y < cpos.height() {
+                tree.push(FoundNode {
+                    node: n.clone(),
+                    x,
+                    y,
+                });
+                n.node_find_tree_at(x, y, tree);
+            }
         }
         FindTreeResult::AcceptsInput
     }
@@ -225,6 +283,12 @@ impl ContainingNode for WorkspaceNode {
                 return;
             }
         }
+        for node in self.minimized.iter() {
+            if node.node_id() == child.node_id() {
+                node.tl_data().minimized_data.take();
+                return;
+            }
+        }
         log::error!("Trying to remove child that's not a child");
     }
 
@@ -3,7 +3,7 @@ use {
         linkedlist::NodeRef,
         ptr_ext::{MutPtrExt, PtrExt},
     },
-    jay_config::keyboard::mods::Modifiers,
+    jay_config::{exec::ExitStatus, keyboard::mods::Modifiers},
     std::{
         cell::UnsafeCell,
         fmt::{Debug, Formatter},
@@ -75,9 +75,11 @@ unsafe impl<T: ?Sized> UnsafeCellCloneSafe for Weak<T> {}
 unsafe impl<T> UnsafeCellCloneSafe for NodeRef<T> {}
 
 unsafe impl UnsafeCellCloneSafe for () {}
+unsafe impl UnsafeCellCloneSafe for u32 {}
 unsafe impl UnsafeCellCloneSafe for u64 {}
 unsafe impl UnsafeCellCloneSafe for i32 {}
 
 unsafe impl<A: UnsafeCellCloneSafe, B: UnsafeCellCloneSafe> UnsafeCellCloneSafe for (A, B) {}
 
 unsafe impl UnsafeCellCloneSafe for Modifiers {}
+unsafe impl UnsafeCellCloneSafe for ExitStatus {}
@@ -197,6 +197,30 @@ impl Wheel {
             .set(future.data.id.get(), future.data.clone());
         future
     }
+
+    /// Completes all timeouts that expire within the next `ms` milliseconds.
+    ///
+    /// This allows tests to skip ahead instead of waiting for the timeouts.
+    #[cfg(feature = "it")]
+    pub fn fast_forward(&self, ms: u64) {
+        let limit = match Time::now() {
+            Ok(n) => n + Duration::from_millis(ms),
+            Err(e) => {
+                log::error!("Cannot determine the time: {}", ErrorFmt(e));
+                return;
+            }
+        };
+        let mut expirations = self.data.expirations.borrow_mut();
+        while let Some(Reverse(entry)) = expirations.peek() {
+            if entry.expiration > limit {
+                break;
+            }
+            if let Some(dispatcher) = self.data.dispatchers.remove(&entry.id) {
+                dispatcher.complete(Ok(()));
+            }
+            expirations.pop();
+        }
+    }
 }
 
 impl WheelData {
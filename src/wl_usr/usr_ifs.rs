@@ -11,6 +11,7 @@ pub mod usr_wl_buffer;
 pub mod usr_wl_callback;
 pub mod usr_wl_compositor;
 pub mod usr_wl_display;
+pub mod usr_wl_keyboard;
 pub mod usr_wl_output;
 pub mod usr_wl_pointer;
 pub mod usr_wl_registry;
@@ -24,5 +25,8 @@ pub mod usr_wp_fractional_scale;
 pub mod usr_wp_fractional_scale_manager;
 pub mod usr_wp_viewport;
 pub mod usr_wp_viewporter;
+pub mod usr_xdg_surface;
+pub mod usr_xdg_toplevel;
+pub mod usr_xdg_wm_base;
 pub mod usr_zwlr_screencopy_frame;
 pub mod usr_zwlr_screencopy_manager;
@@ -0,0 +1,93 @@
+use {
+    crate::{
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            clonecell::CloneCell,
+        },
+        wire::{wl_keyboard::*, WlKeyboardId},
+        wl_usr::{usr_object::UsrObject, UsrCon},
+    },
+    std::rc::Rc,
+};
+
+pub struct UsrWlKeyboard {
+    pub id: WlKeyboardId,
+    pub con: Rc<UsrCon>,
+    pub owner: CloneCell<Option<Rc<dyn UsrWlKeyboardOwner>>>,
+}
+
+pub trait UsrWlKeyboardOwner {
+    fn enter(&self, ev: &Enter) {
+        let _ = ev;
+    }
+
+    fn leave(&self, ev: &Leave) {
+        let _ = ev;
+    }
+
+    fn key(&self, ev: &Key) {
+        let _ = ev;
+    }
+}
+
+impl UsrWlKeyboard {
+    fn keymap(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
+        let _ev: Keymap = self.con.parse(self, parser)?;
+        Ok(())
+    }
+
+    fn enter(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
+        let ev: Enter = self.con.parse(self, parser)?;
+        if let Some(owner) = self.owner.get() {
+            owner.enter(&ev);
+        }
+        Ok(())
+    }
+
+    fn leave(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
+        let ev: Leave = self.con.parse(self, parser)?;
+        if let Some(owner) = self.owner.get() {
+            owner.leave(&ev);
+        }
+        Ok(())
+    }
+
+    fn key(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
+        let ev: Key = self.con.parse(self, parser)?;
+        if let Some(owner) = self.owner.get() {
+            owner.key(&ev);
+        }
+        Ok(())
+    }
+
+    fn modifiers(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
+        let _ev: Modifiers = self.con.parse(self, parser)?;
+        Ok(())
+    }
+
+    fn repeat_info(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
+        let _ev: RepeatInfo = self.con.parse(self, parser)?;
+        Ok(())
+    }
+}
+
+usr_object_base! {
+    UsrWlKeyboard, WlKeyboard;
+
+    KEYMAP => keymap,
+    ENTER => enter,
+    LEAVE => leave,
+    KEY => key,
+    MODIFIERS => modifiers,
+    REPEAT_INFO => repeat_info,
+}
+
+impl UsrObject for UsrWlKeyboard {
+    fn destroy(&self) {
+        self.con.request(Release { self_id: self.id });
+    }
+
+    fn break_loops(&self) {
+        self.owner.take();
+    }
+}
@@ -5,7 +5,7 @@ use {
             buffd::{MsgParser, MsgParserError},
             clonecell::CloneCell,
         },
-        wire::{wl_pointer::*, WlPointerId},
+        wire::{wl_pointer::*, WlPointerId, WlSurfaceId},
         wl_usr::{usr_ifs::usr_wl_surface::UsrWlSurface, usr_object::UsrObject, UsrCon},
     },
     std::{cell::Cell, rc::Rc},
@@ -53,6 +53,16 @@ impl UsrWlPointer {
         });
     }
 
+    pub fn hide_cursor(&self, serial: u32) {
+        self.con.request(SetCursor {
+            self_id: self.id,
+            serial,
+            surface: WlSurfaceId::NONE,
+            hotspot_x: 0,
+            hotspot_y: 0,
+        });
+    }
+
     fn enter(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
         let ev: Enter = self.con.parse(self, parser)?;
         if let Some(owner) = self.owner.get() {
@@ -5,7 +5,11 @@ use {
             clonecell::CloneCell,
         },
         wire::{wl_seat::*, WlSeatId},
-        wl_usr::{usr_ifs::usr_wl_pointer::UsrWlPointer, usr_object::UsrObject, UsrCon},
+        wl_usr::{
+            usr_ifs::{usr_wl_keyboard::UsrWlKeyboard, usr_wl_pointer::UsrWlPointer},
+            usr_object::UsrObject,
+            UsrCon,
+        },
     },
     std::{cell::Cell, rc::Rc},
 };
@@ -43,6 +47,20 @@ impl UsrWlSeat {
         ptr
     }
 
+    pub fn get_keyboard(&self) -> Rc<UsrWlKeyboard> {
+        let kb = Rc::new(UsrWlKeyboard {
+            id: self.con.id(),
+            con: self.con.clone(),
+            owner: Default::default(),
+        });
+        self.con.add_object(kb.clone());
+        self.con.request(GetKeyboard {
+            self_id: self.id,
+            id: kb.id,
+        });
+        kb
+    }
+
     fn capabilities(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
         let ev: Capabilities = self.con.parse(self, parser)?;
         if let Some(owner) = self.owner.get() {
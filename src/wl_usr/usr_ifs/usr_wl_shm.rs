@@ -19,7 +19,6 @@ pub struct UsrWlShm {
 }
 
 impl UsrWlShm {
-    #[allow(dead_code)]
     pub fn create_pool(&self, fd: &Rc<OwnedFd>, size: i32) -> Rc<UsrWlShmPool> {
         let pool = Rc::new(UsrWlShmPool {
             id: self.con.id(),
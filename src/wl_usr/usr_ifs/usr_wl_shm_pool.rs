@@ -1,7 +1,7 @@
 use {
     crate::{
         wire::{wl_shm_pool::*, WlShmPoolId},
-        wl_usr::{usr_object::UsrObject, UsrCon},
+        wl_usr::{usr_ifs::usr_wl_buffer::UsrWlBuffer, usr_object::UsrObject, UsrCon},
     },
     std::rc::Rc,
 };
@@ -12,6 +12,32 @@ pub struct UsrWlShmPool {
 }
 
 impl UsrWlShmPool {
+    pub fn create_buffer(
+        &self,
+        offset: i32,
+        width: i32,
+        height: i32,
+        stride: i32,
+        format: u32,
+    ) -> Rc<UsrWlBuffer> {
+        let buffer = Rc::new(UsrWlBuffer {
+            id: self.con.id(),
+            con: self.con.clone(),
+            owner: Default::default(),
+        });
+        self.con.request(CreateBuffer {
+            self_id: self.id,
+            id: buffer.id,
+            offset,
+            width,
+            height,
+            stride,
+            format,
+        });
+        self.con.add_object(buffer.clone());
+        buffer
+    }
+
     #[allow(dead_code)]
     pub fn resize(&self, size: i32) {
         self.con.request(Resize {
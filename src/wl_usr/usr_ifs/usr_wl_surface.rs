@@ -26,6 +26,16 @@ impl UsrWlSurface {
         });
     }
 
+    pub fn damage_buffer(&self, x: i32, y: i32, width: i32, height: i32) {
+        self.con.request(DamageBuffer {
+            self_id: self.id,
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+
     pub fn frame<F>(&self, f: F)
     where
         F: FnOnce() + 'static,
@@ -0,0 +1,65 @@
+use {
+    crate::{
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            clonecell::CloneCell,
+        },
+        wire::{xdg_surface::*, XdgSurfaceId},
+        wl_usr::{usr_ifs::usr_xdg_toplevel::UsrXdgToplevel, usr_object::UsrObject, UsrCon},
+    },
+    std::rc::Rc,
+};
+
+pub struct UsrXdgSurface {
+    pub id: XdgSurfaceId,
+    pub con: Rc<UsrCon>,
+    pub owner: CloneCell<Option<Rc<dyn UsrXdgSurfaceOwner>>>,
+}
+
+pub trait UsrXdgSurfaceOwner {
+    fn configure(&self) {}
+}
+
+impl UsrXdgSurface {
+    pub fn get_toplevel(&self) -> Rc<UsrXdgToplevel> {
+        let tl = Rc::new(UsrXdgToplevel {
+            id: self.con.id(),
+            con: self.con.clone(),
+            owner: Default::default(),
+        });
+        self.con.add_object(tl.clone());
+        self.con.request(GetToplevel {
+            self_id: self.id,
+            id: tl.id,
+        });
+        tl
+    }
+
+    fn configure(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
+        let ev: Configure = self.con.parse(self, parser)?;
+        if let Some(owner) = self.owner.get() {
+            owner.configure();
+        }
+        self.con.request(AckConfigure {
+            self_id: self.id,
+            serial: ev.serial,
+        });
+        Ok(())
+    }
+}
+
+usr_object_base! {
+    UsrXdgSurface, XdgSurface;
+
+    CONFIGURE => configure,
+}
+
+impl UsrObject for UsrXdgSurface {
+    fn destroy(&self) {
+        self.con.request(Destroy { self_id: self.id });
+    }
+
+    fn break_loops(&self) {
+        self.owner.take();
+    }
+}
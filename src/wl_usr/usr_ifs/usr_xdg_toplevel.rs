@@ -0,0 +1,87 @@
+use {
+    crate::{
+        utils::{
+            buffd::{MsgParser, MsgParserError},
+            clonecell::CloneCell,
+        },
+        wire::{xdg_toplevel::*, XdgToplevelId},
+        wl_usr::{usr_object::UsrObject, UsrCon},
+    },
+    std::rc::Rc,
+};
+
+pub struct UsrXdgToplevel {
+    pub id: XdgToplevelId,
+    pub con: Rc<UsrCon>,
+    pub owner: CloneCell<Option<Rc<dyn UsrXdgToplevelOwner>>>,
+}
+
+pub trait UsrXdgToplevelOwner {
+    fn configure(&self, width: i32, height: i32) {
+        let _ = width;
+        let _ = height;
+    }
+
+    fn close(&self) {}
+}
+
+impl UsrXdgToplevel {
+    pub fn set_title(&self, title: &str) {
+        self.con.request(SetTitle {
+            self_id: self.id,
+            title,
+        });
+    }
+
+    pub fn set_app_id(&self, app_id: &str) {
+        self.con.request(SetAppId {
+            self_id: self.id,
+            app_id,
+        });
+    }
+
+    fn configure(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
+        let ev: Configure = self.con.parse(self, parser)?;
+        if let Some(owner) = self.owner.get() {
+            owner.configure(ev.width, ev.height);
+        }
+        Ok(())
+    }
+
+    fn close(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
+        let _ev: Close = self.con.parse(self, parser)?;
+        if let Some(owner) = self.owner.get() {
+            owner.close();
+        }
+        Ok(())
+    }
+
+    fn configure_bounds(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
+        let _ev: ConfigureBounds = self.con.parse(self, parser)?;
+        Ok(())
+    }
+
+    fn wm_capabilities(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
+        let _ev: WmCapabilities = self.con.parse(self, parser)?;
+        Ok(())
+    }
+}
+
+usr_object_base! {
+    UsrXdgToplevel, XdgToplevel;
+
+    CONFIGURE => configure,
+    CLOSE => close,
+    CONFIGURE_BOUNDS => configure_bounds,
+    WM_CAPABILITIES => wm_capabilities,
+}
+
+impl UsrObject for UsrXdgToplevel {
+    fn destroy(&self) {
+        self.con.request(Destroy { self_id: self.id });
+    }
+
+    fn break_loops(&self) {
+        self.owner.take();
+    }
+}
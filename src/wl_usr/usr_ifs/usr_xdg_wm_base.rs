@@ -0,0 +1,55 @@
+use {
+    crate::{
+        utils::buffd::{MsgParser, MsgParserError},
+        wire::{xdg_wm_base::*, XdgWmBaseId},
+        wl_usr::{
+            usr_ifs::{usr_wl_surface::UsrWlSurface, usr_xdg_surface::UsrXdgSurface},
+            usr_object::UsrObject,
+            UsrCon,
+        },
+    },
+    std::rc::Rc,
+};
+
+pub struct UsrXdgWmBase {
+    pub id: XdgWmBaseId,
+    pub con: Rc<UsrCon>,
+}
+
+impl UsrXdgWmBase {
+    pub fn get_xdg_surface(&self, surface: &UsrWlSurface) -> Rc<UsrXdgSurface> {
+        let xdg = Rc::new(UsrXdgSurface {
+            id: self.con.id(),
+            con: self.con.clone(),
+            owner: Default::default(),
+        });
+        self.con.add_object(xdg.clone());
+        self.con.request(GetXdgSurface {
+            self_id: self.id,
+            id: xdg.id,
+            surface: surface.id,
+        });
+        xdg
+    }
+
+    fn ping(&self, parser: MsgParser<'_, '_>) -> Result<(), MsgParserError> {
+        let ev: Ping = self.con.parse(self, parser)?;
+        self.con.request(Pong {
+            self_id: self.id,
+            serial: ev.serial,
+        });
+        Ok(())
+    }
+}
+
+usr_object_base! {
+    UsrXdgWmBase, XdgWmBase;
+
+    PING => ping,
+}
+
+impl UsrObject for UsrXdgWmBase {
+    fn destroy(&self) {
+        self.con.request(Destroy { self_id: self.id });
+    }
+}
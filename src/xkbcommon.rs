@@ -110,6 +110,11 @@ extern "C" {
     fn xkb_state_serialize_mods(state: *mut xkb_state, components: xkb_state_component) -> u32;
     #[allow(dead_code)]
     fn xkb_state_serialize_layout(state: *mut xkb_state, components: xkb_state_component) -> u32;
+    fn xkb_keymap_num_layouts(keymap: *mut xkb_keymap) -> xkb_layout_index_t;
+    fn xkb_keymap_layout_get_name(
+        keymap: *mut xkb_keymap,
+        idx: xkb_layout_index_t,
+    ) -> *const c::c_char;
 }
 
 pub struct XkbContext {
@@ -189,6 +194,20 @@ pub struct XkbKeymap {
 }
 
 impl XkbKeymap {
+    pub fn num_groups(&self) -> u32 {
+        unsafe { xkb_keymap_num_layouts(self.keymap) }
+    }
+
+    pub fn group_name(&self, group: u32) -> Option<String> {
+        unsafe {
+            let name = xkb_keymap_layout_get_name(self.keymap, group);
+            if name.is_null() {
+                return None;
+            }
+            Some(CStr::from_ptr(name).to_string_lossy().into_owned())
+        }
+    }
+
     pub fn state(self: &Rc<Self>) -> Result<XkbState, XkbCommonError> {
         let res = unsafe { xkb_state_new(self.keymap) };
         if res.is_null() {
@@ -204,6 +223,8 @@ impl XkbKeymap {
                 mods_effective: 0,
                 group: 0,
             },
+            group_offset: 0,
+            toggled_locked_mods: 0,
         })
     }
 }
@@ -247,6 +268,13 @@ pub struct XkbState {
     map: Rc<XkbKeymap>,
     state: *mut xkb_state,
     mods: ModifierState,
+    /// The offset of the locked group relative to the group of the key-driven state.
+    ///
+    /// The state is only ever updated with `xkb_state_update_key`, therefore groups set
+    /// by the compositor are applied on top of it.
+    group_offset: u32,
+    /// Locked modifiers whose state is inverted relative to the key-driven state.
+    toggled_locked_mods: u32,
 }
 
 impl XkbState {
@@ -258,22 +286,58 @@ impl XkbState {
     pub fn update(&mut self, key: u32, direction: XkbKeyDirection) -> Option<ModifierState> {
         unsafe {
             let changes = xkb_state_update_key(self.state, key + 8, direction.raw() as _);
-            if changes != 0 {
-                self.mods.mods_depressed =
-                    xkb_state_serialize_mods(self.state, XKB_STATE_MODS_DEPRESSED.raw() as _);
-                self.mods.mods_latched =
-                    xkb_state_serialize_mods(self.state, XKB_STATE_MODS_LATCHED.raw() as _);
-                self.mods.mods_locked =
-                    xkb_state_serialize_mods(self.state, XKB_STATE_MODS_LOCKED.raw() as _);
-                self.mods.mods_effective =
-                    self.mods.mods_depressed | self.mods.mods_latched | self.mods.mods_locked;
-                self.mods.group =
-                    xkb_state_serialize_layout(self.state, XKB_STATE_LAYOUT_EFFECTIVE.raw() as _);
-                Some(self.mods)
-            } else {
-                None
-            }
+            self.handle_changes(changes)
+        }
+    }
+
+    /// Locks the keyboard group. Groups are wrapped around the number of groups in the
+    /// keymap.
+    pub fn set_group(&mut self, group: u32) -> Option<ModifierState> {
+        let num_groups = self.map.num_groups().max(1);
+        let group = group % num_groups;
+        if group == self.mods.group {
+            return None;
+        }
+        let base = unsafe { self.key_group() };
+        self.group_offset = (group + num_groups - base) % num_groups;
+        self.mods.group = group;
+        Some(self.mods)
+    }
+
+    /// Takes over the locked modifiers and the locked group of another state, e.g. the
+    /// state of a keyboard with a different keymap.
+    pub fn apply_locked(&mut self, mods: &ModifierState) {
+        unsafe {
+            let locked = xkb_state_serialize_mods(self.state, XKB_STATE_MODS_LOCKED.raw() as _);
+            self.toggled_locked_mods = locked ^ mods.mods_locked;
+            self.update_mods();
+        }
+        self.set_group(mods.group);
+    }
+
+    unsafe fn key_group(&self) -> u32 {
+        xkb_state_serialize_layout(self.state, XKB_STATE_LAYOUT_EFFECTIVE.raw() as _)
+    }
+
+    unsafe fn handle_changes(&mut self, changes: xkb_state_component) -> Option<ModifierState> {
+        if changes == 0 {
+            return None;
         }
+        self.update_mods();
+        Some(self.mods)
+    }
+
+    unsafe fn update_mods(&mut self) {
+        self.mods.mods_depressed =
+            xkb_state_serialize_mods(self.state, XKB_STATE_MODS_DEPRESSED.raw() as _);
+        self.mods.mods_latched =
+            xkb_state_serialize_mods(self.state, XKB_STATE_MODS_LATCHED.raw() as _);
+        self.mods.mods_locked =
+            xkb_state_serialize_mods(self.state, XKB_STATE_MODS_LOCKED.raw() as _)
+                ^ self.toggled_locked_mods;
+        self.mods.mods_effective =
+            self.mods.mods_depressed | self.mods.mods_latched | self.mods.mods_locked;
+        self.mods.group = (self.key_group() + self.group_offset) % self.map.num_groups().max(1);
     }
 
     pub fn unmodified_keysyms(&self, key: u32) -> &[xkb_keysym_t] {
@@ -251,6 +251,8 @@ pub enum XWaylandEvent {
     Activate(Rc<XwindowData>),
     ActivateRoot,
     Close(Rc<XwindowData>),
+    SetMinimized(Rc<XwindowData>, bool),
+    SetMaximized(Rc<XwindowData>, bool),
     #[allow(dead_code)]
     SeatChanged,
 
@@ -4,6 +4,7 @@ use {
     crate::{
         async_engine::SpawnedFuture,
         client::Client,
+        cursor::KnownCursor,
         ifs::{
             ipc::{
                 add_data_source_mime_type, destroy_data_device, destroy_data_offer,
@@ -14,7 +15,7 @@ use {
                 },
                 IpcVtable,
             },
-            wl_seat::{SeatId, WlSeatGlobal},
+            wl_seat::{SeatId, WlSeatGlobal, RESIZE_BOTTOM, RESIZE_LEFT, RESIZE_RIGHT, RESIZE_TOP},
             wl_surface::{
                 x_surface::xwindow::{XInputModel, Xwindow, XwindowData},
                 WlSurface,
@@ -42,6 +43,10 @@ use {
         },
         xcon::{
             consts::{
+                _NET_WM_MOVERESIZE_SIZE_BOTTOM, _NET_WM_MOVERESIZE_SIZE_BOTTOMLEFT,
+                _NET_WM_MOVERESIZE_SIZE_BOTTOMRIGHT, _NET_WM_MOVERESIZE_SIZE_LEFT,
+                _NET_WM_MOVERESIZE_SIZE_RIGHT, _NET_WM_MOVERESIZE_SIZE_TOP,
+                _NET_WM_MOVERESIZE_SIZE_TOPLEFT, _NET_WM_MOVERESIZE_SIZE_TOPRIGHT,
                 ATOM_ATOM, ATOM_NONE, ATOM_STRING, ATOM_WINDOW, ATOM_WM_CLASS, ATOM_WM_NAME,
                 ATOM_WM_SIZE_HINTS, ATOM_WM_TRANSIENT_FOR, COMPOSITE_REDIRECT_MANUAL,
                 CONFIG_WINDOW_HEIGHT, CONFIG_WINDOW_WIDTH, CONFIG_WINDOW_X, CONFIG_WINDOW_Y,
@@ -425,7 +430,7 @@ impl Wm {
                 Some(g) => g,
                 _ => break 'set_root_cursor,
             };
-            let first = match cursors.default.xcursor.first() {
+            let first = match cursors.get(KnownCursor::Default).xcursor.first() {
                 Some(f) => f,
                 _ => break 'set_root_cursor,
             };
@@ -610,6 +615,14 @@ impl Wm {
             }
             XWaylandEvent::ActivateRoot => self.activate_window(None, Initiator::Wayland).await,
             XWaylandEvent::Close(window) => self.close_window(&window).await,
+            XWaylandEvent::SetMinimized(window, minimized) => {
+                if window.info.minimized.get() != minimized {
+                    self.set_minimized(&window, minimized).await
+                }
+            }
+            XWaylandEvent::SetMaximized(window, maximized) => {
+                self.set_maximized(&window, maximized).await
+            }
             XWaylandEvent::SeatChanged => self.seats_changed(),
             XWaylandEvent::PrimarySelectionCancelSource(src) => {
                 self.dd_cancel_source(&self.shared.clone().primary_selection, &src)
@@ -885,7 +898,6 @@ impl Wm {
         self.set_net_wm_state(data).await;
     }
 
-    #[allow(dead_code)]
     async fn set_maximized(&self, data: &Rc<XwindowData>, maximized: bool) {
         data.info.maximized_vert.set(maximized);
         data.info.maximized_horz.set(maximized);
@@ -2206,11 +2218,29 @@ impl Wm {
         &mut self,
         event: &ClientMessage<'_>,
     ) -> Result<(), XWaylandError> {
-        let _data = match self.windows.get(&event.window) {
+        let data = match self.windows.get(&event.window) {
             Some(d) => d,
             _ => return Ok(()),
         };
-        let _detail = event.data[2];
+        let window = match data.window.get() {
+            Some(w) => w,
+            _ => return Ok(()),
+        };
+        let edges = match event.data[2] {
+            _NET_WM_MOVERESIZE_SIZE_TOPLEFT => RESIZE_TOP | RESIZE_LEFT,
+            _NET_WM_MOVERESIZE_SIZE_TOP => RESIZE_TOP,
+            _NET_WM_MOVERESIZE_SIZE_TOPRIGHT => RESIZE_TOP | RESIZE_RIGHT,
+            _NET_WM_MOVERESIZE_SIZE_RIGHT => RESIZE_RIGHT,
+            _NET_WM_MOVERESIZE_SIZE_BOTTOMRIGHT => RESIZE_BOTTOM | RESIZE_RIGHT,
+            _NET_WM_MOVERESIZE_SIZE_BOTTOM => RESIZE_BOTTOM,
+            _NET_WM_MOVERESIZE_SIZE_BOTTOMLEFT => RESIZE_BOTTOM | RESIZE_LEFT,
+            _NET_WM_MOVERESIZE_SIZE_LEFT => RESIZE_LEFT,
+            _ => return Ok(()),
+        };
+        let seats = self.state.globals.seats.lock();
+        for seat in seats.values() {
+            seat.start_resize(window.clone(), edges);
+        }
         Ok(())
     }
 
@@ -2223,7 +2253,10 @@ impl Wm {
             _ => return Ok(()),
         };
         let minimize = match event.data[0] {
-            ICCCM_WM_STATE_NORMAL => false,
+            ICCCM_WM_STATE_NORMAL => {
+                self.handle_restore_requested(data);
+                false
+            }
             ICCCM_WM_STATE_ICONIC => self.handle_minimize_requested(data).await,
             _ => return Ok(()),
         };
@@ -2234,7 +2267,7 @@ impl Wm {
 
     async fn handle_minimize_requested(&self, data: &Rc<XwindowData>) -> bool {
         if let Some(w) = data.window.get() {
-            if w.toplevel_data.active_surfaces.get() > 0 {
+            if !w.toplevel_data.minimize(w.clone()) {
                 self.set_wm_state(data, ICCCM_WM_STATE_NORMAL).await;
                 return false;
             }
@@ -2243,6 +2276,12 @@ impl Wm {
         true
     }
 
+    fn handle_restore_requested(&self, data: &Rc<XwindowData>) {
+        if let Some(w) = data.window.get() {
+            w.toplevel_data.restore(w.clone());
+        }
+    }
+
     async fn handle_net_startup_info(
         &mut self,
         event: &ClientMessage<'_>,
@@ -2342,6 +2381,8 @@ impl Wm {
         if minimized != data.info.minimized.get() {
             if minimized {
                 minimized = self.handle_minimize_requested(data).await;
+            } else {
+                self.handle_restore_requested(data);
             }
         }
         if fullscreen != data.info.fullscreen.get() {
@@ -2352,6 +2393,10 @@ impl Wm {
         data.info.fullscreen.set(fullscreen);
         data.info.maximized_horz.set(maximized_horz);
         data.info.maximized_vert.set(maximized_vert);
+        if let Some(w) = data.window.get() {
+            w.toplevel_data
+                .set_maximized(w.deref(), maximized_horz && maximized_vert);
+        }
         data.info.minimized.set(minimized);
         data.info.modal.set(modal);
         self.update_wants_floating(data);